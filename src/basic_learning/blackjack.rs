@@ -0,0 +1,838 @@
+// 二十一点
+/*
+    用 cards 模块中的 Card (花色是 enum_test::PokerSuit) 实现二十一点:
+        - Shoe          多副牌混在一起的牌靴，发到只剩一部分时在下一局开始前重新洗牌
+        - Rules         几副牌、庄家软 17 点是否要牌 (H17 / S17)、黑杰克赔率、分牌后能否加倍、最多分几次牌
+        - Game          发牌、要牌 / 停牌 / 加倍 / 分牌、庄家补牌、结算，并记录余额
+        - Strategy      玩家的决策，Game 只通过这个特征和玩家交互:
+            BasicStrategy   按基本策略表决策的机器人
+            DealerMimic     学庄家，不到 17 点就要牌，用来对比
+            Human           从标准输入读取操作的真人玩家
+        - simulate      用固定的种子玩很多局，统计每单位下注的期望收益
+
+    点数: 2~10 按牌面，J、Q、K 算 10，A 算 1 或 11
+    庄家明牌是 A 或 10 点时先看底牌，庄家是黑杰克时玩家直接输掉下注 (除非玩家也是黑杰克)
+    分出来的两手 A 各只发一张牌，分牌后凑成的 21 点不算黑杰克
+    不支持保险和投降
+
+    cargo run -- blackjack                       和基本策略机器人一样的规则，自己玩
+    cargo run -- blackjack simulate --rounds 100000 --strategy dealer
+ */
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::basic_learning::cards::{format_hand, Card, Deck, Rank};
+use crate::basic_learning::style::{self, ColorMode, Stream};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    pub decks: usize,
+    // true 为 H17: 庄家软 17 点 (A + 6) 继续要牌，对玩家不利
+    pub dealer_hits_soft_17: bool,
+    // 黑杰克的赔率，通常是 3:2
+    pub blackjack_payout: f64,
+    pub double_after_split: bool,
+    // 最多分几次牌，3 表示最多 4 手
+    pub max_splits: usize,
+    // 发到总牌数的这个比例后重新洗牌
+    pub penetration: f64,
+}
+
+impl Default for Rules {
+    // 常见的 6 副牌规则
+    fn default() -> Self {
+        Rules {
+            decks: 6,
+            dealer_hits_soft_17: true,
+            blackjack_payout: 1.5,
+            double_after_split: true,
+            max_splits: 3,
+            penetration: 0.75,
+        }
+    }
+}
+
+pub fn card_value(card: Card) -> u8 {
+    match card.rank.value() {
+        14 => 11,
+        v if v >= 10 => 10,
+        v => v,
+    }
+}
+
+// 手牌的点数，以及是否是软点数 (有一张 A 当作 11)
+pub fn hand_value(cards: &[Card]) -> (u8, bool) {
+    let total: u32 = cards
+        .iter()
+        .map(|&c| if c.rank == Rank::ACE { 1 } else { card_value(c) as u32 })
+        .sum();
+    let has_ace = cards.iter().any(|c| c.rank == Rank::ACE);
+    // 最多只有一张 A 能当 11，两张就超过 21 了
+    if has_ace && total + 10 <= 21 {
+        (total as u8 + 10, true)
+    } else {
+        (total.min(u8::MAX as u32) as u8, false)
+    }
+}
+
+pub fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && hand_value(cards).0 == 21
+}
+
+// 牌靴
+pub struct Shoe {
+    deck: Deck<Card>,
+    decks: usize,
+    // 剩下的牌少于 cut 张时需要重新洗牌
+    cut: usize,
+    rng: StdRng,
+}
+
+impl Shoe {
+    pub fn new(decks: usize, penetration: f64, seed: u64) -> Shoe {
+        let total = decks.max(1) * 52;
+        let cut = (total as f64 * (1.0 - penetration.clamp(0.0, 1.0))).round() as usize;
+        let mut shoe = Shoe {
+            deck: Deck::from_cards(Vec::new()),
+            decks: decks.max(1),
+            cut,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        shoe.shuffle();
+        shoe
+    }
+
+    // 收回所有的牌重新洗
+    pub fn shuffle(&mut self) {
+        let cards: Vec<Card> = (0..self.decks).flat_map(|_| Deck::new().cards().to_vec()).collect();
+        self.deck = Deck::from_cards(cards);
+        self.deck.shuffle(&mut self.rng);
+    }
+
+    pub fn needs_shuffle(&self) -> bool {
+        self.deck.len() < self.cut
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.deck.len()
+    }
+
+    // 一局中途发完了也要能继续，这时直接重新洗牌
+    pub fn draw(&mut self) -> Card {
+        if self.deck.is_empty() {
+            self.shuffle();
+        }
+        self.deck.draw().unwrap()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+}
+
+// 玩家做决定时能看到的信息
+#[derive(Debug)]
+pub struct Decision<'a> {
+    pub hand: &'a [Card],
+    pub dealer_up: Card,
+    pub can_double: bool,
+    pub can_split: bool,
+    // 分牌后有多手牌时，当前是第几手
+    pub hand_index: usize,
+    pub hand_count: usize,
+    pub bankroll: f64,
+}
+
+pub trait Strategy {
+    // 这一局下注多少，返回 None 表示不玩了
+    fn bet(&mut self, bankroll: f64) -> Option<f64>;
+    // 不允许的操作按要牌处理，比如三张牌时选择加倍
+    fn decide(&mut self, decision: &Decision) -> Action;
+    // 一局结束后得知结果，默认什么都不做
+    fn observe(&mut self, _result: &RoundResult) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Blackjack,
+    Win,
+    Push,
+    Lose,
+    Bust,
+}
+
+impl Outcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            Outcome::Blackjack => "黑杰克",
+            Outcome::Win => "赢",
+            Outcome::Push => "平",
+            Outcome::Lose => "输",
+            Outcome::Bust => "爆牌",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandResult {
+    pub cards: Vec<Card>,
+    // 加倍后是两倍的下注
+    pub bet: f64,
+    pub outcome: Outcome,
+    // 输赢的筹码，输为负数
+    pub net: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundResult {
+    pub hands: Vec<HandResult>,
+    pub dealer: Vec<Card>,
+    // 开局时的下注
+    pub initial_bet: f64,
+    pub net: f64,
+    // 结算后的余额
+    pub bankroll: f64,
+}
+
+impl fmt::Display for RoundResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (dealer_total, _) = hand_value(&self.dealer);
+        writeln!(f, "庄家: {} ({})", format_hand(&self.dealer, false), dealer_total)?;
+        for hand in &self.hands {
+            writeln!(
+                f,
+                "玩家: {} ({}) 下注 {} {} {:+}",
+                format_hand(&hand.cards, false),
+                hand_value(&hand.cards).0,
+                hand.bet,
+                hand.outcome.name(),
+                hand.net
+            )?;
+        }
+        write!(f, "本局 {:+}，余额 {}", self.net, self.bankroll)
+    }
+}
+
+struct PlayerHand {
+    cards: Vec<Card>,
+    bet: f64,
+    from_split: bool,
+    split_aces: bool,
+    doubled: bool,
+}
+
+pub struct Game {
+    pub rules: Rules,
+    shoe: Shoe,
+    bankroll: f64,
+}
+
+impl Game {
+    pub fn new(rules: Rules, bankroll: f64, seed: u64) -> Game {
+        let shoe = Shoe::new(rules.decks, rules.penetration, seed);
+        Game { rules, shoe, bankroll }
+    }
+
+    pub fn bankroll(&self) -> f64 {
+        self.bankroll
+    }
+
+    pub fn shoe(&self) -> &Shoe {
+        &self.shoe
+    }
+
+    // 玩一局，玩家不下注或者余额不够时返回 None
+    pub fn play_round(&mut self, strategy: &mut dyn Strategy) -> Option<RoundResult> {
+        let bet = strategy.bet(self.bankroll)?;
+        // 同时排除了 NaN
+        if !(bet > 0.0 && bet <= self.bankroll) {
+            return None;
+        }
+        if self.shoe.needs_shuffle() {
+            self.shoe.shuffle();
+        }
+
+        // 和真实发牌的顺序一样，玩家和庄家轮流各拿两张，庄家的第一张是明牌
+        let mut player = Vec::new();
+        let mut dealer = Vec::new();
+        for _ in 0..2 {
+            player.push(self.shoe.draw());
+            dealer.push(self.shoe.draw());
+        }
+        let dealer_up = dealer[0];
+
+        // 有一方是黑杰克时直接结算
+        let (player_bj, dealer_bj) = (is_blackjack(&player), is_blackjack(&dealer));
+        if player_bj || dealer_bj {
+            let (outcome, net) = match (player_bj, dealer_bj) {
+                (true, true) => (Outcome::Push, 0.0),
+                (true, false) => (Outcome::Blackjack, bet * self.rules.blackjack_payout),
+                _ => (Outcome::Lose, -bet),
+            };
+            let hands = vec![HandResult { cards: player, bet, outcome, net }];
+            return Some(self.finish(strategy, hands, dealer, bet));
+        }
+
+        let mut hands = vec![PlayerHand { cards: player, bet, from_split: false, split_aces: false, doubled: false }];
+        // 已经押上的筹码，加倍和分牌时不能超过余额
+        let mut committed = bet;
+        let mut i = 0;
+        while i < hands.len() {
+            loop {
+                let hand = &hands[i];
+                // 21 点自动停牌，加倍后只能拿一张牌，分出来的 A 也只发一张
+                if hand_value(&hand.cards).0 >= 21 || hand.doubled || hand.split_aces {
+                    break;
+                }
+                let pair = hand.cards.len() == 2;
+                let funds = committed + hand.bet <= self.bankroll;
+                let can_double = pair && funds && (!hand.from_split || self.rules.double_after_split);
+                let can_split = pair
+                    && funds
+                    && card_value(hand.cards[0]) == card_value(hand.cards[1])
+                    && hands.len() <= self.rules.max_splits;
+                let action = strategy.decide(&Decision {
+                    hand: &hand.cards,
+                    dealer_up,
+                    can_double,
+                    can_split,
+                    hand_index: i,
+                    hand_count: hands.len(),
+                    bankroll: self.bankroll - committed,
+                });
+                match action {
+                    Action::Stand => break,
+                    Action::Double if can_double => {
+                        committed += hands[i].bet;
+                        hands[i].bet *= 2.0;
+                        hands[i].doubled = true;
+                        hands[i].cards.push(self.shoe.draw());
+                    }
+                    Action::Split if can_split => {
+                        committed += hands[i].bet;
+                        let moved = hands[i].cards.pop().unwrap();
+                        let aces = moved.rank == Rank::ACE;
+                        let hand = &mut hands[i];
+                        hand.from_split = true;
+                        hand.split_aces = aces;
+                        hand.cards.push(self.shoe.draw());
+                        let new_hand = PlayerHand {
+                            cards: vec![moved, self.shoe.draw()],
+                            bet: hand.bet,
+                            from_split: true,
+                            split_aces: aces,
+                            doubled: false,
+                        };
+                        hands.insert(i + 1, new_hand);
+                    }
+                    _ => hands[i].cards.push(self.shoe.draw()),
+                }
+            }
+            i += 1;
+        }
+
+        // 玩家全部爆牌时庄家不用补牌
+        if hands.iter().any(|h| hand_value(&h.cards).0 <= 21) {
+            loop {
+                let (total, soft) = hand_value(&dealer);
+                if total < 17 || (total == 17 && soft && self.rules.dealer_hits_soft_17) {
+                    dealer.push(self.shoe.draw());
+                } else {
+                    break;
+                }
+            }
+        }
+
+        let (dealer_total, _) = hand_value(&dealer);
+        let results = hands
+            .into_iter()
+            .map(|hand| {
+                let (total, _) = hand_value(&hand.cards);
+                let (outcome, net) = if total > 21 {
+                    (Outcome::Bust, -hand.bet)
+                } else if dealer_total > 21 || total > dealer_total {
+                    (Outcome::Win, hand.bet)
+                } else if total == dealer_total {
+                    (Outcome::Push, 0.0)
+                } else {
+                    (Outcome::Lose, -hand.bet)
+                };
+                HandResult { cards: hand.cards, bet: hand.bet, outcome, net }
+            })
+            .collect();
+        Some(self.finish(strategy, results, dealer, bet))
+    }
+
+    fn finish(&mut self, strategy: &mut dyn Strategy, hands: Vec<HandResult>, dealer: Vec<Card>, initial_bet: f64) -> RoundResult {
+        let net: f64 = hands.iter().map(|h| h.net).sum();
+        self.bankroll += net;
+        let result = RoundResult { hands, dealer, initial_bet, net, bankroll: self.bankroll };
+        strategy.observe(&result);
+        result
+    }
+}
+
+// 基本策略: 只根据自己的手牌和庄家的明牌决策，是不记牌时期望收益最高的玩法
+// 适用于多副牌、庄家看底牌、不能投降的规则
+pub struct BasicStrategy {
+    pub bet: f64,
+    pub dealer_hits_soft_17: bool,
+    pub double_after_split: bool,
+}
+
+impl BasicStrategy {
+    pub fn new(rules: &Rules, bet: f64) -> BasicStrategy {
+        BasicStrategy {
+            bet,
+            dealer_hits_soft_17: rules.dealer_hits_soft_17,
+            double_after_split: rules.double_after_split,
+        }
+    }
+
+    // 对子是否分牌，pair 和 up 都是 card_value，A 为 11
+    fn should_split(&self, pair: u8, up: u8) -> bool {
+        let das = self.double_after_split;
+        match pair {
+            11 | 8 => true,
+            10 | 5 => false,
+            9 => matches!(up, 2..=6 | 8 | 9),
+            7 => (2..=7).contains(&up),
+            // 分牌后不能加倍时，小对子分牌的价值低一些
+            6 if das => (2..=6).contains(&up),
+            6 => (3..=6).contains(&up),
+            4 => das && (5..=6).contains(&up),
+            _ if das => (2..=7).contains(&up),
+            _ => (4..=7).contains(&up),
+        }
+    }
+
+    // 按表格查出的操作，加倍不可用时有的退回要牌，有的退回停牌
+    fn play(&self, total: u8, soft: bool, up: u8) -> (Action, Action) {
+        use Action::{Double, Hit, Stand};
+        let h17 = self.dealer_hits_soft_17;
+        let double_else = |cond: bool, fallback: Action| if cond { (Double, fallback) } else { (fallback, fallback) };
+        if soft {
+            match total {
+                20.. => (Stand, Stand),
+                19 => double_else(h17 && up == 6, Stand),
+                18 => match up {
+                    2 => double_else(h17, Stand),
+                    3..=6 => (Double, Stand),
+                    7 | 8 => (Stand, Stand),
+                    _ => (Hit, Hit),
+                },
+                17 => double_else((3..=6).contains(&up), Hit),
+                15 | 16 => double_else((4..=6).contains(&up), Hit),
+                13 | 14 => double_else((5..=6).contains(&up), Hit),
+                _ => (Hit, Hit),
+            }
+        } else {
+            match total {
+                17.. => (Stand, Stand),
+                13..=16 if up <= 6 => (Stand, Stand),
+                12 if (4..=6).contains(&up) => (Stand, Stand),
+                11 => double_else(up != 11 || h17, Hit),
+                10 => double_else(up <= 9, Hit),
+                9 => double_else((3..=6).contains(&up), Hit),
+                _ => (Hit, Hit),
+            }
+        }
+    }
+}
+
+impl Strategy for BasicStrategy {
+    fn bet(&mut self, bankroll: f64) -> Option<f64> {
+        (bankroll >= self.bet).then_some(self.bet)
+    }
+
+    fn decide(&mut self, d: &Decision) -> Action {
+        let up = card_value(d.dealer_up);
+        if d.can_split && self.should_split(card_value(d.hand[0]), up) {
+            return Action::Split;
+        }
+        let (total, soft) = hand_value(d.hand);
+        match self.play(total, soft, up) {
+            (Action::Double, fallback) if !d.can_double => fallback,
+            (action, _) => action,
+        }
+    }
+}
+
+// 学庄家: 不到 17 点就要牌，从不加倍和分牌
+pub struct DealerMimic {
+    pub bet: f64,
+}
+
+impl Strategy for DealerMimic {
+    fn bet(&mut self, bankroll: f64) -> Option<f64> {
+        (bankroll >= self.bet).then_some(self.bet)
+    }
+
+    fn decide(&mut self, d: &Decision) -> Action {
+        if hand_value(d.hand).0 < 17 {
+            Action::Hit
+        } else {
+            Action::Stand
+        }
+    }
+}
+
+// 真人玩家，输入输出可以替换，演示时用字符串代替键盘输入
+pub struct Human<R, W> {
+    input: R,
+    output: W,
+    pub default_bet: f64,
+    mode: ColorMode,
+}
+
+impl Human<io::StdinLock<'static>, io::Stdout> {
+    pub fn stdin(default_bet: f64) -> Self {
+        Human { input: io::stdin().lock(), output: io::stdout(), default_bet, mode: ColorMode::of(Stream::Stdout) }
+    }
+}
+
+impl<R: BufRead, W: Write> Human<R, W> {
+    pub fn new(input: R, output: W, default_bet: f64) -> Self {
+        Human { input, output, default_bet, mode: ColorMode::Never }
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    // 显示提示并读一行，读到末尾时返回 None
+    fn prompt(&mut self, text: &str) -> Option<String> {
+        write!(self.output, "{}", text).ok()?;
+        self.output.flush().ok()?;
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim().to_lowercase()),
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Strategy for Human<R, W> {
+    fn bet(&mut self, bankroll: f64) -> Option<f64> {
+        loop {
+            let line = self.prompt(&format!("余额 {}，下注多少? (回车 = {}，q = 离开) ", bankroll, self.default_bet))?;
+            let bet = match line.as_str() {
+                "q" => return None,
+                "" => self.default_bet,
+                s => match s.parse::<f64>() {
+                    Ok(bet) => bet,
+                    Err(_) => {
+                        writeln!(self.output, "请输入数字").ok();
+                        continue;
+                    }
+                },
+            };
+            if bet > 0.0 && bet <= bankroll {
+                return Some(bet);
+            }
+            writeln!(self.output, "下注必须大于 0 且不超过余额").ok();
+        }
+    }
+
+    fn decide(&mut self, d: &Decision) -> Action {
+        let (total, soft) = hand_value(d.hand);
+        let which = if d.hand_count > 1 { format!(" (第 {}/{} 手)", d.hand_index + 1, d.hand_count) } else { String::new() };
+        let mut options = vec!["h 要牌", "s 停牌"];
+        if d.can_double {
+            options.push("d 加倍");
+        }
+        if d.can_split {
+            options.push("p 分牌");
+        }
+        let text = format!(
+            "庄家明牌 {}，你的牌{} {} ({}{})  [{}] ",
+            d.dealer_up,
+            which,
+            format_hand(d.hand, false),
+            if soft { "软 " } else { "" },
+            total,
+            options.join(", ")
+        );
+        loop {
+            // 输入结束时停牌
+            let Some(line) = self.prompt(&text) else { return Action::Stand };
+            match line.as_str() {
+                "h" => return Action::Hit,
+                "s" => return Action::Stand,
+                "d" if d.can_double => return Action::Double,
+                "p" if d.can_split => return Action::Split,
+                _ => {}
+            }
+        }
+    }
+
+    fn observe(&mut self, result: &RoundResult) {
+        let style = match result.net {
+            n if n > 0.0 => style::EQUAL,
+            n if n < 0.0 => style::PANIC,
+            _ => style::DBG,
+        };
+        writeln!(self.output, "{}\n", style.render(&result.to_string(), self.mode)).ok();
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+    pub rounds: u64,
+    // 开局下注的总和，不包括加倍和分牌追加的部分
+    pub wagered: f64,
+    pub net: f64,
+    // 每局按开局下注归一化的收益的平方和，用于计算标准误差
+    sum_squares: f64,
+    pub blackjacks: u64,
+    pub wins: u64,
+    pub pushes: u64,
+    pub losses: u64,
+}
+
+impl SimulationReport {
+    // 每下注一单位平均赢回多少，负数表示庄家优势
+    pub fn expected_return(&self) -> f64 {
+        self.net / self.wagered
+    }
+
+    pub fn std_error(&self) -> f64 {
+        let n = self.rounds as f64;
+        let mean = self.expected_return();
+        ((self.sum_squares / n - mean * mean) / n).sqrt()
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pct = |n: u64| n as f64 / self.rounds as f64 * 100.0;
+        writeln!(f, "局数     {}", self.rounds)?;
+        writeln!(f, "黑杰克   {:.2}%", pct(self.blackjacks))?;
+        writeln!(f, "赢 / 平 / 输   {:.2}% / {:.2}% / {:.2}%", pct(self.wins), pct(self.pushes), pct(self.losses))?;
+        write!(
+            f,
+            "期望收益 {:+.3}% ± {:.3}% (每单位下注，95% 置信区间)",
+            self.expected_return() * 100.0,
+            self.std_error() * 196.0
+        )
+    }
+}
+
+// 用同一个种子模拟 rounds 局，余额无限，所以每局都能加倍和分牌
+pub fn simulate(rules: Rules, strategy: &mut dyn Strategy, rounds: u64, seed: u64) -> SimulationReport {
+    let mut game = Game::new(rules, f64::INFINITY, seed);
+    let mut report = SimulationReport::default();
+    for _ in 0..rounds {
+        let Some(result) = game.play_round(strategy) else { break };
+        let x = result.net / result.initial_bet;
+        report.rounds += 1;
+        report.wagered += result.initial_bet;
+        report.net += result.net;
+        report.sum_squares += x * x;
+        match result.net {
+            _ if result.hands[0].outcome == Outcome::Blackjack => report.blackjacks += 1,
+            n if n > 0.0 => report.wins += 1,
+            n if n < 0.0 => report.losses += 1,
+            _ => report.pushes += 1,
+        }
+    }
+    report
+}
+
+pub fn cli(args: &[String]) {
+    if let Err(e) = run_cli(args) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+fn run_cli(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let usage = "用法: blackjack [simulate] [--decks N] [--s17] [--seed N] [--bankroll N] [--rounds N] [--strategy basic|dealer]";
+    let mut rules = Rules::default();
+    let mut simulate_mode = false;
+    let mut seed: u64 = rand::random();
+    let mut bankroll = 100.0;
+    let mut rounds = 100_000;
+    let mut strategy_name = "basic".to_string();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(usage);
+        match arg.as_str() {
+            "simulate" => simulate_mode = true,
+            "--s17" => rules.dealer_hits_soft_17 = false,
+            "--decks" => rules.decks = value()?.parse()?,
+            "--seed" => seed = value()?.parse()?,
+            "--bankroll" => bankroll = value()?.parse()?,
+            "--rounds" => rounds = value()?.parse()?,
+            "--strategy" => strategy_name = value()?.clone(),
+            _ => return Err(usage.into()),
+        }
+    }
+
+    if simulate_mode {
+        let mut strategy: Box<dyn Strategy> = match strategy_name.as_str() {
+            "basic" => Box::new(BasicStrategy::new(&rules, 1.0)),
+            "dealer" => Box::new(DealerMimic { bet: 1.0 }),
+            _ => return Err(usage.into()),
+        };
+        println!("种子 {}，{} 副牌，{}", seed, rules.decks, if rules.dealer_hits_soft_17 { "H17" } else { "S17" });
+        println!("{}", simulate(rules, strategy.as_mut(), rounds, seed));
+        return Ok(());
+    }
+
+    let mut game = Game::new(rules, bankroll, seed);
+    let mut human = Human::stdin(10.0f64.min(bankroll));
+    while game.play_round(&mut human).is_some() {}
+    println!("离开时余额 {}，{:+}", game.bankroll(), game.bankroll() - bankroll);
+    Ok(())
+}
+
+pub fn run() {
+    use crate::basic_learning::cards::parse_hand;
+
+    // 点数: A 可以当 1 也可以当 11，第二个值表示是不是软点数
+    for s in ["AS KD", "AS 6D", "AS 6D 10C", "AS AD 9C", "KS QD 5C"] {
+        println!("{:<10} {:?}", s, hand_value(&parse_hand(s).unwrap()));
+    }
+
+    // 真人玩家: 用字符串代替键盘输入，回车下注 10，停牌，然后 q 离开
+    let mut human = Human::new("\ns\nq\n".as_bytes(), Vec::new(), 10.0);
+    let mut game = Game::new(Rules::default(), 100.0, 3);
+    while game.play_round(&mut human).is_some() {}
+    println!("{}", String::from_utf8(human.into_output()).unwrap());
+
+    // 基本策略和学庄家各玩一万局，局数少所以置信区间比较宽
+    let rounds = 10_000;
+    let basic = simulate(Rules::default(), &mut BasicStrategy::new(&Rules::default(), 1.0), rounds, 2024);
+    let mimic = simulate(Rules::default(), &mut DealerMimic { bet: 1.0 }, rounds, 2024);
+    println!("基本策略:\n{}\n", basic);
+    println!("学庄家:\n{}", mimic);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_learning::cards::parse_hand;
+
+    fn value(s: &str) -> (u8, bool) {
+        hand_value(&parse_hand(s).unwrap())
+    }
+
+    #[test]
+    fn hand_values() {
+        assert_eq!(value("AS KD"), (21, true));
+        assert_eq!(value("AS 6D"), (17, true));
+        assert_eq!(value("AS 6D 10C"), (17, false));
+        assert_eq!(value("AS AD"), (12, true));
+        assert_eq!(value("AS AD 9C"), (21, true));
+        assert_eq!(value("KS QD 5C"), (25, false));
+        assert!(is_blackjack(&parse_hand("AH JC").unwrap()));
+        assert!(!is_blackjack(&parse_hand("7H 7C 7D").unwrap()));
+    }
+
+    // 牌靴发到 75% 后需要重新洗牌
+    #[test]
+    fn shoe_penetration() {
+        let mut shoe = Shoe::new(2, 0.75, 1);
+        assert_eq!(shoe.remaining(), 104);
+        for _ in 0..78 {
+            shoe.draw();
+        }
+        assert!(!shoe.needs_shuffle());
+        shoe.draw();
+        assert!(shoe.needs_shuffle());
+    }
+
+    // 基本策略的几个典型决策
+    #[test]
+    fn basic_strategy_table() {
+        let rules = Rules::default();
+        let mut bot = BasicStrategy::new(&rules, 1.0);
+        let mut decide = |hand: &str, up: &str, can_double: bool, can_split: bool| {
+            let hand = parse_hand(hand).unwrap();
+            let dealer_up: Card = up.parse().unwrap();
+            bot.decide(&Decision { hand: &hand, dealer_up, can_double, can_split, hand_index: 0, hand_count: 1, bankroll: 100.0 })
+        };
+        assert_eq!(decide("8S 8D", "10H", true, true), Action::Split);
+        assert_eq!(decide("8S 8D", "10H", true, false), Action::Hit);
+        assert_eq!(decide("10S 10D", "6H", true, true), Action::Stand);
+        assert_eq!(decide("5S 5D", "6H", true, true), Action::Double);
+        assert_eq!(decide("6S 5D", "AH", true, false), Action::Double);
+        assert_eq!(decide("10S 6D", "7H", true, false), Action::Hit);
+        assert_eq!(decide("10S 2D", "4H", true, false), Action::Stand);
+        assert_eq!(decide("AS 7D", "5H", true, false), Action::Double);
+        // 软 18 不能加倍时停牌，而不是要牌
+        assert_eq!(decide("AS 4D 3C", "5H", false, false), Action::Stand);
+        assert_eq!(decide("AS 7D", "9H", true, false), Action::Hit);
+    }
+
+    // S17 时 11 点对 A 只要牌
+    #[test]
+    fn basic_strategy_s17() {
+        let mut s17 = BasicStrategy::new(&Rules { dealer_hits_soft_17: false, ..Rules::default() }, 1.0);
+        let hand = parse_hand("6S 5D").unwrap();
+        let d = Decision { hand: &hand, dealer_up: "AH".parse().unwrap(), can_double: true, can_split: false, hand_index: 0, hand_count: 1, bankroll: 100.0 };
+        assert_eq!(s17.decide(&d), Action::Hit);
+    }
+
+    // 余额的变化等于每局输赢的总和，余额不够时不能再下注
+    #[test]
+    fn bankroll_tracks_every_round() {
+        let mut game = Game::new(Rules::default(), 20.0, 7);
+        let mut bot = BasicStrategy::new(&game.rules, 5.0);
+        let mut total = 0.0;
+        let mut played = 0;
+        while let Some(result) = game.play_round(&mut bot) {
+            total += result.net;
+            played += 1;
+            assert_eq!(result.bankroll, 20.0 + total, "第 {played} 局");
+            // 每一手的下注不会让押上的筹码超过开局前的余额
+            let staked: f64 = result.hands.iter().map(|h| h.bet).sum();
+            assert!(staked <= result.bankroll - result.net, "第 {played} 局");
+            if played == 1000 {
+                break;
+            }
+        }
+        assert!(game.bankroll() < 5.0 || played == 1000);
+    }
+
+    // 输入提前结束时按停牌和离开处理
+    #[test]
+    fn scripted_human() {
+        let mut human = Human::new("\ns\nq\n".as_bytes(), Vec::new(), 10.0);
+        let mut game = Game::new(Rules::default(), 100.0, 3);
+        let mut rounds = 0;
+        while game.play_round(&mut human).is_some() {
+            rounds += 1;
+        }
+        let output = String::from_utf8(human.into_output()).unwrap();
+        assert!(rounds >= 1);
+        assert!(output.contains("下注多少?"));
+        assert!(output.contains(&format!("余额 {}", game.bankroll())));
+    }
+
+    // 同一个种子的模拟结果完全相同
+    #[test]
+    fn simulation_is_deterministic() {
+        let a = simulate(Rules::default(), &mut BasicStrategy::new(&Rules::default(), 1.0), 2000, 42);
+        let b = simulate(Rules::default(), &mut BasicStrategy::new(&Rules::default(), 1.0), 2000, 42);
+        assert_eq!(a, b);
+    }
+
+    // 基本策略的庄家优势大约是 0.6%，学庄家大约是 5%
+    #[test]
+    fn expected_returns() {
+        let rounds = 200_000;
+        let basic = simulate(Rules::default(), &mut BasicStrategy::new(&Rules::default(), 1.0), rounds, 2024);
+        let mimic = simulate(Rules::default(), &mut DealerMimic { bet: 1.0 }, rounds, 2024);
+        assert!((-0.02..0.01).contains(&basic.expected_return()), "{basic}");
+        assert!((-0.08..-0.03).contains(&mimic.expected_return()), "{mimic}");
+    }
+}
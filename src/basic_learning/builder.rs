@@ -0,0 +1,199 @@
+// 构建者模式
+/*
+    字段较多、又有一部分字段有默认值时，直接写结构体字面量很啰嗦，结构体更新语法 ..base 也需要先有一个完整的实例
+    builder! 宏写在结构体定义的旁边，结构体本身保持原样，只生成一个构建者:
+        - 列出结构体的全部字段，字段后面写 = 默认值 的是可选字段，没写的是必填字段
+        - 每个字段生成一个同名的 setter，参数是 impl Into<T>，所以 String 字段可以直接传 &str
+        - build() 返回 Result，缺少必填字段时一次性列出所有缺少的字段
+        - 结构体上生成 builder() 关联函数
+
+        pub struct Button {
+            pub width: u32,
+            pub label: String,
+        }
+        builder!(pub Button => ButtonBuilder {
+            width: u32 = 50,
+            label: String,
+        });
+        let button = Button::builder().label("OK").build()?;
+
+    macro_rules! 没法拼接标识符，所以构建者的名字要写出来
+    漏写了字段时，build() 中的结构体字面量会编译报错，不会和结构体定义对不上
+    默认值只在没有调用 setter 时才求值
+    整数字面量经过 impl Into<T> 时推断不出类型，需要写后缀，比如 width(80u32)
+ */
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildError {
+    // 要构建的类型名
+    pub ty: &'static str,
+    // 按定义顺序排列的缺少的必填字段
+    pub missing: Vec<&'static str>,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "构建 {} 时缺少必填字段: {}", self.ty, self.missing.join(", "))
+    }
+}
+
+impl Error for BuildError {}
+
+macro_rules! builder {
+    // 内部规则: 字段有没有默认值
+    (@required $default:expr) => { false };
+    (@required) => { true };
+    // 内部规则: 取默认值，必填字段在 build() 中已经检查过
+    (@default $default:expr) => { $default };
+    (@default) => { unreachable!() };
+
+    (
+        $vis:vis $name:ident => $builder:ident {
+            $($field:ident : $ty:ty $(= $default:expr)?),* $(,)?
+        }
+    ) => {
+        // 每个字段都用 Option 保存，None 表示还没有设置
+        #[derive(Default)]
+        $vis struct $builder {
+            $($field: Option<$ty>,)*
+        }
+
+        impl $name {
+            $vis fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        impl $builder {
+            $(
+                $vis fn $field(mut self, value: impl Into<$ty>) -> Self {
+                    self.$field = Some(value.into());
+                    self
+                }
+            )*
+
+            $vis fn build(self) -> Result<$name, $crate::basic_learning::builder::BuildError> {
+                let mut missing = Vec::new();
+                $(
+                    if self.$field.is_none() && builder!(@required $($default)?) {
+                        missing.push(stringify!($field));
+                    }
+                )*
+                if !missing.is_empty() {
+                    return Err($crate::basic_learning::builder::BuildError {
+                        ty: stringify!($name),
+                        missing,
+                    });
+                }
+                Ok($name {
+                    $($field: match self.$field {
+                        Some(value) => value,
+                        None => builder!(@default $($default)?),
+                    },)*
+                })
+            }
+        }
+    };
+}
+
+// 导出为普通的路径，其他模块用 use 引入，不受模块声明顺序的限制
+pub(crate) use builder;
+
+pub fn run() {
+    use crate::basic_learning::compound_type::struct_test::User;
+    use crate::basic_learning::generic_and_traits::trait_object_test_2::Button;
+    use crate::basic_learning::generic_and_traits::trait_test::Post1;
+    use crate::basic_learning::style::dim_dbg;
+
+    // active 和 sign_in_count 有默认值，与 create_user 一致
+    let user = User::builder()
+        .username("sunface")
+        .email("sunface@example.com")
+        .build()
+        .unwrap();
+    dim_dbg!(&user);
+
+    // 缺少必填字段时列出全部
+    let err = User::builder().active(false).build().unwrap_err();
+    println!("{}", err);
+
+    // 同一个字段设置多次，以最后一次为准
+    let post = Post1::builder()
+        .title("Rust语言简介")
+        .title("Rust语言入门")
+        .author("Sunface")
+        .build()
+        .unwrap();
+    dim_dbg!(&post);
+
+    // 构建者可以分步设置，适合根据条件决定是否设置某个字段
+    let mut builder = Button::builder().label("Submit");
+    let large = true;
+    if large {
+        builder = builder.width(120u32).height(30u32);
+    }
+    dim_dbg!(builder.build().unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_learning::compound_type::struct_test::User;
+    use crate::basic_learning::generic_and_traits::trait_object_test_2::Button;
+    use crate::basic_learning::generic_and_traits::trait_test::Post1;
+
+    #[test]
+    fn defaults_fill_optional_fields() {
+        let user = User::builder().username("sunface").email("sunface@example.com").build().unwrap();
+        assert_eq!(
+            user,
+            User { active: true, username: "sunface".to_string(), email: "sunface@example.com".to_string(), sign_in_count: 1 }
+        );
+
+        let post = Post1::builder().title("Rust语言简介").author("Sunface").build().unwrap();
+        assert_eq!(post.content, "");
+
+        let button = Button::builder().label("OK").build().unwrap();
+        assert_eq!((button.width, button.height, button.label.as_str()), (50, 10, "OK"));
+    }
+
+    #[test]
+    fn setters_override_defaults() {
+        let user = User::builder()
+            .username("old")
+            .email("old@example.com")
+            .active(false)
+            .sign_in_count(42u64)
+            .build()
+            .unwrap();
+        assert!(!user.active);
+        assert_eq!(user.sign_in_count, 42);
+
+        let button = Button::builder().label("Cancel").width(80u32).build().unwrap();
+        assert_eq!((button.width, button.height, button.label.as_str()), (80, 10, "Cancel"));
+    }
+
+    #[test]
+    fn last_setter_wins() {
+        let post = Post1::builder().title("Rust语言简介").title("Rust语言入门").author("Sunface").build().unwrap();
+        assert_eq!(post.title, "Rust语言入门");
+    }
+
+    #[test]
+    fn missing_required_fields_are_all_listed() {
+        let err = User::builder().active(false).build().unwrap_err();
+        assert_eq!(err, BuildError { ty: "User", missing: vec!["username", "email"] });
+
+        let err = Post1::builder().content("Rust棒极了!").build().unwrap_err();
+        assert_eq!(err.missing, vec!["title", "author"]);
+        assert_eq!(err.to_string(), "构建 Post1 时缺少必填字段: title, author");
+
+        // 只缺一个时也一样，有默认值的字段不算缺少
+        let err = Button::builder().width(80u32).build().unwrap_err();
+        assert_eq!(err, BuildError { ty: "Button", missing: vec!["label"] });
+        let err = User::builder().username("sunface").build().unwrap_err();
+        assert_eq!(err.missing, vec!["email"]);
+    }
+}
@@ -0,0 +1,553 @@
+// 扑克牌
+/*
+    在 enum_test::PokerSuit 的基础上实现一副完整的扑克牌:
+        - Rank          点数，只能是 2~14 (J=11, Q=12, K=13, A=14)，构造时检查
+        - Card          点数 + 花色，先比较点数再比较花色，花色按桥牌的顺序 ♣ < ♦ < ♥ < ♠
+        - AnyCard       普通牌或大小王
+        - Deck          52 张牌，可以加上大小王，支持洗牌、抽牌和发牌
+        - 洗牌          Fisher–Yates 算法，随机数由 rand 提供，传入同一个种子得到同样的顺序
+        - 解析和输出    "7♣"、"7C"、"10H"、"TH"、"A♠"，{} 输出花色符号，{:#} 输出字母
+ */
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::basic_learning::compound_type::enum_test::{PokerCard, PokerSuit};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardError {
+    InvalidRank(u8),
+    Parse(String),
+    NotEnoughCards { needed: usize, remaining: usize },
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardError::InvalidRank(n) => write!(f, "点数必须在 2~14 之间: {}", n),
+            CardError::Parse(s) => write!(f, "无法解析的牌: {}", s),
+            CardError::NotEnoughCards { needed, remaining } => {
+                write!(f, "牌不够: 需要 {} 张，只剩 {} 张", needed, remaining)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+impl PokerSuit {
+    // 按桥牌的花色顺序
+    pub const ALL: [PokerSuit; 4] = [PokerSuit::Clubs, PokerSuit::Diamonds, PokerSuit::Hearts, PokerSuit::Spades];
+
+    pub fn symbol(self) -> char {
+        match self {
+            PokerSuit::Clubs => '♣',
+            PokerSuit::Diamonds => '♦',
+            PokerSuit::Hearts => '♥',
+            PokerSuit::Spades => '♠',
+        }
+    }
+
+    pub fn letter(self) -> char {
+        match self {
+            PokerSuit::Clubs => 'C',
+            PokerSuit::Diamonds => 'D',
+            PokerSuit::Hearts => 'H',
+            PokerSuit::Spades => 'S',
+        }
+    }
+
+    pub fn is_red(self) -> bool {
+        matches!(self, PokerSuit::Diamonds | PokerSuit::Hearts)
+    }
+
+    // 花色符号或字母，字母不区分大小写
+    pub fn from_char(c: char) -> Option<PokerSuit> {
+        PokerSuit::ALL
+            .into_iter()
+            .find(|s| s.symbol() == c || s.letter() == c.to_ascii_uppercase())
+    }
+
+    fn order(self) -> u8 {
+        PokerSuit::ALL.iter().position(|&s| s == self).unwrap() as u8
+    }
+}
+
+// 枚举定义的顺序是 Clubs, Spades, Diamonds, Hearts，不能直接 derive
+impl PartialOrd for PokerSuit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PokerSuit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order().cmp(&other.order())
+    }
+}
+
+// 字段私有，只能通过 Rank::new 或者常量创建，所以一定是有效的点数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rank(u8);
+
+impl Rank {
+    pub const TWO: Rank = Rank(2);
+    pub const TEN: Rank = Rank(10);
+    pub const JACK: Rank = Rank(11);
+    pub const QUEEN: Rank = Rank(12);
+    pub const KING: Rank = Rank(13);
+    pub const ACE: Rank = Rank(14);
+
+    pub fn new(value: u8) -> Result<Rank, CardError> {
+        match value {
+            2..=14 => Ok(Rank(value)),
+            _ => Err(CardError::InvalidRank(value)),
+        }
+    }
+
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    // 从 2 到 A
+    pub fn all() -> impl Iterator<Item = Rank> {
+        (2..=14).map(Rank)
+    }
+
+    pub fn is_face(self) -> bool {
+        (11..=13).contains(&self.0)
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self.0 {
+            11 => "J",
+            12 => "Q",
+            13 => "K",
+            14 => "A",
+            n => return write!(f, "{}", n),
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for Rank {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "J" => Ok(Rank::JACK),
+            "Q" => Ok(Rank::QUEEN),
+            "K" => Ok(Rank::KING),
+            "A" => Ok(Rank::ACE),
+            "T" => Ok(Rank::TEN),
+            // J、Q、K、A 必须用字母，"11" 不是合法的写法
+            // u8::from_str 还接受 "+7" 和 "07"，这里只认不以 0 开头的纯数字
+            n if n.bytes().all(|b| b.is_ascii_digit()) && !n.starts_with('0') => match n.parse::<u8>() {
+                Ok(n @ 2..=10) => Ok(Rank(n)),
+                _ => Err(CardError::Parse(s.to_string())),
+            },
+            _ => Err(CardError::Parse(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: PokerSuit,
+}
+
+impl Card {
+    pub fn new(rank: Rank, suit: PokerSuit) -> Card {
+        Card { rank, suit }
+    }
+}
+
+// 先比较点数，点数相同时比较花色，这样排序结果是唯一的
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.rank, self.suit).cmp(&(other.rank, other.suit))
+    }
+}
+
+// {} 输出 "7♣"，{:#} 输出 "7C"
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suit = if f.alternate() { self.suit.letter() } else { self.suit.symbol() };
+        write!(f, "{}{}", self.rank, suit)
+    }
+}
+
+// 最后一个字符是花色，前面是点数
+impl FromStr for Card {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CardError::Parse(s.to_string());
+        let s = s.trim();
+        let suit_char = s.chars().last().ok_or_else(err)?;
+        let suit = PokerSuit::from_char(suit_char).ok_or_else(err)?;
+        let rank = s[..s.len() - suit_char.len_utf8()].parse().map_err(|_| err())?;
+        Ok(Card { rank, suit })
+    }
+}
+
+// enum_test 中的 PokerCard 用 1 表示 A
+impl From<Card> for PokerCard {
+    fn from(card: Card) -> PokerCard {
+        let value = if card.rank == Rank::ACE { 1 } else { card.rank.value() };
+        PokerCard { suit: card.suit, value }
+    }
+}
+
+impl TryFrom<PokerCard> for Card {
+    type Error = CardError;
+
+    fn try_from(card: PokerCard) -> Result<Card, CardError> {
+        let rank = if card.value == 1 { Rank::ACE } else { Rank::new(card.value)? };
+        Ok(Card { rank, suit: card.suit })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Joker {
+    Black,
+    Red,
+}
+
+// 普通牌比大小王小
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnyCard {
+    Card(Card),
+    Joker(Joker),
+}
+
+impl fmt::Display for AnyCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyCard::Card(card) => fmt::Display::fmt(card, f),
+            AnyCard::Joker(Joker::Black) => f.write_str("BJ"),
+            AnyCard::Joker(Joker::Red) => f.write_str("RJ"),
+        }
+    }
+}
+
+impl FromStr for AnyCard {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "BJ" => Ok(AnyCard::Joker(Joker::Black)),
+            "RJ" => Ok(AnyCard::Joker(Joker::Red)),
+            _ => s.parse().map(AnyCard::Card),
+        }
+    }
+}
+
+impl From<Card> for AnyCard {
+    fn from(card: Card) -> AnyCard {
+        AnyCard::Card(card)
+    }
+}
+
+// 一副牌，C 是 Card 或者 AnyCard
+// 抽牌从末尾取，和从牌堆顶上拿牌一样
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deck<C = Card> {
+    cards: Vec<C>,
+}
+
+impl Deck<Card> {
+    // 按花色、点数排好的 52 张牌
+    pub fn new() -> Deck<Card> {
+        let cards = PokerSuit::ALL
+            .into_iter()
+            .flat_map(|suit| Rank::all().map(move |rank| Card { rank, suit }))
+            .collect();
+        Deck { cards }
+    }
+}
+
+impl Default for Deck<Card> {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
+impl Deck<AnyCard> {
+    // 52 张牌加上大小王
+    pub fn with_jokers() -> Deck<AnyCard> {
+        let mut cards: Vec<AnyCard> = Deck::new().cards.into_iter().map(AnyCard::Card).collect();
+        cards.push(AnyCard::Joker(Joker::Black));
+        cards.push(AnyCard::Joker(Joker::Red));
+        Deck { cards }
+    }
+}
+
+impl<C> Deck<C> {
+    pub fn from_cards(cards: Vec<C>) -> Deck<C> {
+        Deck { cards }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    // 剩下的牌，最后一张是下一张被抽到的
+    pub fn cards(&self) -> &[C] {
+        &self.cards
+    }
+
+    // Fisher–Yates 洗牌: 从后往前，每个位置和它前面(包括自己)随机的一个位置交换
+    // 每种排列出现的概率都是 1/n!
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for i in (1..self.cards.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            self.cards.swap(i, j);
+        }
+    }
+
+    // 同一个种子总是得到同样的顺序，方便重现一局牌
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        self.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+
+    pub fn draw(&mut self) -> Option<C> {
+        self.cards.pop()
+    }
+
+    pub fn draw_n(&mut self, n: usize) -> Result<Vec<C>, CardError> {
+        if n > self.cards.len() {
+            return Err(CardError::NotEnoughCards { needed: n, remaining: self.cards.len() });
+        }
+        Ok(self.cards.split_off(self.cards.len() - n).into_iter().rev().collect())
+    }
+
+    // 像真实发牌一样轮流给每一手发一张，牌不够时不发任何牌
+    pub fn deal(&mut self, hands: usize, cards_each: usize) -> Result<Vec<Vec<C>>, CardError> {
+        let remaining = self.cards.len();
+        // 乘法溢出时无论如何都不够发
+        let needed = hands.checked_mul(cards_each).ok_or(CardError::NotEnoughCards { needed: usize::MAX, remaining })?;
+        if needed > remaining {
+            return Err(CardError::NotEnoughCards { needed, remaining });
+        }
+        let mut result: Vec<Vec<C>> = (0..hands).map(|_| Vec::with_capacity(cards_each)).collect();
+        for _ in 0..cards_each {
+            for hand in result.iter_mut() {
+                hand.push(self.cards.pop().unwrap());
+            }
+        }
+        Ok(result)
+    }
+}
+
+// 用空格连接一手牌，{:#} 时每张牌都用字母表示
+pub fn format_hand<C: fmt::Display>(cards: &[C], ascii: bool) -> String {
+    cards
+        .iter()
+        .map(|c| if ascii { format!("{:#}", c) } else { c.to_string() })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 解析用空格分隔的多张牌，比如 "AS KS QS JS 10S"
+pub fn parse_hand(s: &str) -> Result<Vec<Card>, CardError> {
+    s.split_whitespace().map(str::parse).collect()
+}
+
+pub fn run() {
+    // 排序: 先点数后花色
+    let mut hand = parse_hand("KS 2H AD 2C 10S").unwrap();
+    hand.sort();
+    println!("{} / {}", format_hand(&hand, false), format_hand(&hand, true));
+
+    // 洗牌后轮流发牌，种子固定所以每次运行结果一样
+    let mut deck = Deck::new();
+    deck.shuffle_seeded(2024);
+    let hands = deck.deal(4, 2).unwrap();
+    for (i, hand) in hands.iter().enumerate() {
+        println!("玩家 {}: {}", i + 1, format_hand(hand, false));
+    }
+    let flop = deck.draw_n(3).unwrap();
+    println!("翻牌: {}", format_hand(&flop, false));
+    println!("剩余 {} 张", deck.len());
+    if let Err(err) = deck.deal(6, 7) {
+        println!("{err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks() {
+        assert!(Rank::new(1).is_err());
+        assert!(Rank::new(15).is_err());
+        assert_eq!(Rank::all().count(), 13);
+        assert!(Rank::ACE > Rank::KING && Rank::TWO < Rank::TEN);
+        assert!(Rank::QUEEN.is_face() && !Rank::ACE.is_face());
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let seven: Card = "7♣".parse().unwrap();
+        assert_eq!(seven, "7C".parse().unwrap());
+        assert_eq!(seven, "7c".parse().unwrap());
+        assert_eq!(seven.to_string(), "7♣");
+        assert_eq!(format!("{:#}", seven), "7C");
+        let ten: Card = "10H".parse().unwrap();
+        assert_eq!(ten, Card::new(Rank::TEN, PokerSuit::Hearts));
+        assert_eq!(ten, "TH".parse().unwrap());
+        assert_eq!(ten.to_string(), "10♥");
+        assert_eq!("A♠".parse::<Card>().unwrap(), Card::new(Rank::ACE, PokerSuit::Spades));
+        for bad in ["", "7", "1H", "11H", "7X", "♣", "AKS"] {
+            assert!(bad.parse::<Card>().is_err(), "{bad:?}");
+        }
+        assert_eq!("rj".parse::<AnyCard>().unwrap(), AnyCard::Joker(Joker::Red));
+        assert_eq!("QD".parse::<AnyCard>().unwrap().to_string(), "Q♦");
+        assert!(parse_hand("KS 2H XX").is_err());
+    }
+
+    #[test]
+    fn poker_card_conversion() {
+        let c1 = PokerCard { value: 1, suit: PokerSuit::Clubs };
+        let ace = Card::try_from(c1.clone()).unwrap();
+        assert_eq!(ace, Card::new(Rank::ACE, PokerSuit::Clubs));
+        assert_eq!(PokerCard::from(ace), c1);
+        for value in [0, 15] {
+            assert!(Card::try_from(PokerCard { value, suit: PokerSuit::Clubs }).is_err(), "{value}");
+        }
+        // 2 ~ 13 原样对应
+        for value in 2..=13 {
+            let card = Card::try_from(PokerCard { value, suit: PokerSuit::Hearts }).unwrap();
+            assert_eq!(PokerCard::from(card).value, value);
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let mut hand = parse_hand("KS 2H AD 2C 10S").unwrap();
+        hand.sort();
+        assert_eq!(format_hand(&hand, false), "2♣ 2♥ 10♠ K♠ A♦");
+        assert_eq!(format_hand(&hand, true), "2C 2H 10S KS AD");
+        assert!(PokerSuit::Clubs < PokerSuit::Spades && PokerSuit::Diamonds < PokerSuit::Hearts);
+        let ace = AnyCard::Card(Card::new(Rank::ACE, PokerSuit::Spades));
+        assert!(AnyCard::Joker(Joker::Black) > ace);
+        assert!(AnyCard::Joker(Joker::Red) > AnyCard::Joker(Joker::Black));
+    }
+
+    #[test]
+    fn full_deck() {
+        let deck = Deck::new();
+        assert_eq!(deck.len(), 52);
+        let mut sorted = deck.cards().to_vec();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 52);
+        assert_eq!(Deck::with_jokers().len(), 54);
+    }
+
+    #[test]
+    fn seeded_shuffle_is_repeatable() {
+        // 同一个种子洗出同样的顺序，洗牌后还是那 52 张
+        let (mut a, mut b) = (Deck::new(), Deck::new());
+        a.shuffle_seeded(42);
+        b.shuffle_seeded(42);
+        assert_eq!(a, b);
+        assert_ne!(a, Deck::new());
+        b.shuffle_seeded(43);
+        assert_ne!(a, b);
+        let mut cards = a.cards().to_vec();
+        let mut fresh = Deck::new().cards().to_vec();
+        cards.sort();
+        fresh.sort();
+        assert_eq!(cards, fresh);
+    }
+
+    #[test]
+    fn shuffle_is_uniform() {
+        // 统计黑桃 A 出现在每个位置的次数，期望都是 N/52
+        let mut rng = StdRng::seed_from_u64(7);
+        let trials = 52_000;
+        let mut counts = [0u32; 52];
+        let target = Card::new(Rank::ACE, PokerSuit::Spades);
+        for _ in 0..trials {
+            let mut d = Deck::new();
+            d.shuffle(&mut rng);
+            counts[d.cards().iter().position(|&c| c == target).unwrap()] += 1;
+        }
+        // 卡方检验，51 个自由度在 0.001 显著性水平下的临界值约为 87
+        let expected = trials as f64 / 52.0;
+        let chi2: f64 = counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum();
+        assert!(chi2 < 87.0, "chi2 = {chi2:.1}");
+    }
+
+    #[test]
+    fn deal_round_robin() {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(2024);
+        let top: Vec<Card> = deck.cards().iter().rev().take(8).copied().collect();
+        let hands = deck.deal(4, 2).unwrap();
+        assert_eq!(deck.len(), 44);
+        // 轮流发牌: 第一手拿到第 1 和第 5 张
+        assert_eq!(hands[0], vec![top[0], top[4]]);
+        assert_eq!(hands[3], vec![top[3], top[7]]);
+        let next = *deck.cards().last().unwrap();
+        assert_eq!(deck.draw_n(3).unwrap()[0], next);
+        assert_eq!(deck.len(), 41);
+    }
+
+    #[test]
+    fn not_enough_cards_leaves_deck_unchanged() {
+        let mut deck = Deck::new();
+        deck.draw_n(11).unwrap();
+        let before = deck.clone();
+        assert_eq!(deck.deal(6, 7).unwrap_err(), CardError::NotEnoughCards { needed: 42, remaining: 41 });
+        assert_eq!(deck.draw_n(42).unwrap_err(), CardError::NotEnoughCards { needed: 42, remaining: 41 });
+        assert_eq!(deck, before);
+    }
+
+    #[test]
+    fn jokers_are_dealt_too() {
+        let mut deck = Deck::with_jokers();
+        deck.shuffle_seeded(1);
+        let hands = deck.deal(3, 18).unwrap();
+        assert!(deck.is_empty());
+        let jokers = hands.iter().flatten().filter(|c| matches!(c, AnyCard::Joker(_))).count();
+        assert_eq!(jokers, 2);
+    }
+
+    #[test]
+    fn rank_accepts_only_plain_digits() {
+        assert_eq!("7".parse::<Rank>().unwrap(), Rank::new(7).unwrap());
+        assert_eq!("10".parse::<Rank>().unwrap(), Rank::TEN);
+        for bad in ["+7", "07", "010", " 7", "7 ", "-2", "11", "1"] {
+            assert!(bad.parse::<Rank>().is_err(), "{bad:?}");
+        }
+        assert!("+7H".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn deal_overflow_is_not_enough_cards() {
+        let mut deck = Deck::new();
+        let err = deck.deal(usize::MAX, 2).unwrap_err();
+        assert_eq!(err, CardError::NotEnoughCards { needed: usize::MAX, remaining: 52 });
+        assert_eq!(deck.len(), 52);
+    }
+}
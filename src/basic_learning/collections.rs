@@ -1,5 +1,7 @@
 // 当我们想拥有一个列表，里面都是相同类型的数据时，Vector将会非常有用。
 pub mod vector {
+    use crate::basic_learning::style::dim_dbg;
+
     // 创建
     pub fn create() {
         // Vec::new()
@@ -7,25 +9,26 @@ pub mod vector {
             // 无法从 Vec::new() 中得到任何关于类型的信息
             // 无法推导出 a 的具体类型
             let a: Vec<i32> = Vec::new();
-            dbg!(a);
+            dim_dbg!(a);
 
 
             // 编译器通过 b.push(1u8)，推测出 b 中的元素类型是 u8
             let mut b = Vec::new();
             b.push(1u8);
-            dbg!(b);
+            dim_dbg!(b);
 
             /*
                 如果预先知道要存储的元素个数
                 可以使用 Vec::with_capacity(capacity) 创建动态数组
                 这样可以避免因为插入大量新数据导致频繁的内存分配和拷贝，提升性能
+                扩容的具体过程可以运行下方 my_vec::run 观察
              */
         }
 
         // vec![]
         {
             let a = vec![1, 2, 3];
-            dbg!(a);
+            dim_dbg!(a);
         }
     }
 
@@ -33,12 +36,12 @@ pub mod vector {
     pub fn update() {
         // 声明为 mut 后，才能进行修改
         let mut a: Vec<i32> = vec![];
-        dbg!(&a);
+        dim_dbg!(&a);
         a.push(1);
         a.push(2);
-        dbg!(&a);
+        dim_dbg!(&a);
         a.pop();
-        dbg!(&a);
+        dim_dbg!(&a);
     }
 
     // 取值
@@ -74,7 +77,9 @@ pub mod vector {
         for i in &mut v {
             *i += 10
         }
-        dbg!(v);
+        dim_dbg!(v);
+
+        // 除了 for 循环，还可以使用 map、filter 等迭代器适配器，见 iterators 模块
     }
 
     // 存储不同类型的元素
@@ -90,28 +95,11 @@ pub mod vector {
                 IpAddr::V4("127.0.0.1".to_string()),
                 IpAddr::V6("::1".to_string())
             ];
-            dbg!(v);
+            dim_dbg!(v);
         }
 
-        // 使用特征实现
+        // 使用特征实现 (IpAddr、V4、V6 定义在下方，插件注册表 plugin_registry 也会用到)
         {
-            trait IpAddr {
-                fn display(&self);
-            }
-
-            struct V4(String);
-            impl IpAddr for V4 {
-                fn display(&self) {
-                    println!("ipv4: {:?}", self.0)
-                }
-            }
-            struct V6(String);
-            impl IpAddr for V6 {
-                fn display(&self) {
-                    println!("ipv6: {:?}", self.0)
-                }
-            }
-
             // 这里必须手动地指定类型：Vec<Box<dyn IpAddr>>，表示数组 v 存储的是特征 IpAddr 的对象
             let v: Vec<Box<dyn IpAddr>> = vec![
                 Box::new(V4("127.0.0.1".to_string())),
@@ -127,6 +115,24 @@ pub mod vector {
         // 主要原因在于特征对象非常灵活，而编译器对枚举的限制较多，且无法动态增加类型
     }
 
+    pub trait IpAddr {
+        fn display(&self);
+    }
+
+    pub struct V4(pub String);
+    impl IpAddr for V4 {
+        fn display(&self) {
+            println!("ipv4: {:?}", self.0)
+        }
+    }
+
+    pub struct V6(pub String);
+    impl IpAddr for V6 {
+        fn display(&self) {
+            println!("ipv6: {:?}", self.0)
+        }
+    }
+
     // 排序
     pub fn sort(){
         /*
@@ -139,4 +145,2113 @@ pub mod vector {
          */
         // todo 未完
     }
-}
\ No newline at end of file
+}
+
+// 手写一个动态数组 MyVec<T>，模仿 Vec 的内部实现
+// 参考 https://doc.rust-lang.org/nomicon/vec/vec.html
+pub mod my_vec {
+    use std::alloc::{self, Layout};
+    use std::marker::PhantomData;
+    use std::mem;
+    use std::ops::{Deref, DerefMut};
+    use std::ptr::{self, NonNull};
+
+    use crate::basic_learning::style::dim_dbg;
+    use crate::basic_learning::text_layout::{Align, Table};
+
+    /*
+        Vec 在内存中只有三个字段：指向堆内存的指针、容量 cap、长度 len
+            - len 是已经初始化的元素个数
+            - cap 是已经分配的内存能容纳的元素个数
+        当 len == cap 时继续 push，就需要重新分配一块更大的内存，并把旧数据拷贝过去
+     */
+    pub struct MyVec<T> {
+        ptr: NonNull<T>,
+        cap: usize,
+        len: usize,
+        // 记录每次内存分配时容量的变化，方便观察扩容过程
+        growth_log: Vec<Growth>,
+        // 告诉编译器 MyVec 拥有 T，drop 检查时会把 T 考虑进去
+        _marker: PhantomData<T>,
+    }
+
+    // 一次扩容记录
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Growth {
+        // 触发扩容时的长度
+        pub len: usize,
+        pub old_cap: usize,
+        pub new_cap: usize,
+    }
+
+    // 与 Vec 一样，只要 T 可以跨线程，MyVec<T> 也可以
+    unsafe impl<T: Send> Send for MyVec<T> {}
+    unsafe impl<T: Sync> Sync for MyVec<T> {}
+
+    impl<T> MyVec<T> {
+        // 零大小类型(ZST)不需要分配内存，容量视为无限大
+        const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+        pub fn new() -> Self {
+            MyVec {
+                // 悬垂指针，在真正分配内存之前不会被解引用
+                ptr: NonNull::dangling(),
+                cap: if Self::IS_ZST { usize::MAX } else { 0 },
+                len: 0,
+                growth_log: Vec::new(),
+                _marker: PhantomData,
+            }
+        }
+
+        // 预先分配好 capacity 个元素的空间
+        pub fn with_capacity(capacity: usize) -> Self {
+            let mut v = Self::new();
+            if !Self::IS_ZST && capacity > 0 {
+                v.grow_to(capacity);
+            }
+            v
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.cap
+        }
+
+        pub fn growth_log(&self) -> &[Growth] {
+            &self.growth_log
+        }
+
+        pub fn push(&mut self, elem: T) {
+            if self.len == self.cap {
+                self.grow();
+            }
+            // 写入未初始化的内存，不能用 *p = elem，否则会 drop 掉一块垃圾数据
+            unsafe { ptr::write(self.ptr.as_ptr().add(self.len), elem) }
+            self.len += 1;
+        }
+
+        pub fn pop(&mut self) -> Option<T> {
+            if self.len == 0 {
+                None
+            } else {
+                self.len -= 1;
+                // 把值按位读出来，这块内存从此被视为未初始化
+                unsafe { Some(ptr::read(self.ptr.as_ptr().add(self.len))) }
+            }
+        }
+
+        // 在 index 处插入元素，后面的元素整体后移一位
+        // 与 Vec 一样，index > len 时会 panic
+        pub fn insert(&mut self, index: usize, elem: T) {
+            assert!(index <= self.len, "insert index (is {index}) should be <= len (is {})", self.len);
+            if self.len == self.cap {
+                self.grow();
+            }
+            unsafe {
+                let p = self.ptr.as_ptr().add(index);
+                // ptr::copy 等同于 C 的 memmove，源和目标区域可以重叠
+                ptr::copy(p, p.add(1), self.len - index);
+                ptr::write(p, elem);
+            }
+            self.len += 1;
+        }
+
+        // 删除 index 处的元素，后面的元素整体前移一位
+        // 与 Vec 一样，index >= len 时会 panic
+        pub fn remove(&mut self, index: usize) -> T {
+            assert!(index < self.len, "removal index (is {index}) should be < len (is {})", self.len);
+            unsafe {
+                self.len -= 1;
+                let p = self.ptr.as_ptr().add(index);
+                let result = ptr::read(p);
+                ptr::copy(p.add(1), p, self.len - index);
+                result
+            }
+        }
+
+        pub fn get(&self, index: usize) -> Option<&T> {
+            self.deref().get(index)
+        }
+
+        pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+            self.deref_mut().get_mut(index)
+        }
+
+        // 容量翻倍，第一次分配 4 个元素
+        fn grow(&mut self) {
+            // ZST 的容量是 usize::MAX，走到这里说明长度溢出了
+            assert!(!Self::IS_ZST, "capacity overflow");
+            let new_cap = if self.cap == 0 { 4 } else { self.cap.checked_mul(2).expect("capacity overflow") };
+            self.grow_to(new_cap);
+        }
+
+        fn grow_to(&mut self, new_cap: usize) {
+            // Layout::array 会检查 new_cap * size_of::<T>() 是否超过 isize::MAX
+            let new_layout = Layout::array::<T>(new_cap).expect("capacity overflow");
+
+            let new_ptr = if self.cap == 0 {
+                unsafe { alloc::alloc(new_layout) }
+            } else {
+                let old_layout = Layout::array::<T>(self.cap).unwrap();
+                unsafe { alloc::realloc(self.ptr.as_ptr() as *mut u8, old_layout, new_layout.size()) }
+            };
+
+            // 分配失败时返回空指针
+            self.ptr = match NonNull::new(new_ptr as *mut T) {
+                Some(p) => p,
+                None => alloc::handle_alloc_error(new_layout),
+            };
+            self.growth_log.push(Growth { len: self.len, old_cap: self.cap, new_cap });
+            self.cap = new_cap;
+        }
+    }
+
+    impl<T> Default for MyVec<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Drop for MyVec<T> {
+        fn drop(&mut self) {
+            unsafe {
+                // 先 drop 掉每一个已初始化的元素，再释放内存
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len));
+                if !Self::IS_ZST && self.cap != 0 {
+                    alloc::dealloc(self.ptr.as_ptr() as *mut u8, Layout::array::<T>(self.cap).unwrap());
+                }
+            }
+        }
+    }
+
+    // 实现 Deref 后，MyVec 可以直接使用切片的所有方法，v[i]、v.iter()、v.len() 等
+    // 索引 v[i] 也是通过自动解引用到 [T] 实现的
+    impl<T> Deref for MyVec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] {
+            unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T> DerefMut for MyVec<T> {
+        fn deref_mut(&mut self) -> &mut [T] {
+            unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T: std::fmt::Debug> std::fmt::Debug for MyVec<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T> FromIterator<T> for MyVec<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut v = MyVec::new();
+            for elem in iter {
+                v.push(elem);
+            }
+            v
+        }
+    }
+
+    // for x in v     获取所有权
+    pub struct IntoIter<T> {
+        ptr: NonNull<T>,
+        cap: usize,
+        // [start, end) 之间的元素还没有被取走
+        start: usize,
+        end: usize,
+        _marker: PhantomData<T>,
+    }
+
+    impl<T> IntoIterator for MyVec<T> {
+        type Item = T;
+        type IntoIter = IntoIter<T>;
+        fn into_iter(self) -> IntoIter<T> {
+            // 内存的所有权转移给 IntoIter，MyVec 自己不能再执行 drop
+            let vec = mem::ManuallyDrop::new(self);
+            IntoIter {
+                ptr: vec.ptr,
+                cap: vec.cap,
+                start: 0,
+                end: vec.len,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<T> Iterator for IntoIter<T> {
+        type Item = T;
+        fn next(&mut self) -> Option<T> {
+            if self.start == self.end {
+                None
+            } else {
+                let elem = unsafe { ptr::read(self.ptr.as_ptr().add(self.start)) };
+                self.start += 1;
+                Some(elem)
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.end - self.start;
+            (len, Some(len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IntoIter<T> {
+        fn next_back(&mut self) -> Option<T> {
+            if self.start == self.end {
+                None
+            } else {
+                self.end -= 1;
+                unsafe { Some(ptr::read(self.ptr.as_ptr().add(self.end))) }
+            }
+        }
+    }
+
+    impl<T> ExactSizeIterator for IntoIter<T> {}
+
+    impl<T> Drop for IntoIter<T> {
+        fn drop(&mut self) {
+            unsafe {
+                // 只 drop 还没有被迭代出去的元素
+                let rest = self.ptr.as_ptr().add(self.start);
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(rest, self.end - self.start));
+                if mem::size_of::<T>() != 0 && self.cap != 0 {
+                    alloc::dealloc(self.ptr.as_ptr() as *mut u8, Layout::array::<T>(self.cap).unwrap());
+                }
+            }
+        }
+    }
+
+    // for x in &v    不可变借用，直接复用切片的迭代器
+    impl<'a, T> IntoIterator for &'a MyVec<T> {
+        type Item = &'a T;
+        type IntoIter = std::slice::Iter<'a, T>;
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    // for x in &mut v    可变借用
+    impl<'a, T> IntoIterator for &'a mut MyVec<T> {
+        type Item = &'a mut T;
+        type IntoIter = std::slice::IterMut<'a, T>;
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter_mut()
+        }
+    }
+
+    pub fn run() {
+        // 观察扩容：4 -> 8 -> 16 -> 32
+        let mut v = MyVec::new();
+        for i in 0..20 {
+            v.push(i);
+        }
+        let mut table = Table::new(&["扩容时长度", "原容量", "新容量"])
+            .align(0, Align::Right)
+            .align(1, Align::Right)
+            .align(2, Align::Right);
+        for g in v.growth_log() {
+            table.add_row(&[g.len, g.old_cap, g.new_cap]);
+        }
+        println!("{table}");
+
+        // 预先分配容量，push 的过程中不会再扩容
+        let mut w = MyVec::with_capacity(20);
+        for i in 0..20 {
+            w.push(i);
+        }
+        dim_dbg!(w.growth_log());
+
+        // 增删改查
+        let mut v: MyVec<String> = MyVec::new();
+        v.push("b".to_string());
+        v.push("d".to_string());
+        v.insert(0, "a".to_string());
+        v.insert(2, "c".to_string());
+        dim_dbg!(&v);
+        dim_dbg!(v.remove(1));
+        dim_dbg!(v.pop());
+        v[0].push_str("_new");
+        for s in &mut v {
+            s.push('!');
+        }
+        dim_dbg!(&v);
+        let reversed: Vec<String> = v.into_iter().rev().collect();
+        dim_dbg!(reversed);
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::rc::Rc;
+
+        #[test]
+        fn push_doubles_capacity() {
+            let mut v = MyVec::new();
+            assert_eq!(v.capacity(), 0);
+            for i in 0..9 {
+                v.push(i);
+            }
+            assert_eq!(v.capacity(), 16);
+            assert_eq!(&v[..], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+            let caps: Vec<usize> = v.growth_log().iter().map(|g| g.new_cap).collect();
+            assert_eq!(caps, [4, 8, 16]);
+        }
+
+        #[test]
+        fn with_capacity_allocates_once() {
+            let mut v = MyVec::with_capacity(5);
+            for i in 0..5 {
+                v.push(i);
+            }
+            assert_eq!(v.capacity(), 5);
+            assert_eq!(v.growth_log(), [Growth { len: 0, old_cap: 0, new_cap: 5 }]);
+        }
+
+        #[test]
+        fn insert_remove_pop_get() {
+            let mut v: MyVec<String> = MyVec::new();
+            v.push("b".to_string());
+            v.push("d".to_string());
+            v.insert(0, "a".to_string());
+            v.insert(2, "c".to_string());
+            assert_eq!(&v[..], ["a", "b", "c", "d"]);
+            assert_eq!(v.remove(1), "b");
+            assert_eq!(v.pop().as_deref(), Some("d"));
+            assert_eq!(v.get(5), None);
+            v.get_mut(0).unwrap().push('!');
+            assert_eq!(&v[..], ["a!", "c"]);
+        }
+
+        #[test]
+        #[should_panic(expected = "insert index")]
+        fn insert_out_of_bounds_panics() {
+            let mut v = MyVec::new();
+            v.push(1);
+            v.insert(2, 2);
+        }
+
+        #[test]
+        fn into_iter_both_ends() {
+            let v: MyVec<i32> = (1..=4).collect();
+            let mut iter = v.into_iter();
+            assert_eq!(iter.len(), 4);
+            assert_eq!(iter.next(), Some(1));
+            assert_eq!(iter.next_back(), Some(4));
+            assert_eq!(iter.collect::<Vec<_>>(), [2, 3]);
+        }
+
+        #[test]
+        fn drop_releases_every_element() {
+            let counter = Rc::new(());
+            let mut v = MyVec::new();
+            for _ in 0..6 {
+                v.push(Rc::clone(&counter));
+            }
+            assert_eq!(Rc::strong_count(&counter), 7);
+            drop(v.remove(2));
+            assert_eq!(Rc::strong_count(&counter), 6);
+            drop(v);
+            assert_eq!(Rc::strong_count(&counter), 1);
+        }
+
+        #[test]
+        fn partially_consumed_into_iter_drops_rest() {
+            let counter = Rc::new(());
+            let v: MyVec<Rc<()>> = (0..6).map(|_| Rc::clone(&counter)).collect();
+            let mut iter = v.into_iter();
+            iter.next();
+            iter.next_back();
+            assert_eq!(Rc::strong_count(&counter), 5);
+            drop(iter);
+            assert_eq!(Rc::strong_count(&counter), 1);
+        }
+
+        #[test]
+        fn zero_sized_types_never_allocate() {
+            let mut z = MyVec::new();
+            for _ in 0..10 {
+                z.push(());
+            }
+            assert_eq!(z.len(), 10);
+            assert_eq!(z.capacity(), usize::MAX);
+            assert!(z.growth_log().is_empty());
+            assert_eq!(z.into_iter().count(), 10);
+        }
+    }
+}
+
+
+// 环形缓冲区 / 双端队列，类似 std::collections::VecDeque
+pub mod ring_buffer {
+    use std::fmt;
+    use std::iter::Chain;
+    use std::mem::MaybeUninit;
+    use std::ops::{Index, IndexMut};
+    use std::slice;
+
+    use crate::basic_learning::style::dim_dbg;
+
+    /*
+        元素存放在一块固定长度的内存中，head 指向第一个元素
+        逻辑上的第 i 个元素位于 (head + i) % cap 处，写到末尾后会绕回开头
+
+            cap = 8, head = 6, len = 4
+            [ 2 3 _ _ _ _ 0 1 ]
+                          ^ head
+     */
+    pub struct RingBuffer<T> {
+        buf: Box<[MaybeUninit<T>]>,
+        head: usize,
+        len: usize,
+        overflow: Overflow,
+    }
+
+    // 缓冲区满了之后再写入的处理方式
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Overflow {
+        // 容量翻倍，和 VecDeque 一样
+        Grow,
+        // 拒绝写入，把值原样返回
+        Reject,
+        // 覆盖另一端最旧的元素，适合只保留最近 N 条的日志
+        Overwrite,
+    }
+
+    pub type Iter<'a, T> = Chain<slice::Iter<'a, T>, slice::Iter<'a, T>>;
+    pub type IterMut<'a, T> = Chain<slice::IterMut<'a, T>, slice::IterMut<'a, T>>;
+
+    impl<T> RingBuffer<T> {
+        // 可增长的缓冲区
+        pub fn new() -> Self {
+            Self::with_overflow(0, Overflow::Grow)
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            Self::with_overflow(capacity, Overflow::Grow)
+        }
+
+        // 固定容量，写满后拒绝写入
+        pub fn bounded(capacity: usize) -> Self {
+            assert!(capacity > 0, "bounded ring buffer needs a non-zero capacity");
+            Self::with_overflow(capacity, Overflow::Reject)
+        }
+
+        // 固定容量，写满后覆盖最旧的元素
+        pub fn overwriting(capacity: usize) -> Self {
+            assert!(capacity > 0, "overwriting ring buffer needs a non-zero capacity");
+            Self::with_overflow(capacity, Overflow::Overwrite)
+        }
+
+        fn with_overflow(capacity: usize, overflow: Overflow) -> Self {
+            RingBuffer {
+                buf: Self::alloc(capacity),
+                head: 0,
+                len: 0,
+                overflow,
+            }
+        }
+
+        fn alloc(capacity: usize) -> Box<[MaybeUninit<T>]> {
+            (0..capacity).map(|_| MaybeUninit::uninit()).collect()
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.buf.len()
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.len == self.capacity()
+        }
+
+        pub fn overflow(&self) -> Overflow {
+            self.overflow
+        }
+
+        // 逻辑下标 -> 物理下标
+        fn physical(&self, index: usize) -> usize {
+            let i = self.head + index;
+            if i >= self.capacity() { i - self.capacity() } else { i }
+        }
+
+        /*
+            写入一个元素，返回值:
+                - Ok(None)         正常写入
+                - Ok(Some(old))    Overwrite 模式下被挤掉的最旧元素
+                - Err(value)       Reject 模式下缓冲区已满，原样返回
+         */
+        pub fn push_back(&mut self, value: T) -> Result<Option<T>, T> {
+            let evicted = match self.make_room() {
+                Ok(()) => None,
+                Err(()) if self.overflow == Overflow::Reject => return Err(value),
+                // 从队头挤掉一个
+                Err(()) => self.pop_front(),
+            };
+            let i = self.physical(self.len);
+            self.buf[i].write(value);
+            self.len += 1;
+            Ok(evicted)
+        }
+
+        // 与 push_back 相同，只是 Overwrite 模式下挤掉的是队尾的元素
+        pub fn push_front(&mut self, value: T) -> Result<Option<T>, T> {
+            let evicted = match self.make_room() {
+                Ok(()) => None,
+                Err(()) if self.overflow == Overflow::Reject => return Err(value),
+                Err(()) => self.pop_back(),
+            };
+            self.head = self.physical(self.capacity() - 1);
+            self.buf[self.head].write(value);
+            self.len += 1;
+            Ok(evicted)
+        }
+
+        // 保证还能再放下一个元素，固定容量的缓冲区满了则返回 Err
+        fn make_room(&mut self) -> Result<(), ()> {
+            if !self.is_full() {
+                Ok(())
+            } else if self.overflow == Overflow::Grow {
+                self.grow();
+                Ok(())
+            } else {
+                Err(())
+            }
+        }
+
+        fn grow(&mut self) {
+            let new_cap = if self.capacity() == 0 { 4 } else { self.capacity() * 2 };
+            let len = self.len;
+            self.make_contiguous();
+            let mut new_buf = Self::alloc(new_cap);
+            // 逐位交换 MaybeUninit，旧内存里的元素被视为未初始化，不会被 drop
+            new_buf[..len].swap_with_slice(&mut self.buf[..len]);
+            self.buf = new_buf;
+            self.head = 0;
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            if self.is_empty() {
+                return None;
+            }
+            let value = unsafe { self.buf[self.head].assume_init_read() };
+            self.head = self.physical(1);
+            self.len -= 1;
+            Some(value)
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            if self.is_empty() {
+                return None;
+            }
+            self.len -= 1;
+            let i = self.physical(self.len);
+            unsafe { Some(self.buf[i].assume_init_read()) }
+        }
+
+        pub fn front(&self) -> Option<&T> {
+            self.get(0)
+        }
+
+        pub fn back(&self) -> Option<&T> {
+            self.len.checked_sub(1).and_then(|i| self.get(i))
+        }
+
+        // 下标相对于队头计算，0 永远是最旧(最前面)的元素
+        pub fn get(&self, index: usize) -> Option<&T> {
+            if index < self.len {
+                unsafe { Some(self.buf[self.physical(index)].assume_init_ref()) }
+            } else {
+                None
+            }
+        }
+
+        pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+            if index < self.len {
+                let i = self.physical(index);
+                unsafe { Some(self.buf[i].assume_init_mut()) }
+            } else {
+                None
+            }
+        }
+
+        // 按逻辑顺序返回两段切片，第二段是绕回开头的部分
+        pub fn as_slices(&self) -> (&[T], &[T]) {
+            let (first, second) = self.ranges();
+            unsafe { (assume_init(&self.buf[first]), assume_init(&self.buf[second])) }
+        }
+
+        pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+            let (first, second) = self.ranges();
+            // second 在 first 之前，先切开再分别转换
+            let (wrapped, rest) = self.buf.split_at_mut(first.start);
+            unsafe {
+                (
+                    assume_init_mut(&mut rest[..first.end - first.start]),
+                    assume_init_mut(&mut wrapped[second]),
+                )
+            }
+        }
+
+        fn ranges(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+            let tail = self.head + self.len;
+            if tail <= self.capacity() {
+                (self.head..tail, 0..0)
+            } else {
+                (self.head..self.capacity(), 0..tail - self.capacity())
+            }
+        }
+
+        // 旋转内部内存，让所有元素连续存放，然后以一个切片返回
+        pub fn make_contiguous(&mut self) -> &mut [T] {
+            // MaybeUninit 的旋转只是搬动字节，不会读取未初始化的内容
+            self.buf.rotate_left(self.head);
+            self.head = 0;
+            unsafe { assume_init_mut(&mut self.buf[..self.len]) }
+        }
+
+        pub fn clear(&mut self) {
+            while self.pop_front().is_some() {}
+            self.head = 0;
+        }
+
+        pub fn iter(&self) -> Iter<'_, T> {
+            let (a, b) = self.as_slices();
+            a.iter().chain(b.iter())
+        }
+
+        pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+            let (a, b) = self.as_mut_slices();
+            a.iter_mut().chain(b.iter_mut())
+        }
+    }
+
+    // 调用方需要保证切片里的元素都已经初始化
+    unsafe fn assume_init<T>(s: &[MaybeUninit<T>]) -> &[T] {
+        slice::from_raw_parts(s.as_ptr() as *const T, s.len())
+    }
+
+    unsafe fn assume_init_mut<T>(s: &mut [MaybeUninit<T>]) -> &mut [T] {
+        slice::from_raw_parts_mut(s.as_mut_ptr() as *mut T, s.len())
+    }
+
+    impl<T> Drop for RingBuffer<T> {
+        fn drop(&mut self) {
+            let (a, b) = self.as_mut_slices();
+            unsafe {
+                std::ptr::drop_in_place(a);
+                std::ptr::drop_in_place(b);
+            }
+        }
+    }
+
+    impl<T> Default for RingBuffer<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Index<usize> for RingBuffer<T> {
+        type Output = T;
+        fn index(&self, index: usize) -> &T {
+            let len = self.len;
+            self.get(index).unwrap_or_else(|| panic!("index out of bounds: the len is {len} but the index is {index}"))
+        }
+    }
+
+    impl<T> IndexMut<usize> for RingBuffer<T> {
+        fn index_mut(&mut self, index: usize) -> &mut T {
+            let len = self.len;
+            self.get_mut(index).unwrap_or_else(|| panic!("index out of bounds: the len is {len} but the index is {index}"))
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for RingBuffer<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T> FromIterator<T> for RingBuffer<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut rb = RingBuffer::new();
+            for value in iter {
+                let _ = rb.push_back(value);
+            }
+            rb
+        }
+    }
+
+    pub struct IntoIter<T>(RingBuffer<T>);
+
+    impl<T> Iterator for IntoIter<T> {
+        type Item = T;
+        fn next(&mut self) -> Option<T> {
+            self.0.pop_front()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.0.len, Some(self.0.len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IntoIter<T> {
+        fn next_back(&mut self) -> Option<T> {
+            self.0.pop_back()
+        }
+    }
+
+    impl<T> ExactSizeIterator for IntoIter<T> {}
+
+    impl<T> IntoIterator for RingBuffer<T> {
+        type Item = T;
+        type IntoIter = IntoIter<T>;
+        fn into_iter(self) -> IntoIter<T> {
+            IntoIter(self)
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+        type Item = &'a T;
+        type IntoIter = Iter<'a, T>;
+        fn into_iter(self) -> Iter<'a, T> {
+            self.iter()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a mut RingBuffer<T> {
+        type Item = &'a mut T;
+        type IntoIter = IterMut<'a, T>;
+        fn into_iter(self) -> IterMut<'a, T> {
+            self.iter_mut()
+        }
+    }
+
+    pub fn run() {
+        // 双端操作
+        let mut rb = RingBuffer::with_capacity(4);
+        rb.push_back(1).unwrap();
+        rb.push_back(2).unwrap();
+        rb.push_front(0).unwrap();
+        rb.push_front(-1).unwrap();
+        // 此时 head 已经绕到了内存的末尾
+        dim_dbg!(rb.as_slices());
+        dim_dbg!(rb.make_contiguous());
+
+        // 固定容量，满了就拒绝
+        let mut bounded = RingBuffer::bounded(2);
+        bounded.push_back("a").unwrap();
+        bounded.push_back("b").unwrap();
+        if let Err(rejected) = bounded.push_back("c") {
+            println!("缓冲区已满，拒绝了 {rejected}");
+        }
+
+        // 有界日志: 只保留最近 3 条
+        let mut log = RingBuffer::overwriting(3);
+        for i in 1..=5 {
+            if let Ok(Some(old)) = log.push_back(format!("第{i}条日志")) {
+                println!("丢弃了 {old}");
+            }
+        }
+        dim_dbg!(&log);
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use std::collections::VecDeque;
+
+        #[test]
+        fn wraps_around_and_makes_contiguous() {
+            let mut rb = RingBuffer::with_capacity(4);
+            rb.push_back(1).unwrap();
+            rb.push_back(2).unwrap();
+            rb.push_front(0).unwrap();
+            rb.push_front(-1).unwrap();
+            assert_eq!(rb[0], -1);
+            assert_eq!(rb.iter().copied().collect::<Vec<_>>(), [-1, 0, 1, 2]);
+            assert_eq!(rb.make_contiguous(), [-1, 0, 1, 2]);
+            assert_eq!(rb.as_slices().1, []);
+        }
+
+        #[test]
+        fn bounded_rejects_when_full() {
+            let mut bounded = RingBuffer::bounded(2);
+            assert_eq!(bounded.push_back("a"), Ok(None));
+            assert_eq!(bounded.push_back("b"), Ok(None));
+            assert_eq!(bounded.push_back("c"), Err("c"));
+        }
+
+        #[test]
+        fn overwriting_keeps_latest() {
+            let mut log = RingBuffer::overwriting(3);
+            for i in 1..=5 {
+                log.push_back(i).unwrap();
+            }
+            assert_eq!(log.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+            assert_eq!(log.push_front(0), Ok(Some(5)));
+        }
+
+        #[test]
+        fn matches_vec_deque() {
+            for seed in 0..20 {
+                check_against_vec_deque(seed);
+            }
+        }
+
+        #[test]
+        fn overwrite_matches_vec_deque() {
+            for seed in 0..20 {
+                check_overwrite_against_vec_deque(seed);
+            }
+        }
+
+        // 用同一串随机操作分别驱动 RingBuffer 和 VecDeque，每一步都比较结果
+        fn check_against_vec_deque(seed: u64) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut ours = RingBuffer::new();
+            let mut std = VecDeque::new();
+            for step in 0..500 {
+                match rng.gen_range(0..8) {
+                    0 | 1 => {
+                        ours.push_back(step).unwrap();
+                        std.push_back(step);
+                    }
+                    2 | 3 => {
+                        ours.push_front(step).unwrap();
+                        std.push_front(step);
+                    }
+                    4 => assert_eq!(ours.pop_front(), std.pop_front()),
+                    5 => assert_eq!(ours.pop_back(), std.pop_back()),
+                    6 => {
+                        let i = rng.gen_range(0..=std.len());
+                        assert_eq!(ours.get(i), std.get(i));
+                        if let (Some(a), Some(b)) = (ours.get_mut(i), std.get_mut(i)) {
+                            *a += 1;
+                            *b += 1;
+                        }
+                    }
+                    _ => {
+                        std.make_contiguous();
+                        ours.make_contiguous();
+                    }
+                }
+                assert_eq!(ours.len(), std.len());
+                assert_eq!(ours.front(), std.front());
+                assert_eq!(ours.back(), std.back());
+                assert!(ours.iter().eq(std.iter()));
+            }
+            assert!(ours.into_iter().rev().eq(std.into_iter().rev()));
+        }
+
+        // Overwrite 模式下，用 VecDeque 手动淘汰来模拟
+        fn check_overwrite_against_vec_deque(seed: u64) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let cap = rng.gen_range(1..8);
+            let mut ours = RingBuffer::overwriting(cap);
+            let mut std = VecDeque::new();
+            for step in 0..300 {
+                if rng.gen_bool(0.5) {
+                    let expected = if std.len() == cap { std.pop_front() } else { None };
+                    std.push_back(step);
+                    assert_eq!(ours.push_back(step), Ok(expected));
+                } else {
+                    let expected = if std.len() == cap { std.pop_back() } else { None };
+                    std.push_front(step);
+                    assert_eq!(ours.push_front(step), Ok(expected));
+                }
+                assert_eq!(ours.capacity(), cap);
+                assert!(ours.iter().eq(std.iter()));
+            }
+        }
+    }
+}
+
+
+// 基于 Vec<Box<dyn Trait>> 的插件注册表
+// vector::multiple_types 最后提到: 特征对象数组更常见，因为可以动态增加类型
+pub mod plugin_registry {
+    use std::any::Any;
+    use std::fmt;
+
+    use super::vector::{IpAddr, V4, V6};
+    use crate::basic_learning::style::dim_dbg;
+
+    /*
+        特征对象只保留了 Plugin 的方法，具体类型的信息已经被擦除了
+        想从 &dyn Plugin 拿回具体类型，需要借助 Any 做向下转型(downcast)
+
+        Any 只能由具体类型转出来，所以给所有 'static 类型统一实现 AsAny，
+        再让 Plugin 继承它，每个插件就不用各自手写 as_any 了
+     */
+    pub trait AsAny {
+        fn as_any(&self) -> &dyn Any;
+        fn as_any_mut(&mut self) -> &mut dyn Any;
+    }
+
+    impl<T: Any> AsAny for T {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    pub trait Plugin: AsAny {
+        fn run(&self);
+
+        // 数值越大越先执行
+        fn priority(&self) -> i32 {
+            0
+        }
+    }
+
+    impl Plugin for V4 {
+        fn run(&self) {
+            self.display()
+        }
+    }
+
+    // 让 ipv6 排在 ipv4 前面
+    impl Plugin for V6 {
+        fn run(&self) {
+            self.display()
+        }
+        fn priority(&self) -> i32 {
+            10
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum RegistryError {
+        // 名称已被占用
+        DuplicateName(String),
+    }
+
+    impl fmt::Display for RegistryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RegistryError::DuplicateName(name) => write!(f, "插件 {name} 已经注册过了"),
+            }
+        }
+    }
+
+    impl std::error::Error for RegistryError {}
+
+    struct Entry {
+        name: String,
+        plugin: Box<dyn Plugin>,
+    }
+
+    /*
+        插件按优先级从高到低保存在 Vec 中，优先级相同的按注册顺序排列
+        插件数量一般很少，按名称查找直接线性扫描即可
+     */
+    #[derive(Default)]
+    pub struct PluginRegistry {
+        entries: Vec<Entry>,
+    }
+
+    impl PluginRegistry {
+        pub fn new() -> Self {
+            PluginRegistry { entries: Vec::new() }
+        }
+
+        // 启动时一次性注册一批插件，遇到重名立即返回错误
+        pub fn with_plugins<I, S>(plugins: I) -> Result<Self, RegistryError>
+        where
+            I: IntoIterator<Item = (S, Box<dyn Plugin>)>,
+            S: Into<String>,
+        {
+            let mut registry = Self::new();
+            for (name, plugin) in plugins {
+                registry.register(name, plugin)?;
+            }
+            Ok(registry)
+        }
+
+        pub fn register(&mut self, name: impl Into<String>, plugin: Box<dyn Plugin>) -> Result<(), RegistryError> {
+            let name = name.into();
+            if self.position(&name).is_some() {
+                return Err(RegistryError::DuplicateName(name));
+            }
+            // 插到第一个优先级更低的插件之前，保持有序
+            let priority = plugin.priority();
+            let index = self.entries.partition_point(|e| e.plugin.priority() >= priority);
+            self.entries.insert(index, Entry { name, plugin });
+            Ok(())
+        }
+
+        pub fn remove(&mut self, name: &str) -> Option<Box<dyn Plugin>> {
+            let index = self.position(name)?;
+            Some(self.entries.remove(index).plugin)
+        }
+
+        pub fn get(&self, name: &str) -> Option<&dyn Plugin> {
+            self.position(name).map(|i| self.entries[i].plugin.as_ref())
+        }
+
+        // 按名称查找并转换为具体类型，类型不匹配时返回 None
+        pub fn get_as<T: Plugin + 'static>(&self, name: &str) -> Option<&T> {
+            self.get(name)?.as_any().downcast_ref::<T>()
+        }
+
+        pub fn get_as_mut<T: Plugin + 'static>(&mut self, name: &str) -> Option<&mut T> {
+            let index = self.position(name)?;
+            // 先解引用出 dyn Plugin，否则会调用到 Box<dyn Plugin> 自身的 as_any_mut
+            self.entries[index].plugin.as_mut().as_any_mut().downcast_mut::<T>()
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        // 按优先级顺序遍历 (名称, 插件)
+        pub fn iter(&self) -> impl Iterator<Item = (&str, &dyn Plugin)> {
+            self.entries.iter().map(|e| (e.name.as_str(), e.plugin.as_ref()))
+        }
+
+        pub fn run_all(&self) {
+            for (name, plugin) in self.iter() {
+                print!("[{name}] ");
+                plugin.run();
+            }
+        }
+
+        fn position(&self, name: &str) -> Option<usize> {
+            self.entries.iter().position(|e| e.name == name)
+        }
+    }
+
+    pub fn run() {
+        // 启动时注册
+        let mut registry = PluginRegistry::with_plugins([
+            ("localhost-v4", Box::new(V4("127.0.0.1".to_string())) as Box<dyn Plugin>),
+            ("localhost-v6", Box::new(V6("::1".to_string()))),
+        ])
+        .unwrap();
+
+        // 运行期间动态增加
+        registry.register("lan-v4", Box::new(V4("192.168.1.1".to_string()))).unwrap();
+        let err = registry.register("lan-v4", Box::new(V4("10.0.0.1".to_string()))).unwrap_err();
+        println!("{err}");
+
+        // V6 的优先级更高，排在最前面，其余按注册顺序
+        registry.run_all();
+
+        // 向下转型拿回具体类型，类型不匹配时得到 None
+        let v4 = registry.get_as::<V4>("localhost-v4").unwrap();
+        dim_dbg!(&v4.0);
+        dim_dbg!(registry.get_as::<V6>("localhost-v4").is_none());
+
+        if let Some(v4) = registry.get_as_mut::<V4>("lan-v4") {
+            v4.0 = "192.168.0.1".to_string();
+        }
+        registry.get("lan-v4").unwrap().run();
+
+        // 移除
+        let removed = registry.remove("localhost-v6").unwrap();
+        removed.run();
+        dim_dbg!(registry.len());
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn v4(addr: &str) -> Box<dyn Plugin> {
+            Box::new(V4(addr.to_string()))
+        }
+
+        fn v6(addr: &str) -> Box<dyn Plugin> {
+            Box::new(V6(addr.to_string()))
+        }
+
+        fn names(registry: &PluginRegistry) -> Vec<&str> {
+            registry.iter().map(|(name, _)| name).collect()
+        }
+
+        #[test]
+        fn ordered_by_priority_then_registration() {
+            let mut registry = PluginRegistry::with_plugins([("a", v4("1.1.1.1")), ("b", v6("::1"))]).unwrap();
+            registry.register("c", v4("2.2.2.2")).unwrap();
+            registry.register("d", v6("::2")).unwrap();
+            // 优先级高的在前，同优先级保持注册顺序
+            assert_eq!(names(&registry), ["b", "d", "a", "c"]);
+        }
+
+        #[test]
+        fn duplicate_name_is_rejected() {
+            let mut registry = PluginRegistry::new();
+            registry.register("lan", v4("192.168.1.1")).unwrap();
+            let err = registry.register("lan", v6("::1")).unwrap_err();
+            assert_eq!(err, RegistryError::DuplicateName("lan".to_string()));
+            // 失败的注册不影响已有插件
+            assert_eq!(registry.len(), 1);
+            assert_eq!(registry.get_as::<V4>("lan").unwrap().0, "192.168.1.1");
+
+            let err = PluginRegistry::with_plugins([("x", v4("1.1.1.1")), ("x", v4("2.2.2.2"))]).err();
+            assert_eq!(err, Some(RegistryError::DuplicateName("x".to_string())));
+        }
+
+        #[test]
+        fn downcast_to_concrete_type() {
+            let mut registry = PluginRegistry::with_plugins([("v4", v4("127.0.0.1")), ("v6", v6("::1"))]).unwrap();
+            assert_eq!(registry.get_as::<V4>("v4").unwrap().0, "127.0.0.1");
+            assert_eq!(registry.get_as::<V6>("v6").unwrap().0, "::1");
+            // 类型不匹配或名称不存在
+            assert!(registry.get_as::<V6>("v4").is_none());
+            assert!(registry.get_as::<V4>("missing").is_none());
+
+            registry.get_as_mut::<V4>("v4").unwrap().0 = "10.0.0.1".to_string();
+            assert_eq!(registry.get_as::<V4>("v4").unwrap().0, "10.0.0.1");
+            assert!(registry.get_as_mut::<V4>("v6").is_none());
+        }
+
+        #[test]
+        fn remove_returns_plugin() {
+            let mut registry = PluginRegistry::with_plugins([("a", v4("1.1.1.1")), ("b", v6("::1"))]).unwrap();
+            let removed = registry.remove("b").unwrap();
+            assert_eq!(removed.priority(), 10);
+            assert!(registry.get("b").is_none());
+            assert!(registry.remove("b").is_none());
+            assert_eq!(names(&registry), ["a"]);
+
+            // 移除后名称可以重新注册
+            registry.register("b", v4("2.2.2.2")).unwrap();
+            assert_eq!(names(&registry), ["a", "b"]);
+            registry.remove("a");
+            registry.remove("b");
+            assert!(registry.is_empty());
+        }
+    }
+}
+
+
+// 基于有序 Vec + 二分查找的集合
+pub mod sorted_vec {
+    use std::borrow::Borrow;
+    use std::cmp::Ordering;
+    use std::ops::{Bound, Deref, Range, RangeBounds};
+
+    use crate::basic_learning::style::dim_dbg;
+    use crate::basic_learning::text_layout::{Align, Table};
+
+    /*
+        数据量不大时，有序数组往往比 BTreeMap / HashMap 更快:
+            - 元素在内存中连续存放，对 CPU 缓存友好
+            - 二分查找只需要 log2(n) 次比较
+        代价是插入和删除需要移动后面的元素，是 O(n) 的
+     */
+
+    // 根据区间计算出切片中对应的下标范围，key 用于从元素中取出用来比较的部分
+    fn range_indices<T, K, Q, R>(items: &[T], key: impl Fn(&T) -> &K, range: R) -> Range<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(x) => items.partition_point(|e| key(e).borrow() < x),
+            Bound::Excluded(x) => items.partition_point(|e| key(e).borrow() <= x),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(x) => items.partition_point(|e| key(e).borrow() <= x),
+            Bound::Excluded(x) => items.partition_point(|e| key(e).borrow() < x),
+            Bound::Unbounded => items.len(),
+        };
+        // 类似 5..3 这样的空区间
+        start..end.max(start)
+    }
+
+    // 允许重复元素的有序数组
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SortedVec<T> {
+        items: Vec<T>,
+    }
+
+    impl<T: Ord> SortedVec<T> {
+        pub fn new() -> Self {
+            SortedVec { items: Vec::new() }
+        }
+
+        // 批量构建: 先整体排序一次，比逐个插入的 O(n²) 快得多
+        pub fn from_unsorted(mut items: Vec<T>) -> Self {
+            items.sort();
+            SortedVec { items }
+        }
+
+        // 插入到所有相等元素之后，返回插入的位置
+        pub fn insert(&mut self, value: T) -> usize {
+            let index = self.items.partition_point(|e| e <= &value);
+            self.items.insert(index, value);
+            index
+        }
+
+        // 删除一个等于 value 的元素
+        pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> Option<T>
+        where
+            T: Borrow<Q>,
+        {
+            let index = self.find(value)?;
+            Some(self.items.remove(index))
+        }
+
+        // 找到任意一个等于 value 的元素的下标
+        pub fn find<Q: Ord + ?Sized>(&self, value: &Q) -> Option<usize>
+        where
+            T: Borrow<Q>,
+        {
+            self.items.binary_search_by(|e| e.borrow().cmp(value)).ok()
+        }
+
+        pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+        {
+            self.find(value).is_some()
+        }
+
+        // 区间查询，直接返回底层切片，不需要额外分配
+        pub fn range<Q, R>(&self, range: R) -> &[T]
+        where
+            T: Borrow<Q>,
+            Q: Ord + ?Sized,
+            R: RangeBounds<Q>,
+        {
+            &self.items[range_indices(&self.items, |e| e, range)]
+        }
+
+        pub fn into_vec(self) -> Vec<T> {
+            self.items
+        }
+    }
+
+    impl<T: Ord> Default for SortedVec<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    // 只提供只读的切片视图，可变访问会破坏有序性
+    impl<T> Deref for SortedVec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] {
+            &self.items
+        }
+    }
+
+    impl<T: Ord> FromIterator<T> for SortedVec<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            Self::from_unsorted(iter.into_iter().collect())
+        }
+    }
+
+    // 不含重复元素的有序集合
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct VecSet<T> {
+        items: Vec<T>,
+    }
+
+    impl<T: Ord> VecSet<T> {
+        pub fn new() -> Self {
+            VecSet { items: Vec::new() }
+        }
+
+        pub fn from_unsorted(mut items: Vec<T>) -> Self {
+            items.sort();
+            items.dedup();
+            VecSet { items }
+        }
+
+        // 已存在时返回 false，与 HashSet::insert 一致
+        pub fn insert(&mut self, value: T) -> bool {
+            match self.items.binary_search(&value) {
+                Ok(_) => false,
+                Err(index) => {
+                    self.items.insert(index, value);
+                    true
+                }
+            }
+        }
+
+        pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+        {
+            match self.items.binary_search_by(|e| e.borrow().cmp(value)) {
+                Ok(index) => {
+                    self.items.remove(index);
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+
+        pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool
+        where
+            T: Borrow<Q>,
+        {
+            self.items.binary_search_by(|e| e.borrow().cmp(value)).is_ok()
+        }
+
+        pub fn range<Q, R>(&self, range: R) -> &[T]
+        where
+            T: Borrow<Q>,
+            Q: Ord + ?Sized,
+            R: RangeBounds<Q>,
+        {
+            &self.items[range_indices(&self.items, |e| e, range)]
+        }
+
+        pub fn into_vec(self) -> Vec<T> {
+            self.items
+        }
+    }
+
+    /*
+        两个有序集合的合并只需要同时从头扫描一遍，O(n + m)
+        keep 决定三种情况下是否保留元素: (只在左边, 两边都有, 只在右边)
+     */
+    impl<T: Ord + Clone> VecSet<T> {
+        fn merge(&self, other: &Self, keep: (bool, bool, bool)) -> Self {
+            let (mut a, mut b) = (self.items.iter().peekable(), other.items.iter().peekable());
+            let mut items = Vec::new();
+            loop {
+                let (x, keep_x) = match (a.peek(), b.peek()) {
+                    (None, None) => break,
+                    (Some(_), None) => (a.next().unwrap(), keep.0),
+                    (None, Some(_)) => (b.next().unwrap(), keep.2),
+                    (Some(x), Some(y)) => match x.cmp(y) {
+                        Ordering::Less => (a.next().unwrap(), keep.0),
+                        Ordering::Greater => (b.next().unwrap(), keep.2),
+                        Ordering::Equal => {
+                            b.next();
+                            (a.next().unwrap(), keep.1)
+                        }
+                    },
+                };
+                if keep_x {
+                    items.push(x.clone());
+                }
+            }
+            VecSet { items }
+        }
+
+        // 并集
+        pub fn union(&self, other: &Self) -> Self {
+            self.merge(other, (true, true, true))
+        }
+
+        // 交集
+        pub fn intersection(&self, other: &Self) -> Self {
+            self.merge(other, (false, true, false))
+        }
+
+        // 差集 self - other
+        pub fn difference(&self, other: &Self) -> Self {
+            self.merge(other, (true, false, false))
+        }
+    }
+
+    impl<T: Ord> Default for VecSet<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Deref for VecSet<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] {
+            &self.items
+        }
+    }
+
+    impl<T: Ord> FromIterator<T> for VecSet<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            Self::from_unsorted(iter.into_iter().collect())
+        }
+    }
+
+    // 按 key 排序的键值对数组
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct VecMap<K, V> {
+        entries: Vec<(K, V)>,
+    }
+
+    impl<K: Ord, V> VecMap<K, V> {
+        pub fn new() -> Self {
+            VecMap { entries: Vec::new() }
+        }
+
+        // key 重复时保留最后一个值，与逐个 insert 的效果一致
+        pub fn from_unsorted(mut entries: Vec<(K, V)>) -> Self {
+            // 稳定排序保证相同 key 的先后顺序不变
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            let mut deduped: Vec<(K, V)> = Vec::with_capacity(entries.len());
+            for entry in entries {
+                match deduped.last_mut() {
+                    Some(last) if last.0 == entry.0 => *last = entry,
+                    _ => deduped.push(entry),
+                }
+            }
+            VecMap { entries: deduped }
+        }
+
+        fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Result<usize, usize>
+        where
+            K: Borrow<Q>,
+        {
+            self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key))
+        }
+
+        // 返回旧值，与 HashMap::insert 一致
+        pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+            match self.search(&key) {
+                Ok(index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+                Err(index) => {
+                    self.entries.insert(index, (key, value));
+                    None
+                }
+            }
+        }
+
+        pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+        where
+            K: Borrow<Q>,
+        {
+            self.search(key).ok().map(|i| &self.entries[i].1)
+        }
+
+        pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+        where
+            K: Borrow<Q>,
+        {
+            let index = self.search(key).ok()?;
+            Some(&mut self.entries[index].1)
+        }
+
+        pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+        where
+            K: Borrow<Q>,
+        {
+            self.search(key).is_ok()
+        }
+
+        pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+        where
+            K: Borrow<Q>,
+        {
+            let index = self.search(key).ok()?;
+            Some(self.entries.remove(index).1)
+        }
+
+        pub fn range<Q, R>(&self, range: R) -> &[(K, V)]
+        where
+            K: Borrow<Q>,
+            Q: Ord + ?Sized,
+            R: RangeBounds<Q>,
+        {
+            &self.entries[range_indices(&self.entries, |(k, _)| k, range)]
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+            self.entries.iter().map(|(k, v)| (k, v))
+        }
+
+        pub fn keys(&self) -> impl Iterator<Item = &K> {
+            self.entries.iter().map(|(k, _)| k)
+        }
+
+        pub fn values(&self) -> impl Iterator<Item = &V> {
+            self.entries.iter().map(|(_, v)| v)
+        }
+    }
+
+    impl<K: Ord, V> Default for VecMap<K, V> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<K: Ord, V> FromIterator<(K, V)> for VecMap<K, V> {
+        fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+            Self::from_unsorted(iter.into_iter().collect())
+        }
+    }
+
+    pub fn run() {
+        // SortedVec 允许重复
+        let mut scores = SortedVec::from_unsorted(vec![88, 60, 95, 72, 60]);
+        scores.insert(80);
+        dim_dbg!(&scores[..]);
+        // 及格到良好之间的分数
+        dim_dbg!(scores.range(60..80));
+        dim_dbg!(scores.remove(&60));
+
+        // VecSet 的集合运算
+        let a: VecSet<char> = "rust语言".chars().collect();
+        let b: VecSet<char> = "rust course".chars().collect();
+        println!("并集: {:?}", &a.union(&b)[..]);
+        println!("交集: {:?}", &a.intersection(&b)[..]);
+        println!("差集: {:?}", &a.difference(&b)[..]);
+
+        // VecMap 可以像 BTreeMap 一样用 &str 查询 String 类型的 key
+        let mut ages: VecMap<String, u32> = [("Sunface", 30), ("Alice", 18), ("Bob", 25), ("Alice", 19)]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        dim_dbg!(ages.get("Alice"));
+        ages.insert("Carol".to_string(), 40);
+        *ages.get_mut("Bob").unwrap() += 1;
+        let b_to_c: Vec<&str> = ages.range::<str, _>((Bound::Included("B"), Bound::Excluded("D"))).iter().map(|(k, _)| k.as_str()).collect();
+        dim_dbg!(b_to_c);
+        dim_dbg!(ages.iter().collect::<Vec<_>>());
+    }
+
+    // 查找性能对比，建议使用 cargo run --release 运行
+    pub fn bench() {
+        use std::collections::{BTreeMap, HashMap};
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        const LOOKUPS: usize = 1_000_000;
+        let mut table = Table::new(&["元素个数", "VecMap", "BTreeMap", "HashMap"]);
+        for column in 0..4 {
+            table = table.align(column, Align::Right);
+        }
+        for n in [8usize, 64, 512, 4096] {
+            // 用乘法打散 key 的顺序，避免构建时就已经有序
+            let keys: Vec<u64> = (0..n as u64).map(|i| i.wrapping_mul(2_654_435_761) % 1_000_003).collect();
+            let vec_map: VecMap<u64, u64> = keys.iter().map(|&k| (k, k)).collect();
+            let btree: BTreeMap<u64, u64> = keys.iter().map(|&k| (k, k)).collect();
+            let hash: HashMap<u64, u64> = keys.iter().map(|&k| (k, k)).collect();
+
+            let time = |lookup: &dyn Fn(&u64) -> Option<u64>| {
+                let start = Instant::now();
+                let mut hits = 0u64;
+                for i in 0..LOOKUPS {
+                    if let Some(v) = lookup(black_box(&keys[i % n])) {
+                        hits = hits.wrapping_add(v);
+                    }
+                }
+                black_box(hits);
+                start.elapsed().as_nanos() as f64 / LOOKUPS as f64
+            };
+
+            let t_vec = time(&|k| vec_map.get(k).copied());
+            let t_btree = time(&|k| btree.get(k).copied());
+            let t_hash = time(&|k| hash.get(k).copied());
+            table.add_row(&[n.to_string(), format!("{t_vec:.1} ns"), format!("{t_btree:.1} ns"), format!("{t_hash:.1} ns")]);
+        }
+        println!("每次查找的平均耗时:\n{table}");
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // 用随机数据与 BTreeSet / BTreeMap 的结果做对比
+        fn check_against_btree(seed: u64) {
+            use rand::{rngs::StdRng, Rng, SeedableRng};
+            use std::collections::{BTreeMap, BTreeSet};
+
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..50 {
+                let xs: Vec<u8> = (0..rng.gen_range(0..60)).map(|_| rng.gen_range(0..50)).collect();
+                let ys: Vec<u8> = (0..rng.gen_range(0..60)).map(|_| rng.gen_range(0..50)).collect();
+                let (a, b) = (VecSet::from_unsorted(xs.clone()), VecSet::from_unsorted(ys.clone()));
+                let (sa, sb): (BTreeSet<u8>, BTreeSet<u8>) = (xs.iter().copied().collect(), ys.iter().copied().collect());
+                assert!(a.union(&b).iter().eq(sa.union(&sb)));
+                assert!(a.intersection(&b).iter().eq(sa.intersection(&sb)));
+                assert!(a.difference(&b).iter().eq(sa.difference(&sb)));
+
+                let (x, y) = (rng.gen_range(0..50), rng.gen_range(0..50));
+                let (lo, hi) = (x.min(y), x.max(y));
+                assert!(a.range(lo..=hi).iter().eq(sa.range(lo..=hi)));
+
+                let map: VecMap<u8, usize> = xs.iter().copied().zip(0..).collect();
+                let btree: BTreeMap<u8, usize> = xs.iter().copied().zip(0..).collect();
+                assert!(map.iter().eq(btree.iter()));
+                assert!(map.range(..hi).iter().map(|(k, v)| (k, v)).eq(btree.range(..hi)));
+            }
+        }
+
+        #[test]
+        fn sorted_vec_keeps_duplicates_in_order() {
+            let mut scores = SortedVec::from_unsorted(vec![88, 60, 95, 72, 60]);
+            // 相等元素插在最后
+            assert_eq!(scores.insert(60), 2);
+            assert_eq!(scores.insert(80), 4);
+            assert_eq!(&scores[..], [60, 60, 60, 72, 80, 88, 95]);
+            assert_eq!(scores.range(60..80), [60, 60, 60, 72]);
+            assert_eq!(scores.range(..), &scores[..]);
+            assert_eq!(scores.range(96..), []);
+
+            assert_eq!(scores.remove(&60), Some(60));
+            assert_eq!(scores.remove(&61), None);
+            assert!(scores.contains(&60));
+            assert!(!scores.contains(&61));
+            assert_eq!(scores.into_vec(), [60, 60, 72, 80, 88, 95]);
+        }
+
+        #[test]
+        fn sorted_vec_borrowed_lookup() {
+            let words: SortedVec<String> = ["pear", "apple", "fig"].iter().map(|s| s.to_string()).collect();
+            assert_eq!(words.find("fig"), Some(1));
+            assert!(words.contains("pear"));
+            assert_eq!(words.range::<str, _>((Bound::Included("b"), Bound::Excluded("g"))), ["fig"]);
+        }
+
+        #[test]
+        fn vec_set_rejects_duplicates() {
+            let mut set = VecSet::from_unsorted(vec![3, 1, 3, 2, 1]);
+            assert_eq!(&set[..], [1, 2, 3]);
+            assert!(!set.insert(2));
+            assert!(set.insert(0));
+            assert!(set.remove(&3));
+            assert!(!set.remove(&3));
+            assert!(set.contains(&0));
+            assert_eq!(set.range(1..), [1, 2]);
+            assert_eq!(set.into_vec(), [0, 1, 2]);
+        }
+
+        #[test]
+        fn vec_set_operations() {
+            let a: VecSet<char> = "rust语言".chars().collect();
+            let b: VecSet<char> = "rust course".chars().collect();
+            assert_eq!(&a.intersection(&b)[..], ['r', 's', 't', 'u']);
+            assert_eq!(&a.difference(&b)[..], ['言', '语']);
+            assert_eq!(&a.union(&b)[..], [' ', 'c', 'e', 'o', 'r', 's', 't', 'u', '言', '语']);
+            // 与空集运算
+            let empty = VecSet::new();
+            assert_eq!(a.union(&empty), a);
+            assert_eq!(a.intersection(&empty), empty);
+            assert_eq!(a.difference(&empty), a);
+        }
+
+        #[test]
+        fn vec_map_basic() {
+            let mut ages: VecMap<String, u32> = [("Sunface", 30), ("Alice", 18), ("Bob", 25), ("Alice", 19)]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect();
+            // 重复 key 保留最后一个值
+            assert_eq!(ages.len(), 3);
+            assert_eq!(ages.get("Alice"), Some(&19));
+            assert_eq!(ages.insert("Carol".to_string(), 40), None);
+            assert_eq!(ages.insert("Carol".to_string(), 41), Some(40));
+            *ages.get_mut("Bob").unwrap() += 1;
+            assert_eq!(ages.get("Bob"), Some(&26));
+            assert!(ages.contains_key("Sunface"));
+            assert!(ages.get_mut("Dave").is_none());
+
+            let b_to_c: Vec<&str> = ages.range::<str, _>((Bound::Included("B"), Bound::Excluded("D"))).iter().map(|(k, _)| k.as_str()).collect();
+            assert_eq!(b_to_c, ["Bob", "Carol"]);
+            assert!(ages.keys().map(String::as_str).eq(["Alice", "Bob", "Carol", "Sunface"]));
+            assert!(ages.values().copied().eq([19, 26, 41, 30]));
+
+            assert_eq!(ages.remove("Alice"), Some(19));
+            assert_eq!(ages.remove("Alice"), None);
+            assert_eq!(ages.len(), 3);
+            assert!(!ages.is_empty());
+        }
+
+        #[test]
+        fn matches_btree() {
+            for seed in 0..8 {
+                check_against_btree(seed);
+            }
+        }
+    }
+}
+
+// 字符串驻留: 相同的字符串只保存一份，用一个 4 字节的 Symbol 代替
+// 比较两个 Symbol 只需要比较整数，适合大量重复的标识符、标签、作者名等
+pub mod interner {
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashMap;
+    use std::hash::BuildHasher;
+    use std::mem::{size_of, size_of_val};
+    use std::ops::Index;
+    use std::sync::{Arc, RwLock};
+
+    use crate::basic_learning::style::dim_dbg;
+    use crate::basic_learning::text_layout::{Align, Table};
+
+    // 字符串在驻留器中的编号，只在创建它的驻留器中有意义
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Symbol(u32);
+
+    impl Symbol {
+        pub fn index(self) -> usize {
+            self.0 as usize
+        }
+    }
+
+    // 哈希表中的空位
+    const EMPTY: u32 = u32::MAX;
+
+    /*
+        单线程版本，所有字符串首尾相接存放在同一个 String 中
+            buf     "Rust语言简介SunfaceRust棒极了!"
+            spans   [(0, 16), (16, 23), (23, 37)]   第 n 个 Symbol 在 buf 中的范围
+            slots   开放寻址的哈希表，存放 Symbol 的编号，用来根据字符串查找 Symbol
+        HashMap<&str, Symbol> 的 key 需要借用 buf，会变成自引用结构，所以这里手写了一个只存编号的哈希表
+     */
+    #[derive(Debug, Default)]
+    pub struct Interner {
+        buf: String,
+        spans: Vec<(u32, u32)>,
+        // 每个 Symbol 的哈希值，扩容时不必重新计算
+        hashes: Vec<u64>,
+        slots: Vec<u32>,
+        hasher: RandomState,
+    }
+
+    impl Interner {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn len(&self) -> usize {
+            self.spans.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.spans.is_empty()
+        }
+
+        // 线性探测，返回 s 所在的槽位，或者应该插入的空槽位
+        fn find_slot(&self, s: &str, hash: u64) -> usize {
+            let mask = self.slots.len() - 1;
+            let mut i = hash as usize & mask;
+            loop {
+                let id = self.slots[i];
+                if id == EMPTY || (self.hashes[id as usize] == hash && self.resolve(Symbol(id)) == s) {
+                    return i;
+                }
+                i = (i + 1) & mask;
+            }
+        }
+
+        // 负载超过一半时容量翻倍，容量始终是 2 的幂
+        fn grow(&mut self) {
+            let capacity = (self.slots.len() * 2).max(16);
+            self.slots = vec![EMPTY; capacity];
+            for (id, &hash) in self.hashes.iter().enumerate() {
+                let mut i = hash as usize & (capacity - 1);
+                while self.slots[i] != EMPTY {
+                    i = (i + 1) & (capacity - 1);
+                }
+                self.slots[i] = id as u32;
+            }
+        }
+
+        pub fn get(&self, s: &str) -> Option<Symbol> {
+            if self.slots.is_empty() {
+                return None;
+            }
+            match self.slots[self.find_slot(s, self.hasher.hash_one(s))] {
+                EMPTY => None,
+                id => Some(Symbol(id)),
+            }
+        }
+
+        // 已经存在时返回原来的 Symbol，否则保存一份并分配新的 Symbol
+        pub fn intern(&mut self, s: &str) -> Symbol {
+            if (self.len() + 1) * 2 > self.slots.len() {
+                self.grow();
+            }
+            let hash = self.hasher.hash_one(s);
+            let slot = self.find_slot(s, hash);
+            if self.slots[slot] != EMPTY {
+                return Symbol(self.slots[slot]);
+            }
+            let id = u32::try_from(self.len()).ok().filter(|&id| id != EMPTY).expect("too many symbols");
+            // 先检查再写入，失败时不会留下半截数据
+            let (start, end) = span(self.buf.len(), s.len()).expect("interner buffer too large");
+            self.buf.push_str(s);
+            self.spans.push((start, end));
+            self.hashes.push(hash);
+            self.slots[slot] = id;
+            Symbol(id)
+        }
+
+        pub fn resolve(&self, symbol: Symbol) -> &str {
+            let (start, end) = self.spans[symbol.index()];
+            &self.buf[start as usize..end as usize]
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+            (0..self.len() as u32).map(|id| (Symbol(id), self.resolve(Symbol(id))))
+        }
+
+        // 占用的堆内存字节数
+        pub fn memory_usage(&self) -> usize {
+            self.buf.capacity()
+                + self.spans.capacity() * size_of::<(u32, u32)>()
+                + self.hashes.capacity() * size_of::<u64>()
+                + self.slots.capacity() * size_of::<u32>()
+        }
+    }
+
+    // 用 u32 记录区间，字符串总长度不能超过 4 GiB
+    fn span(start: usize, len: usize) -> Option<(u32, u32)> {
+        let end = start.checked_add(len)?;
+        Some((u32::try_from(start).ok()?, u32::try_from(end).ok()?))
+    }
+
+    impl Index<Symbol> for Interner {
+        type Output = str;
+        fn index(&self, symbol: Symbol) -> &str {
+            self.resolve(symbol)
+        }
+    }
+
+    /*
+        多线程版本，用 RwLock 保护
+            - 查找已有的字符串只需要读锁，多个线程可以同时进行
+            - 只有插入新字符串时才需要写锁
+        持有锁时不能把 &str 借出去，所以字符串用 Arc<str> 保存，resolve 返回一个 Arc 的克隆
+     */
+    #[derive(Debug, Default)]
+    pub struct SyncInterner {
+        inner: RwLock<SyncInner>,
+    }
+
+    #[derive(Debug, Default)]
+    struct SyncInner {
+        map: HashMap<Arc<str>, Symbol>,
+        strings: Vec<Arc<str>>,
+    }
+
+    impl SyncInterner {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn len(&self) -> usize {
+            self.inner.read().unwrap().strings.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn get(&self, s: &str) -> Option<Symbol> {
+            self.inner.read().unwrap().map.get(s).copied()
+        }
+
+        pub fn intern(&self, s: &str) -> Symbol {
+            if let Some(symbol) = self.get(s) {
+                return symbol;
+            }
+            let mut inner = self.inner.write().unwrap();
+            // 释放读锁到拿到写锁之间，其它线程可能已经插入了同样的字符串
+            if let Some(&symbol) = inner.map.get(s) {
+                return symbol;
+            }
+            let symbol = Symbol(u32::try_from(inner.strings.len()).expect("too many symbols"));
+            let s: Arc<str> = Arc::from(s);
+            inner.strings.push(Arc::clone(&s));
+            inner.map.insert(s, symbol);
+            symbol
+        }
+
+        pub fn resolve(&self, symbol: Symbol) -> Arc<str> {
+            Arc::clone(&self.inner.read().unwrap().strings[symbol.index()])
+        }
+
+        // 粗略估计: 每个字符串一次 Arc 分配(两个计数 + 内容)，加上 Vec 和 HashMap 的表
+        pub fn memory_usage(&self) -> usize {
+            let inner = self.inner.read().unwrap();
+            let strings: usize = inner.strings.iter().map(|s| 2 * size_of::<usize>() + s.len()).sum();
+            strings
+                + inner.strings.capacity() * size_of::<Arc<str>>()
+                + inner.map.capacity() * (size_of::<Arc<str>>() + size_of::<Symbol>())
+        }
+    }
+
+    // 随机生成一段标识符流，少数常用词出现得最多
+    fn token_stream(count: usize, seed: u64) -> Vec<String> {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        const KEYWORDS: [&str; 8] = ["let", "fn", "mut", "impl", "pub", "struct", "Rust语言简介", "Sunface"];
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| match rng.gen_range(0..10) {
+                0..=5 => KEYWORDS[rng.gen_range(0..KEYWORDS.len())].to_string(),
+                6..=8 => format!("variable_{}", rng.gen_range(0..500)),
+                _ => format!("rare_identifier_{}", rng.gen_range(0..20_000)),
+            })
+            .collect()
+    }
+
+    // 比较直接保存 Vec<String> 和保存 Vec<Symbol> + 驻留器的内存占用
+    pub fn memory_report(tokens: &[String]) -> Table {
+        let cloned = size_of_val(tokens) + tokens.iter().map(String::capacity).sum::<usize>();
+
+        let mut interner = Interner::new();
+        let symbols: Vec<Symbol> = tokens.iter().map(|t| interner.intern(t)).collect();
+        let interned = symbols.capacity() * size_of::<Symbol>() + interner.memory_usage();
+
+        let sync = SyncInterner::new();
+        let sync_symbols: Vec<Symbol> = tokens.iter().map(|t| sync.intern(t)).collect();
+        let sync_interned = sync_symbols.capacity() * size_of::<Symbol>() + sync.memory_usage();
+
+        let mut table = Table::new(&["存储方式", "字符串个数", "内存(KB)", "相对大小"]);
+        for column in 1..4 {
+            table = table.align(column, Align::Right);
+        }
+        for (name, count, bytes) in [
+            ("Vec<String>", tokens.len(), cloned),
+            ("Interner", interner.len(), interned),
+            ("SyncInterner", sync.len(), sync_interned),
+        ] {
+            let ratio = bytes as f64 / cloned as f64 * 100.0;
+            table.add_row(&[name.to_string(), count.to_string(), format!("{:.1}", bytes as f64 / 1024.0), format!("{ratio:.1}%")]);
+        }
+        table
+    }
+
+    pub fn run() {
+        // trait_test 的 run_1 ~ run_4 中反复出现的字面量
+        let mut interner = Interner::new();
+        let titles: Vec<Symbol> = (0..4).map(|_| interner.intern("Rust语言简介")).collect();
+        let author = interner.intern("Sunface");
+        // 大小写不同就是不同的字符串
+        let username = interner.intern("sunface");
+        println!("{titles:?} {author:?} {username:?}");
+        dim_dbg!(&interner[titles[0]]);
+        println!("{:?}", interner.iter().collect::<Vec<_>>());
+
+        println!("{}", memory_report(&token_stream(100_000, 2)));
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn same_string_same_symbol() {
+            let mut interner = Interner::new();
+            let titles: Vec<Symbol> = (0..4).map(|_| interner.intern("Rust语言简介")).collect();
+            let author = interner.intern("Sunface");
+            // 大小写不同就是不同的字符串
+            let username = interner.intern("sunface");
+            assert!(titles.iter().all(|&s| s == titles[0]));
+            assert_ne!(author, username);
+            assert_eq!(interner.len(), 3);
+            // 编号按首次出现的顺序分配
+            assert_eq!((titles[0], author, username), (Symbol(0), Symbol(1), Symbol(2)));
+            assert_eq!(interner.intern(""), Symbol(3));
+            assert_eq!(interner.get("Sunface"), Some(author));
+            assert_eq!(interner.get("Rust"), None);
+            assert_eq!(Interner::new().get("Rust"), None);
+        }
+
+        #[test]
+        fn symbols_stay_stable_while_growing() {
+            let mut interner = Interner::new();
+            let first = interner.intern("first");
+            // 多次扩容之后，已有的 Symbol 仍然指向原来的字符串
+            for i in 0..10_000 {
+                interner.intern(&format!("s{i}"));
+            }
+            assert_eq!(interner.intern("first"), first);
+            assert_eq!(&interner[first], "first");
+            assert_eq!(interner.get("s9999"), Some(Symbol(10_000)));
+        }
+
+        #[test]
+        fn resolve_round_trip() {
+            // 与 HashMap<&str, Symbol> 做对比
+            let tokens = token_stream(20_000, 1);
+            let mut expected: HashMap<&str, Symbol> = HashMap::new();
+            let mut interner = Interner::new();
+            for token in &tokens {
+                let symbol = interner.intern(token);
+                assert_eq!(*expected.entry(token).or_insert(symbol), symbol, "{token}");
+                assert_eq!(interner.resolve(symbol), token);
+            }
+            assert_eq!(interner.len(), expected.len());
+            assert!(interner.iter().all(|(symbol, s)| expected[s] == symbol));
+        }
+
+        #[test]
+        fn threads_agree_on_symbols() {
+            // 多个线程同时驻留，同一个字符串必须得到同一个 Symbol
+            let tokens = token_stream(20_000, 1);
+            let sync = SyncInterner::new();
+            let results: Vec<Vec<Symbol>> = std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| tokens.iter().map(|t| sync.intern(t)).collect())).collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            });
+            assert!(results.iter().all(|r| r == &results[0]));
+            assert!(results[0].iter().zip(&tokens).all(|(&s, t)| &*sync.resolve(s) == t));
+
+            let mut interner = Interner::new();
+            tokens.iter().for_each(|t| {
+                interner.intern(t);
+            });
+            assert_eq!(sync.len(), interner.len());
+        }
+
+        #[test]
+        fn span_must_fit_in_u32() {
+            let max = u32::MAX as usize;
+            assert_eq!(span(0, 5), Some((0, 5)));
+            assert_eq!(span(max - 5, 5), Some((u32::MAX - 5, u32::MAX)));
+            // 终点或起点超出 u32，或者相加溢出 usize
+            assert_eq!(span(max - 5, 6), None);
+            assert_eq!(span(max + 1, 0), None);
+            assert_eq!(span(usize::MAX, 1), None);
+        }
+
+        #[test]
+        fn interning_saves_memory() {
+            let table = memory_report(&token_stream(1_000_000, 2)).to_string();
+            // 取出每一行最后一列的相对大小
+            let ratio = |name: &str| -> f64 {
+                let row = table.lines().find(|l| l.contains(&format!(" {name} "))).unwrap();
+                let cells: Vec<&str> = row.split('|').map(str::trim).collect();
+                cells[cells.len() - 2].trim_end_matches('%').parse().unwrap()
+            };
+            assert_eq!(ratio("Vec<String>"), 100.0);
+            assert!(ratio("Interner") < 20.0, "{table}");
+            assert!(ratio("SyncInterner") < 20.0, "{table}");
+        }
+    }
+}
@@ -0,0 +1,606 @@
+// 颜色
+/*
+    在 struct_test::Rgb(u8, u8, u8) 的基础上实现常用的颜色运算:
+        - Hsv / Hsl       色相用角度 [0, 360)，饱和度、明度、亮度用 [0, 1] 的小数
+                          用 f64 保存，RGB → HSV/HSL → RGB 可以原样还原
+        - 十六进制        #rrggbb 和 #rgb，Display 输出 #rrggbb
+        - CSS 颜色名      "rebeccapurple".parse::<Rgb>()，以及反查最接近的颜色名
+        - 混合            在 sRGB 空间或线性空间按比例混合
+        - 对比度          按 WCAG 2 的相对亮度公式计算，4.5 以上适合正文
+        - ANSI 256 色     找出终端 256 色调色板中最接近的颜色
+ */
+use std::fmt;
+use std::str::FromStr;
+
+use crate::basic_learning::compound_type::struct_test::Rgb;
+use crate::basic_learning::style::Style;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f64,
+    pub s: f64,
+    pub v: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    InvalidHex(String),
+    UnknownName(String),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::InvalidHex(s) => write!(f, "无效的十六进制颜色: {}", s),
+            ColorError::UnknownName(s) => write!(f, "未知的颜色名: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+// 把 [0, 1] 的小数换算回 0..=255
+fn to_channel(x: f64) -> u8 {
+    (x * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+// RGB 三个分量归一化后的最大值、最小值和色相，HSV 和 HSL 共用
+fn hue_max_min(rgb: Rgb) -> (f64, f64, f64) {
+    let (r, g, b) = (rgb.0 as f64 / 255.0, rgb.1 as f64 / 255.0, rgb.2 as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+    let h = if d == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / d)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    (h.rem_euclid(360.0), max, min)
+}
+
+// 由色相、色度(chroma) 和偏移量还原 RGB
+fn from_hue_chroma(h: f64, c: f64, m: f64) -> Rgb {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    Rgb(to_channel(r + m), to_channel(g + m), to_channel(b + m))
+}
+
+impl From<Rgb> for Hsv {
+    fn from(rgb: Rgb) -> Self {
+        let (h, max, min) = hue_max_min(rgb);
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        Hsv { h, s, v: max }
+    }
+}
+
+impl From<Hsv> for Rgb {
+    fn from(hsv: Hsv) -> Self {
+        let c = hsv.v * hsv.s;
+        from_hue_chroma(hsv.h, c, hsv.v - c)
+    }
+}
+
+impl From<Rgb> for Hsl {
+    fn from(rgb: Rgb) -> Self {
+        let (h, max, min) = hue_max_min(rgb);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let s = if d == 0.0 { 0.0 } else { d / (1.0 - (2.0 * l - 1.0).abs()) };
+        Hsl { h, s, l }
+    }
+}
+
+impl From<Hsl> for Rgb {
+    fn from(hsl: Hsl) -> Self {
+        let c = (1.0 - (2.0 * hsl.l - 1.0).abs()) * hsl.s;
+        from_hue_chroma(hsl.h, c, hsl.l - c / 2.0)
+    }
+}
+
+impl From<Hsv> for Hsl {
+    fn from(hsv: Hsv) -> Self {
+        let l = hsv.v * (1.0 - hsv.s / 2.0);
+        let s = if l == 0.0 || l == 1.0 { 0.0 } else { (hsv.v - l) / l.min(1.0 - l) };
+        Hsl { h: hsv.h, s, l }
+    }
+}
+
+impl From<Hsl> for Hsv {
+    fn from(hsl: Hsl) -> Self {
+        let v = hsl.l + hsl.s * hsl.l.min(1.0 - hsl.l);
+        let s = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - hsl.l / v) };
+        Hsv { h: hsl.h, s, v }
+    }
+}
+
+impl fmt::Display for Hsv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hsv({:.0}, {:.0}%, {:.0}%)", self.h, self.s * 100.0, self.v * 100.0)
+    }
+}
+
+impl fmt::Display for Hsl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hsl({:.0}, {:.0}%, {:.0}%)", self.h, self.s * 100.0, self.l * 100.0)
+    }
+}
+
+// sRGB 分量与线性光强之间的转换
+fn to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(l: f64) -> u8 {
+    let c = if l <= 0.0031308 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    };
+    to_channel(c)
+}
+
+impl Rgb {
+    pub fn from_u32(rgb: u32) -> Rgb {
+        Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    pub fn to_u32(self) -> u32 {
+        (self.0 as u32) << 16 | (self.1 as u32) << 8 | self.2 as u32
+    }
+
+    // 支持 #rrggbb 和 #rgb，# 可以省略
+    pub fn from_hex(s: &str) -> Result<Rgb, ColorError> {
+        let err = || ColorError::InvalidHex(s.to_string());
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(err());
+        }
+        let n = u32::from_str_radix(digits, 16).map_err(|_| err())?;
+        match digits.len() {
+            6 => Ok(Rgb::from_u32(n)),
+            // #abc 等价于 #aabbcc
+            3 => {
+                let expand = |d: u32| (d * 17) as u8;
+                Ok(Rgb(expand(n >> 8), expand(n >> 4 & 0xf), expand(n & 0xf)))
+            }
+            _ => Err(err()),
+        }
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:06x}", self.to_u32())
+    }
+
+    pub fn to_hsv(self) -> Hsv {
+        Hsv::from(self)
+    }
+
+    pub fn to_hsl(self) -> Hsl {
+        Hsl::from(self)
+    }
+
+    // 按 CSS 颜色名查找，不区分大小写
+    pub fn named(name: &str) -> Option<Rgb> {
+        let name = name.to_ascii_lowercase();
+        CSS_COLORS
+            .binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+            .ok()
+            .map(|i| Rgb::from_u32(CSS_COLORS[i].1))
+    }
+
+    // 精确匹配的颜色名，gray/grey 这类同义词返回字母序靠前的那个
+    pub fn name(self) -> Option<&'static str> {
+        let rgb = self.to_u32();
+        CSS_COLORS.iter().find(|(_, c)| *c == rgb).map(|(n, _)| *n)
+    }
+
+    // 最接近的颜色名，用于给任意颜色一个大概的描述
+    pub fn nearest_name(self) -> &'static str {
+        CSS_COLORS
+            .iter()
+            .min_by_key(|(_, c)| distance(self, Rgb::from_u32(*c)))
+            .map(|(n, _)| *n)
+            .unwrap()
+    }
+
+    // 在 sRGB 空间中按比例混合，t = 0 得到 self，t = 1 得到 other
+    pub fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(lerp(self.0, other.0), lerp(self.1, other.1), lerp(self.2, other.2))
+    }
+
+    // 在线性空间中混合，更接近两种光实际叠加的效果，中间色不会偏暗
+    pub fn mix_linear(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| from_linear(to_linear(a) + (to_linear(b) - to_linear(a)) * t);
+        Rgb(lerp(self.0, other.0), lerp(self.1, other.1), lerp(self.2, other.2))
+    }
+
+    // WCAG 2 相对亮度，0 为黑，1 为白
+    pub fn luminance(self) -> f64 {
+        0.2126 * to_linear(self.0) + 0.7152 * to_linear(self.1) + 0.0722 * to_linear(self.2)
+    }
+
+    // 对比度在 1 到 21 之间，与参数顺序无关
+    pub fn contrast_ratio(self, other: Rgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    // 在这个背景上用黑字还是白字更清楚
+    pub fn readable_text(self) -> Rgb {
+        let (black, white) = (Rgb(0, 0, 0), Rgb(255, 255, 255));
+        if self.contrast_ratio(black) >= self.contrast_ratio(white) {
+            black
+        } else {
+            white
+        }
+    }
+
+    // 最接近的 ANSI 256 色
+    // 0~15 的基本色在不同终端的主题中差别很大，所以只在 16~255 中找
+    pub fn to_ansi256(self) -> u8 {
+        (16..=255u8)
+            .min_by_key(|&n| distance(self, ansi256_to_rgb(n)))
+            .unwrap()
+    }
+}
+
+// 颜色之间的距离，按人眼对红绿蓝的敏感程度加权 ("redmean" 近似)
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let mean = (a.0 as i32 + b.0 as i32) / 2;
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    ((((512 + mean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean) * db * db) >> 8)) as u32
+}
+
+// xterm 的 256 色调色板:
+//     0~15     基本色和高亮色
+//     16~231   6x6x6 的颜色立方体，每个分量取 0, 95, 135, 175, 215, 255
+//     232~255  24 级灰度，从 8 到 238
+pub fn ansi256_to_rgb(n: u8) -> Rgb {
+    const BASIC: [u32; 16] = [
+        0x000000, 0x800000, 0x008000, 0x808000, 0x000080, 0x800080, 0x008080, 0xc0c0c0,
+        0x808080, 0xff0000, 0x00ff00, 0xffff00, 0x0000ff, 0xff00ff, 0x00ffff, 0xffffff,
+    ];
+    match n {
+        0..=15 => Rgb::from_u32(BASIC[n as usize]),
+        16..=231 => {
+            let level = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+            let i = n - 16;
+            Rgb(level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (n - 232) * 10;
+            Rgb(gray, gray, gray)
+        }
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+// "#ff8800"、"#f80" 或 CSS 颜色名
+impl FromStr for Rgb {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('#') {
+            Rgb::from_hex(s)
+        } else {
+            Rgb::named(s).ok_or_else(|| ColorError::UnknownName(s.to_string()))
+        }
+    }
+}
+
+// CSS Color Module Level 4 定义的全部 148 个颜色名，按字母序排列，用于二分查找
+const CSS_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+pub fn run() {
+    let orange: Rgb = "#f80".parse().unwrap();
+    println!("{orange} 最接近的颜色名: {}", orange.nearest_name());
+    let blue = Rgb(0, 128, 255);
+    println!("{} = {} = {}", blue, blue.to_hsl(), blue.to_hsv());
+
+    // 线性空间中一半的光强对应 sRGB 的 188，看起来比 128 亮
+    let (black, white) = (Rgb(0, 0, 0), Rgb(255, 255, 255));
+    println!("black + white: {} (sRGB) / {} (linear)", black.mix(white, 0.5), black.mix_linear(white, 0.5));
+    println!("red + blue: {} (sRGB) / {} (linear)", Rgb(255, 0, 0).mix(Rgb(0, 0, 255), 0.5), Rgb(255, 0, 0).mix_linear(Rgb(0, 0, 255), 0.5));
+    // #767676 是白底上能达到 4.5:1 的最浅灰色
+    println!("#767676 在白底上的对比度 {:.2}:1", Rgb(0x76, 0x76, 0x76).contrast_ratio(white));
+
+    // 在终端中画出色板，文字颜色按对比度选择
+    // 经过 Style::paint 输出，不是终端或者设置了 NO_COLOR 时只输出文字
+    for name in ["crimson", "darkorange", "gold", "seagreen", "steelblue", "rebeccapurple", "gainsboro"] {
+        let rgb = Rgb::named(name).unwrap();
+        let swatch = Style::new().bg(rgb).fg(rgb.readable_text()).paint(&format!(" {:<14} ", name));
+        println!("{} {} {} ansi {}", swatch, rgb, rgb.to_hsl(), rgb.to_ansi256());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = Rgb(0, 0, 0);
+    const WHITE: Rgb = Rgb(255, 255, 255);
+
+    // 颜色名表必须有序，二分查找才正确
+    #[test]
+    fn css_table_is_sorted() {
+        assert_eq!(CSS_COLORS.len(), 148);
+        assert!(CSS_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn parse_hex() {
+        let orange: Rgb = "#ff8800".parse().unwrap();
+        assert_eq!(orange, Rgb(255, 136, 0));
+        assert_eq!("#f80".parse::<Rgb>().unwrap(), orange);
+        assert_eq!(Rgb::from_hex("FF8800").unwrap(), orange);
+        assert_eq!(orange.to_string(), "#ff8800");
+        assert!(matches!("#ff88".parse::<Rgb>(), Err(ColorError::InvalidHex(_))));
+        assert!(matches!("#+f8800".parse::<Rgb>(), Err(ColorError::InvalidHex(_))));
+        assert!(matches!("blurple".parse::<Rgb>(), Err(ColorError::UnknownName(_))));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("RebeccaPurple".parse::<Rgb>().unwrap(), Rgb(0x66, 0x33, 0x99));
+        assert_eq!(Rgb(128, 128, 128).name(), Some("gray"));
+        assert_eq!(Rgb(1, 2, 3).name(), None);
+        assert_eq!(Rgb(250, 10, 5).nearest_name(), "red");
+        assert_eq!(Rgb(255, 140, 10).nearest_name(), "darkorange");
+    }
+
+    // 已知的换算结果
+    #[test]
+    fn known_conversions() {
+        let hsv = Rgb(255, 0, 0).to_hsv();
+        assert_eq!((hsv.h, hsv.s, hsv.v), (0.0, 1.0, 1.0));
+        let hsl = Rgb(0, 128, 255).to_hsl();
+        assert!((hsl.h - 209.88).abs() < 0.01 && hsl.s == 1.0 && hsl.l == 0.5);
+        assert_eq!(Rgb::from(Hsl { h: 120.0, s: 1.0, l: 0.25 }), Rgb(0, 128, 0));
+        // match_pattern::all_patterns::g 中的 Color::Hsv(0, 160, 255)，按 0~255 的刻度理解
+        assert_eq!(Rgb::from(Hsv { h: 0.0, s: 160.0 / 255.0, v: 1.0 }), Rgb(255, 95, 95));
+        // 色相超出范围时按圆周处理
+        assert_eq!(Rgb::from(Hsv { h: 480.0, s: 1.0, v: 1.0 }), Rgb::from(Hsv { h: 120.0, s: 1.0, v: 1.0 }));
+    }
+
+    // 按不同的步长取样，覆盖每个通道的大部分取值
+    fn samples() -> impl Iterator<Item = Rgb> {
+        (0..=255u8).step_by(3).flat_map(|r| {
+            (0..=255u8).step_by(5).flat_map(move |g| (0..=255u8).step_by(7).map(move |b| Rgb(r, g, b)))
+        })
+    }
+
+    // RGB → HSV/HSL → RGB 必须原样还原，HSV ↔ HSL 之间也一样
+    #[test]
+    fn hsv_hsl_round_trip() {
+        for rgb in samples() {
+            assert_eq!(Rgb::from(rgb.to_hsv()), rgb);
+            assert_eq!(Rgb::from(rgb.to_hsl()), rgb);
+            assert_eq!(Rgb::from(Hsl::from(rgb.to_hsv())), rgb);
+            assert_eq!(Rgb::from(Hsv::from(rgb.to_hsl())), rgb);
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        for rgb in samples() {
+            assert_eq!(Rgb::from_hex(&rgb.to_hex()).unwrap(), rgb);
+            assert_eq!(Rgb::from_u32(rgb.to_u32()), rgb);
+        }
+    }
+
+    #[test]
+    fn mix() {
+        assert_eq!(BLACK.mix(WHITE, 0.5), Rgb(128, 128, 128));
+        assert_eq!(BLACK.mix(WHITE, 0.0), BLACK);
+        assert_eq!(BLACK.mix(WHITE, 2.0), WHITE);
+        assert_eq!(BLACK.mix_linear(WHITE, 0.5), Rgb(188, 188, 188));
+    }
+
+    #[test]
+    fn contrast() {
+        assert!((BLACK.contrast_ratio(WHITE) - 21.0).abs() < 1e-9);
+        assert_eq!(WHITE.contrast_ratio(WHITE), 1.0);
+        assert!(Rgb(0x76, 0x76, 0x76).contrast_ratio(WHITE) >= 4.5);
+        assert!(Rgb(0x77, 0x77, 0x77).contrast_ratio(WHITE) < 4.5);
+        assert_eq!(Rgb::named("navy").unwrap().readable_text(), WHITE);
+        assert_eq!(Rgb::named("gold").unwrap().readable_text(), BLACK);
+    }
+
+    #[test]
+    fn ansi256() {
+        assert_eq!(ansi256_to_rgb(196), Rgb(255, 0, 0));
+        assert_eq!(ansi256_to_rgb(232), Rgb(8, 8, 8));
+        assert_eq!(ansi256_to_rgb(255), Rgb(238, 238, 238));
+        for n in 16..=255u8 {
+            // 调色板中的颜色映射回同一个颜色
+            let back = ansi256_to_rgb(n).to_ansi256();
+            assert_eq!(ansi256_to_rgb(back), ansi256_to_rgb(n), "ansi {n}");
+        }
+        assert_eq!(Rgb(250, 5, 5).to_ansi256(), 196);
+        assert_eq!(Rgb(100, 100, 100).to_ansi256(), 241);
+    }
+}
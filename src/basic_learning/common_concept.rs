@@ -22,6 +22,7 @@ pub fn concept() {
     编译器能够在编译时[计算一组有限的操作]
     这使我们可以选择以更容易理解和验证的方式写出此值
     在声明它的[作用域]之中，常量在整个程序生命周期中都有效
+    各种命名风格之间的转换见 naming 模块
     */
     const MILLISECONDS_IN_A_DAY: u32 = 1000 * 60 * 60 * 24;
     println!("1天等于{MILLISECONDS_IN_A_DAY} 毫秒");
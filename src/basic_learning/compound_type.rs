@@ -1,5 +1,7 @@
 // 字符串
 pub mod str {
+    use crate::basic_learning::style::dim_dbg;
+
     // 转换
     pub fn conversion() {
         // 将 &str 类型转为 String 类型
@@ -32,37 +34,38 @@ pub mod str {
     // 插入 str
     pub fn insert() {
         let mut s = String::from("HelloRust!");
-        dbg!(&s);
+        dim_dbg!(&s);
         s.insert(5, ' ');
-        dbg!(&s);
+        dim_dbg!(&s);
         s.insert_str(6, "一下插入很多内容");
-        dbg!(&s);
+        dim_dbg!(&s);
     }
 
     // 替换 str
     pub fn replace() {
         // 该方法是返回一个新的字符串，而不是操作原来的字符串。
         let initial = String::from("Hello rust! rust! rust! rust!");
-        dbg!(&initial);
+        dim_dbg!(&initial);
 
 
         // 第一个参数是要被替换的字符串，第二个参数是新的字符串
         // 适用于 String 和 &str
         let s1 = initial.replace("rust", "RUST");
-        dbg!(&s1);
+        dim_dbg!(&s1);
 
 
         // 第三个参数则表示替换的个数
         // 适用于 String 和 &str
         let s2 = initial.replacen("rust", "RUST", 2);
-        dbg!(&s2);
+        dim_dbg!(&s2);
+        // 按模式而不是固定字符串替换，可以使用 regex 模块中的 Regex::replace_all / replacen
 
 
         // 仅适用于 String
         // 直接操作原来的字符串  需要使用 mut 关键字修饰
         let mut s3 = String::from("I like rust!");
         s3.replace_range(7..8, "R");
-        dbg!(&s3);
+        dim_dbg!(&s3);
     }
 
     // 删除 str
@@ -76,12 +79,12 @@ pub mod str {
         // 返回值是一个 Option 类型，如果字符串为空，则返回 None。
         fn test_pop() {
             let mut string_pop = String::from("rust pop 中文!");
-            dbg!(&string_pop);
+            dim_dbg!(&string_pop);
             let p1 = string_pop.pop();
-            dbg!(&p1);
+            dim_dbg!(&p1);
             let p2 = string_pop.pop();
-            dbg!(&p2);
-            dbg!(&string_pop);
+            dim_dbg!(&p2);
+            dim_dbg!(&string_pop);
         }
 
         // remove
@@ -93,17 +96,18 @@ pub mod str {
                 "占 {} 个字节",
                 std::mem::size_of_val(string_remove.as_str())
             );
-            dbg!(&string_remove);
+            dim_dbg!(&string_remove);
             // 删除第一个汉字
             let s1 = string_remove.remove(0);
-            dbg!(&s1);
+            dim_dbg!(&s1);
 
             // let r2 = string_remove.remove(1); // 报错  非法字符边界
+            // 按字符下标删除可以使用 utf8_edit::Utf8Edit::remove_at
             // 直接删除第二个汉字
             let r3 = string_remove.remove(3);
-            dbg!(&r3);
+            dim_dbg!(&r3);
 
-            dbg!(&string_remove);
+            dim_dbg!(&string_remove);
         }
 
 
@@ -111,17 +115,17 @@ pub mod str {
         // 无返回值
         fn test_truncate() {
             let mut string_truncate = String::from("测试truncate");
-            dbg!(&string_truncate);
+            dim_dbg!(&string_truncate);
             string_truncate.truncate(3);
-            dbg!(&string_truncate);
+            dim_dbg!(&string_truncate);
         }
 
         // clear
         fn test_clear() {
             let mut string_clear = String::from("string clear");
-            dbg!(&string_clear);
+            dim_dbg!(&string_clear);
             string_clear.clear();
-            dbg!(&string_clear);
+            dim_dbg!(&string_clear);
         }
         test_clear()
     }
@@ -170,22 +174,1042 @@ pub mod str {
         for b in "字符串".bytes() {
             println!("{}", b);
         }
+
+        // 把字符和它们的字节对应起来看
+        println!("{}", inspect::inspect("字符串"));
+    }
+
+    // UTF-8 编码查看器
+    pub mod inspect {
+        use std::fmt;
+        use std::io::{self, Read};
+        use std::ops::Range;
+
+        use crate::basic_learning::text_layout::{Align, Table};
+
+        /*
+            UTF-8 用 1~4 个字节表示一个字符，首字节的高位表示总共有几个字节:
+                0xxxxxxx                              1 字节  U+0000  ~ U+007F
+                110xxxxx 10xxxxxx                     2 字节  U+0080  ~ U+07FF
+                1110xxxx 10xxxxxx 10xxxxxx            3 字节  U+0800  ~ U+FFFF
+                11110xxx 10xxxxxx 10xxxxxx 10xxxxxx   4 字节  U+10000 ~ U+10FFFF
+            后续字节都以 10 开头，所以从任意位置都能找到字符的起点
+         */
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct CharInfo {
+            pub ch: char,
+            pub code_point: u32,
+            pub bytes: Vec<u8>,
+            // 在原字符串中的字节区间
+            pub byte_range: Range<usize>,
+        }
+
+        impl CharInfo {
+            // E5 AD 97
+            pub fn hex(&self) -> String {
+                self.bytes.iter().map(|b| format!("{b:02X}")).collect::<Vec<_>>().join(" ")
+            }
+
+            // 用方括号标出首字节和后续字节的标志位: [1110]0101 [10]101101 [10]010111
+            pub fn binary(&self) -> String {
+                let lead_bits = match self.bytes.len() {
+                    1 => 1,
+                    n => n + 1,
+                };
+                self.bytes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| {
+                        let bits = format!("{b:08b}");
+                        let prefix = if i == 0 { lead_bits } else { 2 };
+                        format!("[{}]{}", &bits[..prefix], &bits[prefix..])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+
+            // 空白和控制字符显示为带引号的转义形式，如 ' '、'\n'
+            fn display_char(&self) -> String {
+                if self.ch.is_control() || self.ch.is_whitespace() {
+                    format!("{:?}", self.ch)
+                } else {
+                    self.ch.to_string()
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct Inspection {
+            pub chars: Vec<CharInfo>,
+            // 所有满足 is_char_boundary 的字节下标，包括 0 和 len
+            pub boundaries: Vec<usize>,
+            pub byte_len: usize,
+        }
+
+        pub fn inspect(s: &str) -> Inspection {
+            let chars = s
+                .char_indices()
+                .map(|(i, ch)| {
+                    let end = i + ch.len_utf8();
+                    CharInfo { ch, code_point: ch as u32, bytes: s.as_bytes()[i..end].to_vec(), byte_range: i..end }
+                })
+                .collect();
+            let boundaries = (0..=s.len()).filter(|&i| s.is_char_boundary(i)).collect();
+            Inspection { chars, boundaries, byte_len: s.len() }
+        }
+
+        impl fmt::Display for Inspection {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut table = Table::new(&["字符", "码点", "UTF-8", "二进制", "字节区间"]).align(1, Align::Right);
+                for info in &self.chars {
+                    table.add_row(&[
+                        info.display_char(),
+                        format!("U+{:04X}", info.code_point),
+                        info.hex(),
+                        info.binary(),
+                        format!("{}..{}", info.byte_range.start, info.byte_range.end),
+                    ]);
+                }
+                writeln!(f, "{table}")?;
+                writeln!(f, "共 {} 个字符，{} 个字节", self.chars.len(), self.byte_len)?;
+
+                // 逐个字节标出是否是合法的字符边界，^ 表示可以在这里切片
+                let marks: String = (0..=self.byte_len)
+                    .map(|i| if self.boundaries.binary_search(&i).is_ok() { '^' } else { '.' })
+                    .collect();
+                writeln!(f, "字符边界(^ 可切片，. 会 panic): {marks}")?;
+                write!(f, "合法的字节下标: {:?}", self.boundaries)
+            }
+        }
+
+        // cargo run -- inspect 字符串
+        // 不传参数时从标准输入读取
+        pub fn cli(args: &[String]) {
+            let text = if args.is_empty() {
+                let mut input = String::new();
+                // 输入不是合法的 UTF-8 时 read_to_string 会返回错误
+                if let Err(err) = io::stdin().read_to_string(&mut input) {
+                    eprintln!("读取输入失败: {err}");
+                    std::process::exit(1);
+                }
+                input.trim_end_matches(['\r', '\n']).to_string()
+            } else {
+                args.join(" ")
+            };
+            println!("{}", inspect(&text));
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn byte_offsets_and_boundaries() {
+                let result = inspect("a字🦀");
+                assert_eq!(result.byte_len, 8);
+                let ranges: Vec<Range<usize>> = result.chars.iter().map(|c| c.byte_range.clone()).collect();
+                assert_eq!(ranges, [0..1, 1..4, 4..8]);
+                assert_eq!(result.boundaries, [0, 1, 4, 8]);
+
+                let empty = inspect("");
+                assert!(empty.chars.is_empty());
+                assert_eq!(empty.boundaries, [0]);
+            }
+
+            #[test]
+            fn widths_of_one_to_four_bytes() {
+                // 每种长度的最小与最大码点
+                let cases = [
+                    ('\u{0}', 1),
+                    ('\u{7F}', 1),
+                    ('\u{80}', 2),
+                    ('\u{7FF}', 2),
+                    ('\u{800}', 3),
+                    ('\u{FFFF}', 3),
+                    ('\u{10000}', 4),
+                    ('\u{10FFFF}', 4),
+                    ('é', 2),
+                    ('字', 3),
+                    ('🦀', 4),
+                ];
+                for (ch, len) in cases {
+                    let info = &inspect(&ch.to_string()).chars[0];
+                    assert_eq!(info.bytes.len(), len, "{ch:?}");
+                    assert_eq!(info.code_point, ch as u32);
+                }
+            }
+
+            #[test]
+            fn hex_and_binary_markers() {
+                let cases = [
+                    ("A", "41", "[0]1000001"),
+                    ("é", "C3 A9", "[110]00011 [10]101001"),
+                    ("字", "E5 AD 97", "[1110]0101 [10]101101 [10]010111"),
+                    ("🦀", "F0 9F A6 80", "[11110]000 [10]011111 [10]100110 [10]000000"),
+                ];
+                for (s, hex, binary) in cases {
+                    let info = &inspect(s).chars[0];
+                    assert_eq!(info.hex(), hex, "{s}");
+                    assert_eq!(info.binary(), binary, "{s}");
+                }
+            }
+
+            #[test]
+            fn display_marks_boundaries() {
+                let text = inspect("a字\n").to_string();
+                assert!(text.contains("共 3 个字符，5 个字节"), "{text}");
+                assert!(text.contains("^^..^^"), "{text}");
+                // 控制字符显示为转义形式
+                assert!(text.contains("'\\n'"), "{text}");
+            }
+        }
+    }
+
+    // 按字符下标安全地编辑字符串
+    pub mod utf8_edit {
+        use std::fmt;
+        use std::ops::{Bound, RangeBounds};
+
+        use crate::basic_learning::style::dim_dbg;
+
+        /*
+            String 的 insert、remove、replace_range、truncate 以及切片 &s[a..b]
+            使用的都是字节下标，一旦落在某个多字节字符的中间就会 panic
+            这里的方法都使用字符下标，越界时返回 Err 而不是 panic
+         */
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum Utf8EditError {
+            // 字符下标超出范围
+            OutOfBounds { index: usize, char_count: usize },
+            // 区间的起点大于终点
+            InvalidRange { start: usize, end: usize },
+        }
+
+        impl fmt::Display for Utf8EditError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Utf8EditError::OutOfBounds { index, char_count } => {
+                        write!(f, "字符下标 {index} 越界，字符串只有 {char_count} 个字符")
+                    }
+                    Utf8EditError::InvalidRange { start, end } => {
+                        write!(f, "字符区间 {start}..{end} 的起点大于终点")
+                    }
+                }
+            }
+        }
+
+        impl std::error::Error for Utf8EditError {}
+
+        pub trait Utf8Edit {
+            // 在第 char_index 个字符前插入，char_index 等于字符数时追加到末尾
+            fn insert_at(&mut self, char_index: usize, ch: char) -> Result<(), Utf8EditError>;
+            fn insert_str_at(&mut self, char_index: usize, s: &str) -> Result<(), Utf8EditError>;
+            // 删除并返回第 char_index 个字符
+            fn remove_at(&mut self, char_index: usize) -> Result<char, Utf8EditError>;
+            fn replace_char_range<R: RangeBounds<usize>>(&mut self, range: R, replace_with: &str) -> Result<(), Utf8EditError>;
+            // 只保留前 char_count 个字符
+            fn truncate_chars(&mut self, char_count: usize) -> Result<(), Utf8EditError>;
+            fn char_slice<R: RangeBounds<usize>>(&self, range: R) -> Result<&str, Utf8EditError>;
+        }
+
+        // 字符下标 -> 字节下标，允许等于字符数(即字符串末尾)
+        fn byte_index(s: &str, char_index: usize) -> Result<usize, Utf8EditError> {
+            s.char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(s.len()))
+                .nth(char_index)
+                .ok_or_else(|| Utf8EditError::OutOfBounds { index: char_index, char_count: s.chars().count() })
+        }
+
+        // 字符区间 -> 字节区间
+        fn byte_range<R: RangeBounds<usize>>(s: &str, range: R) -> Result<(usize, usize), Utf8EditError> {
+            // 0..=usize::MAX 这样的区间加一会溢出，它一定越界，直接报错
+            let past = |i: usize| {
+                i.checked_add(1)
+                    .ok_or_else(|| Utf8EditError::OutOfBounds { index: i, char_count: s.chars().count() })
+            };
+            let start = match range.start_bound() {
+                Bound::Included(&i) => i,
+                Bound::Excluded(&i) => past(i)?,
+                Bound::Unbounded => 0,
+            };
+            let end = match range.end_bound() {
+                Bound::Included(&i) => past(i)?,
+                Bound::Excluded(&i) => i,
+                Bound::Unbounded => s.chars().count(),
+            };
+            if start > end {
+                return Err(Utf8EditError::InvalidRange { start, end });
+            }
+            Ok((byte_index(s, start)?, byte_index(s, end)?))
+        }
+
+        impl Utf8Edit for String {
+            fn insert_at(&mut self, char_index: usize, ch: char) -> Result<(), Utf8EditError> {
+                let i = byte_index(self, char_index)?;
+                self.insert(i, ch);
+                Ok(())
+            }
+
+            fn insert_str_at(&mut self, char_index: usize, s: &str) -> Result<(), Utf8EditError> {
+                let i = byte_index(self, char_index)?;
+                self.insert_str(i, s);
+                Ok(())
+            }
+
+            fn remove_at(&mut self, char_index: usize) -> Result<char, Utf8EditError> {
+                // 末尾位置不是一个字符，不能删除
+                match self.char_indices().nth(char_index) {
+                    Some((i, _)) => Ok(self.remove(i)),
+                    None => Err(Utf8EditError::OutOfBounds { index: char_index, char_count: self.chars().count() }),
+                }
+            }
+
+            fn replace_char_range<R: RangeBounds<usize>>(&mut self, range: R, replace_with: &str) -> Result<(), Utf8EditError> {
+                let (start, end) = byte_range(self, range)?;
+                self.replace_range(start..end, replace_with);
+                Ok(())
+            }
+
+            fn truncate_chars(&mut self, char_count: usize) -> Result<(), Utf8EditError> {
+                let i = byte_index(self, char_count)?;
+                self.truncate(i);
+                Ok(())
+            }
+
+            fn char_slice<R: RangeBounds<usize>>(&self, range: R) -> Result<&str, Utf8EditError> {
+                let (start, end) = byte_range(self, range)?;
+                Ok(&self[start..end])
+            }
+        }
+
+        pub fn run() {
+            let mut s = String::from("测试remove方法");
+
+            // String::remove(1) 会因为非法字符边界而 panic，这里直接按字符下标删除第二个汉字
+            dim_dbg!(s.remove_at(1).unwrap());
+            dim_dbg!(&s);
+
+            s.insert_at(1, '试').unwrap();
+            s.insert_str_at(8, "和")
+                .and_then(|_| s.insert_str_at(9, "insert"))
+                .unwrap();
+            dim_dbg!(&s);
+
+            // 把 "remove和insert" 换成 "replace"
+            s.replace_char_range(2..15, "replace").unwrap();
+            dim_dbg!(&s);
+            dim_dbg!(s.char_slice(2..=8).unwrap());
+
+            // 越界和非法区间都会返回 Err
+            if let Err(err) = s.remove_at(11) {
+                println!("{err}");
+            }
+            let (start, end) = (5, 3);
+            if let Err(err) = s.char_slice(start..end) {
+                println!("{err}");
+            }
+
+            s.truncate_chars(2).unwrap();
+            dim_dbg!(&s);
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn insert_and_remove_mixed_text() {
+                let mut s = String::from("测试remove方法");
+                assert_eq!(s.remove_at(1), Ok('试'));
+                assert_eq!(s, "测remove方法");
+                // 纯 ASCII 部分与多字节部分都按字符计数
+                assert_eq!(s.remove_at(1), Ok('r'));
+                assert_eq!(s.remove_at(6), Ok('方'));
+                assert_eq!(s, "测emove法");
+
+                let mut s = String::from("测remove方法");
+                s.insert_at(1, '试').unwrap();
+                s.insert_str_at(8, "和")
+                    .and_then(|_| s.insert_str_at(9, "insert"))
+                    .unwrap();
+                assert_eq!(s, "测试remove和insert方法");
+                // 等于字符数时追加到末尾
+                s.insert_at(17, '!').unwrap();
+                assert_eq!(s, "测试remove和insert方法!");
+                s.insert_str_at(0, "🦀").unwrap();
+                assert_eq!(s, "🦀测试remove和insert方法!");
+            }
+
+            #[test]
+            fn replace_and_slice_by_chars() {
+                let mut s = String::from("测试remove和insert方法");
+                // 把 "remove和insert" 换成 "replace"
+                s.replace_char_range(2..15, "replace").unwrap();
+                assert_eq!(s, "测试replace方法");
+                assert_eq!(s.char_slice(2..=8), Ok("replace"));
+                assert_eq!(s.char_slice(9..), Ok("方法"));
+                assert_eq!(s.char_slice(..2), Ok("测试"));
+                assert_eq!(s.char_slice(11..), Ok(""));
+
+                s.replace_char_range(..2, "").unwrap();
+                assert_eq!(s, "replace方法");
+                s.replace_char_range(7.., "模式").unwrap();
+                assert_eq!(s, "replace模式");
+            }
+
+            #[test]
+            fn char_boundaries_are_never_split() {
+                let s = String::from("a测🦀b");
+                // 字节长度分别是 1、3、4、1，按字符切片不会落在字符中间
+                for (i, expected) in ["a", "测", "🦀", "b"].iter().enumerate() {
+                    assert_eq!(s.char_slice(i..i + 1), Ok(*expected), "第 {i} 个字符");
+                }
+                let mut t = s.clone();
+                t.truncate_chars(3).unwrap();
+                assert_eq!(t, "a测🦀");
+                t.truncate_chars(3).unwrap();
+                assert_eq!(t, "a测🦀");
+                t.truncate_chars(0).unwrap();
+                assert_eq!(t, "");
+            }
+
+            #[test]
+            fn out_of_bounds_and_invalid_ranges() {
+                let mut s = String::from("测试replace方法");
+                assert_eq!(s.remove_at(11), Err(Utf8EditError::OutOfBounds { index: 11, char_count: 11 }));
+                assert_eq!(s.insert_at(12, 'x'), Err(Utf8EditError::OutOfBounds { index: 12, char_count: 11 }));
+                let (start, end) = (5, 3);
+                assert_eq!(s.char_slice(start..end), Err(Utf8EditError::InvalidRange { start, end }));
+                assert_eq!(s.replace_char_range(start..end, "x"), Err(Utf8EditError::InvalidRange { start, end }));
+                assert!(s.truncate_chars(20).is_err());
+                // 出错时字符串保持不变
+                assert_eq!(s, "测试replace方法");
+            }
+
+            #[test]
+            fn range_ending_at_usize_max_is_out_of_bounds() {
+                let s = String::from("测试");
+                let err = Utf8EditError::OutOfBounds { index: usize::MAX, char_count: 2 };
+                assert_eq!(s.char_slice(0..=usize::MAX), Err(err.clone()));
+                assert_eq!(s.char_slice((Bound::Excluded(usize::MAX), Bound::Unbounded)), Err(err));
+            }
+        }
+    }
+
+    // 绳索(Rope): 适合频繁编辑的大文本
+    pub mod rope {
+        use std::fmt;
+        use std::ops::{Bound, RangeBounds};
+
+        use super::utf8_edit::Utf8EditError;
+        use crate::basic_learning::style::dim_dbg;
+        use crate::basic_learning::text_layout::{Align, Table};
+
+        /*
+            String::insert_str 需要把插入点之后的所有字节往后挪，每次编辑都是 O(n)
+            Rope 把文本切成许多小块(chunk)，放在一棵平衡二叉树的叶子上:
+
+                        (branch)
+                       /        \
+                 (branch)       "文本的"
+                 /      \
+            "一段很"   "长很长"
+
+            每个分支节点记录子树中的字符数和换行数，按字符下标查找时只需要从根往下走一条路径
+            插入和删除都通过 split(切成两棵树) + concat(拼成一棵树) 实现，都是 O(log n)
+         */
+
+        // 叶子最多存放的字节数
+        const MAX_LEAF_BYTES: usize = 512;
+
+        // 一棵子树的统计信息
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        struct Summary {
+            bytes: usize,
+            chars: usize,
+            newlines: usize,
+        }
+
+        impl Summary {
+            fn of(s: &str) -> Self {
+                Summary { bytes: s.len(), chars: s.chars().count(), newlines: s.bytes().filter(|&b| b == b'\n').count() }
+            }
+
+            fn add(self, other: Self) -> Self {
+                Summary {
+                    bytes: self.bytes + other.bytes,
+                    chars: self.chars + other.chars,
+                    newlines: self.newlines + other.newlines,
+                }
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        enum Node {
+            Leaf(String, Summary),
+            Branch {
+                left: Box<Node>,
+                right: Box<Node>,
+                summary: Summary,
+                height: usize,
+            },
+        }
+
+        impl Default for Node {
+            fn default() -> Self {
+                Node::Leaf(String::new(), Summary::default())
+            }
+        }
+
+        impl Node {
+            fn leaf(s: String) -> Node {
+                let summary = Summary::of(&s);
+                Node::Leaf(s, summary)
+            }
+
+            fn branch(left: Node, right: Node) -> Node {
+                Node::Branch {
+                    summary: left.summary().add(right.summary()),
+                    height: left.height().max(right.height()) + 1,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+
+            fn summary(&self) -> Summary {
+                match self {
+                    Node::Leaf(_, summary) | Node::Branch { summary, .. } => *summary,
+                }
+            }
+
+            fn height(&self) -> usize {
+                match self {
+                    Node::Leaf(..) => 0,
+                    Node::Branch { height, .. } => *height,
+                }
+            }
+
+            fn into_children(self) -> (Node, Node) {
+                match self {
+                    Node::Branch { left, right, .. } => (*left, *right),
+                    Node::Leaf(..) => unreachable!("叶子节点没有子节点"),
+                }
+            }
+
+            // 把切好的小块自底向上组装成一棵完全平衡的树
+            fn from_chunks(chunks: &mut Vec<String>) -> Node {
+                fn build(chunks: &mut std::vec::Drain<'_, String>, count: usize) -> Node {
+                    if count == 1 {
+                        return Node::leaf(chunks.next().unwrap());
+                    }
+                    let left = build(chunks, count / 2);
+                    let right = build(chunks, count - count / 2);
+                    Node::branch(left, right)
+                }
+                match chunks.len() {
+                    0 => Node::default(),
+                    n => build(&mut chunks.drain(..), n),
+                }
+            }
+        }
+
+        // 按 AVL 的方式拼接，两棵树高度差不超过 1 时直接作为左右子树
+        fn concat(left: Node, right: Node) -> Node {
+            if left.summary().bytes == 0 {
+                return right;
+            }
+            if right.summary().bytes == 0 {
+                return left;
+            }
+            // 两个小叶子直接合并，避免树里出现大量碎片
+            if let (Node::Leaf(a, _), Node::Leaf(b, _)) = (&left, &right) {
+                if a.len() + b.len() <= MAX_LEAF_BYTES {
+                    return Node::leaf(format!("{a}{b}"));
+                }
+            }
+            let (hl, hr) = (left.height(), right.height());
+            if hl > hr + 1 {
+                // 左边更高，沿着左树的右侧往下找到高度相近的位置再拼
+                let (ll, lr) = left.into_children();
+                balance(ll, concat(lr, right))
+            } else if hr > hl + 1 {
+                let (rl, rr) = right.into_children();
+                balance(concat(left, rl), rr)
+            } else {
+                Node::branch(left, right)
+            }
+        }
+
+        // 高度差为 2 时通过旋转恢复平衡
+        fn balance(left: Node, right: Node) -> Node {
+            if left.height() > right.height() + 1 {
+                let (ll, lr) = left.into_children();
+                if ll.height() >= lr.height() {
+                    // 右旋
+                    Node::branch(ll, Node::branch(lr, right))
+                } else {
+                    // 先左旋再右旋
+                    let (lrl, lrr) = lr.into_children();
+                    Node::branch(Node::branch(ll, lrl), Node::branch(lrr, right))
+                }
+            } else if right.height() > left.height() + 1 {
+                let (rl, rr) = right.into_children();
+                if rr.height() >= rl.height() {
+                    Node::branch(Node::branch(left, rl), rr)
+                } else {
+                    let (rll, rlr) = rl.into_children();
+                    Node::branch(Node::branch(left, rll), Node::branch(rlr, rr))
+                }
+            } else {
+                Node::branch(left, right)
+            }
+        }
+
+        // 在第 at 个字符处切成两棵树
+        fn split(node: Node, at: usize) -> (Node, Node) {
+            match node {
+                Node::Leaf(s, _) => {
+                    let i = s.char_indices().nth(at).map_or(s.len(), |(i, _)| i);
+                    let (a, b) = s.split_at(i);
+                    (Node::leaf(a.to_string()), Node::leaf(b.to_string()))
+                }
+                Node::Branch { left, right, .. } => {
+                    let left_chars = left.summary().chars;
+                    if at < left_chars {
+                        let (a, b) = split(*left, at);
+                        (a, concat(b, *right))
+                    } else if at > left_chars {
+                        let (a, b) = split(*right, at - left_chars);
+                        (concat(*left, a), b)
+                    } else {
+                        (*left, *right)
+                    }
+                }
+            }
+        }
+
+        // 把 [start, end) 范围内的字符追加到 out
+        fn collect_range(node: &Node, start: usize, end: usize, out: &mut String) {
+            match node {
+                Node::Leaf(s, _) => out.extend(s.chars().skip(start).take(end - start)),
+                Node::Branch { left, right, .. } => {
+                    let left_chars = left.summary().chars;
+                    if start < left_chars {
+                        collect_range(left, start, end.min(left_chars), out);
+                    }
+                    if end > left_chars {
+                        collect_range(right, start.saturating_sub(left_chars), end - left_chars, out);
+                    }
+                }
+            }
+        }
+
+        // 第 n 个(从 0 开始)换行符的字符下标
+        fn nth_newline(node: &Node, n: usize) -> usize {
+            match node {
+                Node::Leaf(s, _) => s.chars().enumerate().filter(|&(_, c)| c == '\n').nth(n).unwrap().0,
+                Node::Branch { left, right, .. } => {
+                    let l = left.summary();
+                    if n < l.newlines {
+                        nth_newline(left, n)
+                    } else {
+                        l.chars + nth_newline(right, n - l.newlines)
+                    }
+                }
+            }
+        }
+
+        // 前 at 个字符中的换行数
+        fn newlines_before(node: &Node, at: usize) -> usize {
+            match node {
+                Node::Leaf(s, _) => s.chars().take(at).filter(|&c| c == '\n').count(),
+                Node::Branch { left, right, .. } => {
+                    let l = left.summary();
+                    if at <= l.chars {
+                        newlines_before(left, at)
+                    } else {
+                        l.newlines + newlines_before(right, at - l.chars)
+                    }
+                }
+            }
+        }
+
+        #[derive(Clone, Default)]
+        pub struct Rope {
+            root: Node,
+        }
+
+        impl Rope {
+            pub fn new() -> Self {
+                Rope { root: Node::default() }
+            }
+
+            pub fn len_chars(&self) -> usize {
+                self.root.summary().chars
+            }
+
+            pub fn len_bytes(&self) -> usize {
+                self.root.summary().bytes
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len_bytes() == 0
+            }
+
+            // 树的高度，平衡时约为 log2(叶子数)
+            pub fn height(&self) -> usize {
+                self.root.height()
+            }
+
+            fn check_index(&self, char_index: usize) {
+                assert!(char_index <= self.len_chars(), "char index {char_index} out of bounds (len {})", self.len_chars());
+            }
+
+            // 与 utf8_edit 一样，越界和非法区间返回 Err
+            fn char_range<R: RangeBounds<usize>>(&self, range: R) -> Result<(usize, usize), Utf8EditError> {
+                let len = self.len_chars();
+                // 0..=usize::MAX 这样的区间加一会溢出，它一定越界
+                let past = |i: usize| i.checked_add(1).ok_or(Utf8EditError::OutOfBounds { index: i, char_count: len });
+                let start = match range.start_bound() {
+                    Bound::Included(&i) => i,
+                    Bound::Excluded(&i) => past(i)?,
+                    Bound::Unbounded => 0,
+                };
+                let end = match range.end_bound() {
+                    Bound::Included(&i) => past(i)?,
+                    Bound::Excluded(&i) => i,
+                    Bound::Unbounded => len,
+                };
+                if start > end {
+                    return Err(Utf8EditError::InvalidRange { start, end });
+                }
+                if end > len {
+                    return Err(Utf8EditError::OutOfBounds { index: end, char_count: len });
+                }
+                Ok((start, end))
+            }
+
+            // 在第 char_index 个字符前插入
+            pub fn insert(&mut self, char_index: usize, text: &str) {
+                self.check_index(char_index);
+                let (a, b) = split(std::mem::take(&mut self.root), char_index);
+                self.root = concat(concat(a, Rope::from(text).root), b);
+            }
+
+            // 按字符区间删除，区间非法时 panic，与 String::replace_range 一致
+            pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+                self.try_remove(range).unwrap_or_else(|e| panic!("{e}"))
+            }
+
+            pub fn try_remove<R: RangeBounds<usize>>(&mut self, range: R) -> Result<(), Utf8EditError> {
+                let (start, end) = self.char_range(range)?;
+                let (rest, tail) = split(std::mem::take(&mut self.root), end);
+                let (head, _) = split(rest, start);
+                self.root = concat(head, tail);
+                Ok(())
+            }
+
+            pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> String {
+                self.try_slice(range).unwrap_or_else(|e| panic!("{e}"))
+            }
+
+            pub fn try_slice<R: RangeBounds<usize>>(&self, range: R) -> Result<String, Utf8EditError> {
+                let (start, end) = self.char_range(range)?;
+                let mut out = String::new();
+                collect_range(&self.root, start, end, &mut out);
+                Ok(out)
+            }
+
+            pub fn char_at(&self, char_index: usize) -> Option<char> {
+                if char_index < self.len_chars() {
+                    self.slice(char_index..=char_index).chars().next()
+                } else {
+                    None
+                }
+            }
+
+            // 行数 = 换行符个数 + 1，空字符串也算一行
+            pub fn len_lines(&self) -> usize {
+                self.root.summary().newlines + 1
+            }
+
+            // 第 line 行第一个字符的下标
+            pub fn line_to_char(&self, line: usize) -> usize {
+                assert!(line < self.len_lines(), "line {line} out of bounds (lines {})", self.len_lines());
+                if line == 0 { 0 } else { nth_newline(&self.root, line - 1) + 1 }
+            }
+
+            // 第 char_index 个字符所在的行
+            pub fn char_to_line(&self, char_index: usize) -> usize {
+                self.check_index(char_index);
+                newlines_before(&self.root, char_index)
+            }
+
+            // 第 line 行的内容，不包含末尾的换行符
+            pub fn line(&self, line: usize) -> String {
+                let start = self.line_to_char(line);
+                let end = if line + 1 < self.len_lines() { self.line_to_char(line + 1) - 1 } else { self.len_chars() };
+                self.slice(start..end)
+            }
+
+            // 按顺序遍历所有叶子上的文本块
+            pub fn chunks(&self) -> Chunks<'_> {
+                Chunks { stack: vec![&self.root] }
+            }
+
+            pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+                self.chunks().flat_map(str::chars)
+            }
+
+            pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+                (0..self.len_lines()).map(|i| self.line(i))
+            }
+        }
+
+        pub struct Chunks<'a> {
+            stack: Vec<&'a Node>,
+        }
+
+        impl<'a> Iterator for Chunks<'a> {
+            type Item = &'a str;
+            fn next(&mut self) -> Option<&'a str> {
+                // 深度优先遍历，先压入右子树，保证左子树先出栈
+                while let Some(node) = self.stack.pop() {
+                    match node {
+                        Node::Leaf(s, _) if s.is_empty() => continue,
+                        Node::Leaf(s, _) => return Some(s),
+                        Node::Branch { left, right, .. } => {
+                            self.stack.push(right);
+                            self.stack.push(left);
+                        }
+                    }
+                }
+                None
+            }
+        }
+
+        impl From<&str> for Rope {
+            fn from(s: &str) -> Self {
+                // 在字符边界处切块，每块不超过 MAX_LEAF_BYTES 字节
+                let mut chunks = Vec::new();
+                let mut rest = s;
+                while !rest.is_empty() {
+                    let mut i = rest.len().min(MAX_LEAF_BYTES);
+                    while !rest.is_char_boundary(i) {
+                        i -= 1;
+                    }
+                    let (chunk, tail) = rest.split_at(i);
+                    chunks.push(chunk.to_string());
+                    rest = tail;
+                }
+                Rope { root: Node::from_chunks(&mut chunks) }
+            }
+        }
+
+        impl fmt::Display for Rope {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for chunk in self.chunks() {
+                    f.write_str(chunk)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::Debug for Rope {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct("Rope")
+                    .field("chars", &self.len_chars())
+                    .field("height", &self.height())
+                    .field("chunks", &self.chunks().count())
+                    .finish()
+            }
+        }
+
+        pub fn run() {
+            let mut rope = Rope::from("HelloRust!");
+            rope.insert(5, " ");
+            rope.insert(6, "一下插入很多内容\n");
+            println!("{rope}");
+            dim_dbg!(rope.line(1), rope.char_at(6));
+            rope.remove(6..15);
+            println!("{rope}");
+            if let Err(e) = rope.try_slice(3..=usize::MAX) {
+                println!("{e}");
+            }
+
+            // 大文本
+            let line = "Rust 语言圣经 - 一本全面的 Rust 教程\n";
+            let text = line.repeat(10_000);
+            let mut rope = Rope::from(text.as_str());
+            dim_dbg!(&rope);
+            rope.insert(rope.len_chars() / 2, "【插入】");
+            dim_dbg!(rope.lines().position(|l| l.contains("【插入】")));
+        }
+
+        // 在随机位置插入，比较 String 和 Rope 的耗时，建议使用 cargo run --release 运行
+        pub fn bench() {
+            use rand::{rngs::StdRng, Rng, SeedableRng};
+            use std::time::Instant;
+
+            const EDITS: usize = 1000;
+            let mut table = Table::new(&["文本长度(字符)", "String", "Rope", "更快的是"]);
+            for column in 0..3 {
+                table = table.align(column, Align::Right);
+            }
+            for size in [1_000usize, 10_000, 100_000, 1_000_000, 10_000_000] {
+                let text = "字".repeat(size);
+                let mut rng = StdRng::seed_from_u64(size as u64);
+                let positions: Vec<usize> = (0..EDITS).map(|i| rng.gen_range(0..=size + i)).collect();
+
+                // String 需要先把字符下标换算成字节下标，这里全是 3 字节的汉字，直接乘 3
+                let mut string = text.clone();
+                let start = Instant::now();
+                for &at in &positions {
+                    string.insert(at * 3, '字');
+                }
+                let t_string = start.elapsed();
+
+                let mut rope = Rope::from(text.as_str());
+                let start = Instant::now();
+                for &at in &positions {
+                    rope.insert(at, "字");
+                }
+                let t_rope = start.elapsed();
+                assert_eq!(rope.len_bytes(), string.len());
+
+                let winner = if t_string < t_rope { "String" } else { "Rope" };
+                table.add_row(&[size.to_string(), format!("{t_string:.2?}"), format!("{t_rope:.2?}"), winner.to_string()]);
+            }
+            println!("{EDITS} 次随机插入的总耗时:\n{table}");
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::super::utf8_edit::Utf8Edit;
+            use super::*;
+            use rand::{rngs::StdRng, Rng, SeedableRng};
+
+            #[test]
+            fn edit_small_text() {
+                let mut rope = Rope::from("HelloRust!");
+                rope.insert(5, " ");
+                rope.insert(6, "一下插入很多内容\n");
+                assert_eq!(rope.to_string(), "Hello 一下插入很多内容\nRust!");
+                assert_eq!(rope.len_lines(), 2);
+                assert_eq!(rope.line(1), "Rust!");
+                assert_eq!(rope.char_at(6), Some('一'));
+                rope.remove(6..15);
+                assert_eq!(rope.to_string(), "Hello Rust!");
+            }
+
+            #[test]
+            fn lines_of_large_text() {
+                let line = "Rust 语言圣经 - 一本全面的 Rust 教程\n";
+                let mut rope = Rope::from(line.repeat(2_000).as_str());
+                assert_eq!(rope.len_lines(), 2_001);
+                assert_eq!(rope.line(1_999), line.trim_end());
+                assert_eq!(rope.char_to_line(rope.line_to_char(1234) + 3), 1234);
+                rope.insert(rope.len_chars() / 2, "【插入】");
+                assert_eq!(rope.lines().filter(|l| l.contains("【插入】")).count(), 1);
+            }
+
+            #[test]
+            fn invalid_ranges_are_errors() {
+                let mut rope = Rope::from("测试");
+                let overflow = Utf8EditError::OutOfBounds { index: usize::MAX, char_count: 2 };
+                assert_eq!(rope.try_slice(0..=usize::MAX), Err(overflow.clone()));
+                assert_eq!(rope.try_slice((Bound::Excluded(usize::MAX), Bound::Unbounded)), Err(overflow));
+                assert_eq!(rope.try_slice(1..3), Err(Utf8EditError::OutOfBounds { index: 3, char_count: 2 }));
+                let (start, end) = (2, 1);
+                assert_eq!(rope.try_remove(start..end), Err(Utf8EditError::InvalidRange { start, end }));
+                assert_eq!(rope.to_string(), "测试");
+            }
+
+            #[test]
+            #[should_panic(expected = "越界")]
+            fn slice_out_of_bounds_panics() {
+                Rope::from("测试").slice(..=usize::MAX);
+            }
+
+            // 对 Rope 和 String 执行同样的随机编辑，每一步都比较结果
+            #[test]
+            fn matches_string() {
+                for seed in 0..10 {
+                    check_against_string(seed);
+                }
+            }
+
+            fn check_against_string(seed: u64) {
+                const PIECES: [&str; 6] = ["a", "rust", "中文", "😀", "\n", "多行\n文本\n"];
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut rope = Rope::new();
+                let mut string = String::new();
+                for _ in 0..300 {
+                    let len = string.chars().count();
+                    if len > 0 && rng.gen_bool(0.3) {
+                        let start = rng.gen_range(0..len);
+                        let end = rng.gen_range(start..=len.min(start + 30));
+                        rope.remove(start..end);
+                        string.replace_char_range(start..end, "").unwrap();
+                    } else {
+                        let at = rng.gen_range(0..=len);
+                        let piece = PIECES[rng.gen_range(0..PIECES.len())].repeat(rng.gen_range(1..100));
+                        rope.insert(at, &piece);
+                        string.insert_str_at(at, &piece).unwrap();
+                    }
+                    assert_eq!(rope.len_chars(), string.chars().count());
+                    assert_eq!(rope.len_bytes(), string.len());
+                }
+                assert_eq!(rope.to_string(), string);
+                assert!(rope.chars().eq(string.chars()));
+                assert!(rope.lines().eq(string.split('\n').map(str::to_string)));
+                let len = string.chars().count();
+                let (a, b) = (len / 3, len * 2 / 3);
+                assert_eq!(rope.slice(a..b), string.char_slice(a..b).unwrap());
+                // 平衡树的高度应该远小于叶子数
+                let leaves = rope.chunks().count().max(1);
+                assert!(rope.height() <= 2 * (usize::BITS - leaves.leading_zeros()) as usize + 1);
+            }
+        }
     }
 }
 
 // 结构体
 pub mod struct_test {
-    // 基础部分
-    pub fn base() {
-        // 定义一个结构
-        #[derive(Debug)]
-        struct User {
-            active: bool,
-            username: String,
-            email: String,
-            sign_in_count: u64,
+    use crate::basic_learning::builder::builder;
+    use crate::basic_learning::style::dim_dbg;
+
+    // 定义一个结构
+    // 定义在模块级别，user_registry 也会用到
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct User {
+        pub active: bool,
+        pub username: String,
+        pub email: String,
+        pub sign_in_count: u64,
+    }
+
+    // 生成 UserBuilder，= 后面是构建时的默认值，与 create_user 一致，见 builder 模块
+    builder!(pub User => UserBuilder {
+        active: bool = true,
+        username: String,
+        email: String,
+        sign_in_count: u64 = 1,
+    });
+
+    // 类似js,当函数参数和结构体字段同名时，可以直接使用缩略的方式进行初始化
+    pub fn create_user(email: String, username: String) -> User {
+        User {
+            email,
+            username,
+            active: true,
+            sign_in_count: 1,
         }
+    }
 
+    // 基础部分
+    pub fn base() {
         // 创建一个实例
         // 初始化实例时，每个字段都需要进行初始化
         // 初始化时的字段顺序不需要和结构体定义时的顺序一致
@@ -198,41 +1222,41 @@ pub mod struct_test {
         // 通过.读写字段
         let username = &mut user1.username;
         username.push_str("_new");
-        dbg!(&user1.username);
+        dim_dbg!(&user1.username);
         user1.username.push_str("_and_new");
-        dbg!(&user1.username);
-
-        // 类似js,当函数参数和结构体字段同名时，可以直接使用缩略的方式进行初始化
-        fn create_user(email: String, username: String) -> User {
-            User {
-                email,
-                username,
-                active: true,
-                sign_in_count: 1,
-            }
-        }
+        dim_dbg!(&user1.username);
 
+        // create_user 定义在上面的模块级别
         let user2 = create_user("20482048@email.cc".to_string(), "init name".to_string());
-        dbg!(&user2.username);
+        dim_dbg!(&user2.username);
 
         // 类似js的 结构体更新语法
         let user2 = User {
             username: "new_name".to_string(),
             ..user2
         };
-        dbg!(&user2.username);
+        dim_dbg!(&user2.username);
+
+        // 没有现成的实例可以更新时，可以用构建者，只需设置必填字段
+        let user3 = User::builder()
+            .username("builder")
+            .email("builder@example.com")
+            .build()
+            .unwrap();
+        dim_dbg!(&user3);
     }
 
     // 元组结构体
-    pub fn tuple() {
-        // 结构体必须要有名称，但是结构体的字段可以没有名称
-        // 这种结构体长得很像元组，因此被称为元组结构体
-
-        #[derive(Debug)]
-        struct Rgb(u8, u8, u8);
+    // 结构体必须要有名称，但是结构体的字段可以没有名称
+    // 这种结构体长得很像元组，因此被称为元组结构体
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Rgb(pub u8, pub u8, pub u8);
 
+    pub fn tuple() {
         let color_red = Rgb(255, 0, 0);
-        dbg!(color_red);
+        dim_dbg!(color_red);
+        // Rgb 与 HSV、HSL、十六进制之间的转换见 color 模块
+        // 元组结构体也常用来给数字加上单位，见 units 模块
     }
 
     // 单元结构体
@@ -248,43 +1272,968 @@ pub mod struct_test {
         // 我们不关心 AlwaysEqual 的字段数据，只关心它的行为，因此将它声明为单元结构体，然后再为它实现某个特征
         impl SomeTrait for AlwaysEqual {
             fn some_method(&self) {
-                dbg!(&self);
+                dim_dbg!(&self);
             }
         }
 
         let subject = AlwaysEqual;
-        dbg!(&subject);
+        dim_dbg!(&subject);
         subject.some_method();
     }
-}
 
-// 枚举
-pub mod enum_test {
-    pub fn run() {
-        // 枚举类型是一个类型，它会包含所有可能的枚举成员,
-        #[derive(Debug)]
-        enum PokerSuit {
-            Clubs,
-            Spades,
-            Diamonds,
-            Hearts,
+    // 管理用户的注册表: 增删改查、用户名和邮箱唯一、记录登录、按条件查询、保存到文件
+    pub mod user_registry {
+        use std::cell::OnceCell;
+        use std::collections::{BTreeMap, HashMap};
+        use std::fmt;
+        use std::io;
+        use std::ops::Bound;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        use super::{create_user, User};
+        use crate::basic_learning::password::{PasswordHash, DEFAULT_ITERATIONS};
+
+        pub type UserId = u64;
+
+        // 保存文件的第一行，后面跟着 next_id
+        const HEADER_V2: &str = "# user registry v2 next_id=";
+
+        // 连续输错密码这么多次后锁定账号
+        pub const MAX_FAILED_SIGN_INS: u32 = 5;
+        // 锁定时长(秒)
+        pub const LOCKOUT_SECS: u64 = 15 * 60;
+
+        // 注册表中的一条记录，时间都是 Unix 时间戳(秒)
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Account {
+            pub id: UserId,
+            pub user: User,
+            pub created_at: u64,
+            // 每次登录的时间，个数与 user.sign_in_count 相同
+            pub sign_ins: Vec<u64>,
+            // 没有设置密码的账号无法通过 sign_in 登录
+            pub password: Option<PasswordHash>,
+            // 连续输错密码的次数，登录成功或被锁定后清零
+            pub failed_sign_ins: u32,
+            pub locked_until: Option<u64>,
+        }
+
+        impl Account {
+            pub fn last_sign_in(&self) -> Option<u64> {
+                self.sign_ins.last().copied()
+            }
         }
 
         #[derive(Debug)]
-        struct PokerCard {
-            suit: PokerSuit,
-            value: u8,
+        pub enum RegistryError {
+            NotFound(UserId),
+            DuplicateUsername(String),
+            DuplicateEmail(String),
+            InvalidUsername(String, &'static str),
+            InvalidEmail(String, &'static str),
+            WeakPassword(&'static str),
+            // 用户名不存在、密码错误、账号被锁定都返回同样的错误，不让对方知道哪个用户名是存在的
+            InvalidCredentials,
+            Inactive(String),
+            Io(io::Error),
+            // 文件内容格式错误，line 从 1 开始
+            Parse { line: usize, message: String },
+        }
+
+        impl fmt::Display for RegistryError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    RegistryError::NotFound(id) => write!(f, "用户 {id} 不存在"),
+                    RegistryError::DuplicateUsername(name) => write!(f, "用户名 {name} 已被占用"),
+                    RegistryError::DuplicateEmail(email) => write!(f, "邮箱 {email} 已被注册"),
+                    RegistryError::InvalidUsername(name, reason) => write!(f, "用户名 {name:?} 无效: {reason}"),
+                    RegistryError::InvalidEmail(email, reason) => write!(f, "邮箱 {email:?} 无效: {reason}"),
+                    RegistryError::WeakPassword(reason) => write!(f, "密码太弱: {reason}"),
+                    RegistryError::InvalidCredentials => write!(f, "用户名或密码错误"),
+                    RegistryError::Inactive(name) => write!(f, "用户 {name} 已停用"),
+                    RegistryError::Io(e) => write!(f, "读写文件失败: {e}"),
+                    RegistryError::Parse { line, message } => write!(f, "第 {line} 行格式错误: {message}"),
+                }
+            }
+        }
+
+        impl std::error::Error for RegistryError {}
+
+        impl From<io::Error> for RegistryError {
+            fn from(e: io::Error) -> Self {
+                RegistryError::Io(e)
+            }
+        }
+
+        pub fn now() -> u64 {
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
         }
 
+        // 用户名不能为空，不能包含空白和控制字符(保存文件时用制表符分隔字段)
+        pub fn validate_username(username: &str) -> Result<(), &'static str> {
+            if username.is_empty() {
+                Err("不能为空")
+            } else if username.chars().count() > 32 {
+                Err("不能超过 32 个字符")
+            } else if username.chars().any(|c| c.is_whitespace() || c.is_control()) {
+                Err("不能包含空白或控制字符")
+            } else {
+                Ok(())
+            }
+        }
+
+        /*
+            邮箱格式检查，只覆盖常见的写法，不是完整的 RFC 5322:
+                本地部分@域名
+                - 本地部分: 字母、数字和 .!#$%&'*+/=?^_`{|}~- ，点不能在首尾，也不能连续
+                - 域名: 至少两段，每段由字母、数字和 - 组成，- 不能在首尾，最后一段是至少两个字母
+         */
+        pub fn validate_email(email: &str) -> Result<(), &'static str> {
+            if email.len() > 254 {
+                return Err("长度不能超过 254");
+            }
+            let (local, domain) = email.rsplit_once('@').ok_or("缺少 @")?;
+            if local.is_empty() || local.len() > 64 {
+                return Err("@ 前面的部分长度应在 1 ~ 64 之间");
+            }
+            if !local.chars().all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+/=?^_`{|}~-.".contains(c)) {
+                return Err("@ 前面包含不允许的字符");
+            }
+            if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+                return Err("@ 前面的点不能在首尾或连续出现");
+            }
+            let labels: Vec<&str> = domain.split('.').collect();
+            if labels.len() < 2 {
+                return Err("域名至少需要两段，例如 example.com");
+            }
+            for label in &labels {
+                if label.is_empty() || label.len() > 63 {
+                    return Err("域名的每一段长度应在 1 ~ 63 之间");
+                }
+                if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') || label.starts_with('-') || label.ends_with('-') {
+                    return Err("域名只能包含字母、数字和 -，且 - 不能在首尾");
+                }
+            }
+            let tld = labels[labels.len() - 1];
+            if tld.len() < 2 || !tld.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err("顶级域名应为至少两个字母");
+            }
+            Ok(())
+        }
+
+        // 至少 8 个字符，不能全是数字
+        pub fn validate_password(password: &str) -> Result<(), &'static str> {
+            if password.chars().count() < 8 {
+                Err("至少需要 8 个字符")
+            } else if password.chars().all(|c| c.is_ascii_digit()) {
+                Err("不能全是数字")
+            } else {
+                Ok(())
+            }
+        }
+
+        // 邮箱不区分大小写，统一转成小写保存
+        fn normalize_email(email: &str) -> String {
+            email.trim().to_ascii_lowercase()
+        }
+
+        /*
+            accounts    id -> 记录
+            usernames   用户名 -> id，有序，可以按前缀查询
+            emails      邮箱 -> id
+         */
+        #[derive(Debug, Default)]
+        pub struct UserRegistry {
+            accounts: BTreeMap<UserId, Account>,
+            usernames: BTreeMap<String, UserId>,
+            emails: HashMap<String, UserId>,
+            next_id: UserId,
+            // 新设置的密码使用的 PBKDF2 迭代次数，已保存的密码按各自的次数校验
+            hash_iterations: u32,
+            // 用户不存在或没有设置密码时，登录拿它来校验，第一次用到时才计算
+            dummy_hash: OnceCell<PasswordHash>,
+        }
+
+        impl UserRegistry {
+            pub fn new() -> Self {
+                UserRegistry { next_id: 1, hash_iterations: DEFAULT_ITERATIONS, ..Default::default() }
+            }
+
+            // 演示时可以调低迭代次数，加快速度
+            pub fn with_hash_iterations(mut self, iterations: u32) -> Self {
+                self.hash_iterations = iterations;
+                self.dummy_hash = OnceCell::new();
+                self
+            }
+
+            pub fn len(&self) -> usize {
+                self.accounts.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.accounts.is_empty()
+            }
+
+            fn check_username(&self, username: &str, except: Option<UserId>) -> Result<(), RegistryError> {
+                validate_username(username).map_err(|reason| RegistryError::InvalidUsername(username.to_string(), reason))?;
+                match self.usernames.get(username) {
+                    Some(&id) if Some(id) != except => Err(RegistryError::DuplicateUsername(username.to_string())),
+                    _ => Ok(()),
+                }
+            }
+
+            fn check_email(&self, email: &str, except: Option<UserId>) -> Result<(), RegistryError> {
+                validate_email(email).map_err(|reason| RegistryError::InvalidEmail(email.to_string(), reason))?;
+                match self.emails.get(email) {
+                    Some(&id) if Some(id) != except => Err(RegistryError::DuplicateEmail(email.to_string())),
+                    _ => Ok(()),
+                }
+            }
+
+            // 参数顺序与 create_user 一致
+            pub fn create(&mut self, email: &str, username: &str) -> Result<UserId, RegistryError> {
+                let email = normalize_email(email);
+                self.check_username(username, None)?;
+                self.check_email(&email, None)?;
+                let mut user = create_user(email, username.to_string());
+                // create_user 把登录次数初始化为 1，注册表里从 0 开始，每次登录加一
+                user.sign_in_count = 0;
+                let id = self.next_id;
+                self.next_id = id.checked_add(1).expect("user id overflow");
+                self.insert(Account {
+                    id,
+                    user,
+                    created_at: now(),
+                    sign_ins: Vec::new(),
+                    password: None,
+                    failed_sign_ins: 0,
+                    locked_until: None,
+                });
+                Ok(id)
+            }
+
+            // 创建用户并设置密码，密码不合格时不会创建用户
+            pub fn register(&mut self, email: &str, username: &str, password: &str) -> Result<UserId, RegistryError> {
+                validate_password(password).map_err(RegistryError::WeakPassword)?;
+                let id = self.create(email, username)?;
+                self.set_password(id, password)?;
+                Ok(id)
+            }
+
+            // 修改密码同时解除锁定
+            pub fn set_password(&mut self, id: UserId, password: &str) -> Result<(), RegistryError> {
+                validate_password(password).map_err(RegistryError::WeakPassword)?;
+                let hash = PasswordHash::with_iterations(password, self.hash_iterations);
+                let account = self.account_mut(id)?;
+                account.password = Some(hash);
+                account.failed_sign_ins = 0;
+                account.locked_until = None;
+                Ok(())
+            }
+
+            pub fn sign_in(&mut self, username: &str, password: &str) -> Result<UserId, RegistryError> {
+                self.sign_in_at(username, password, now())
+            }
+
+            /*
+                用密码登录，成功时记录登录时间
+                    - 连续输错 MAX_FAILED_SIGN_INS 次后锁定 LOCKOUT_SECS 秒，锁定期间即使密码正确也不能登录
+                    - 用户不存在、没有设置密码、处于锁定期时同样计算一次哈希，并返回与密码错误相同的错误，
+                      从耗时和返回值都看不出用户名是否存在、账号是否被锁定
+                    - 先校验密码再检查是否停用，密码错误时不透露账号的状态
+             */
+            pub fn sign_in_at(&mut self, username: &str, password: &str, now: u64) -> Result<UserId, RegistryError> {
+                // 结果对不存在的用户没有用处，black_box 防止编译器把这次计算优化掉
+                let matches = std::hint::black_box(self.hash_for_sign_in(username).verify(password));
+                let account = self.usernames.get(username).and_then(|id| self.accounts.get_mut(id));
+                let verified = matches && account.as_ref().is_some_and(|a| a.password.is_some());
+                let Some(account) = account else {
+                    return Err(RegistryError::InvalidCredentials);
+                };
+                if let Some(until) = account.locked_until {
+                    if now < until {
+                        return Err(RegistryError::InvalidCredentials);
+                    }
+                    account.locked_until = None;
+                }
+                if !verified {
+                    account.failed_sign_ins += 1;
+                    if account.failed_sign_ins >= MAX_FAILED_SIGN_INS {
+                        account.failed_sign_ins = 0;
+                        account.locked_until = Some(now + LOCKOUT_SECS);
+                    }
+                    return Err(RegistryError::InvalidCredentials);
+                }
+                if !account.user.active {
+                    return Err(RegistryError::Inactive(username.to_string()));
+                }
+                account.failed_sign_ins = 0;
+                let id = account.id;
+                self.record_sign_in_at(id, now)?;
+                Ok(id)
+            }
+
+            // 有密码时是保存的哈希，否则是一个参数与新密码相同的假哈希，两种情况走同一个 verify
+            fn hash_for_sign_in(&self, username: &str) -> &PasswordHash {
+                self.get_by_username(username)
+                    .and_then(|a| a.password.as_ref())
+                    .unwrap_or_else(|| self.dummy_hash.get_or_init(|| PasswordHash::with_iterations("", self.hash_iterations)))
+            }
+
+            fn insert(&mut self, account: Account) {
+                self.usernames.insert(account.user.username.clone(), account.id);
+                self.emails.insert(account.user.email.clone(), account.id);
+                self.accounts.insert(account.id, account);
+            }
+
+            pub fn get(&self, id: UserId) -> Option<&Account> {
+                self.accounts.get(&id)
+            }
+
+            pub fn get_by_username(&self, username: &str) -> Option<&Account> {
+                self.usernames.get(username).and_then(|id| self.accounts.get(id))
+            }
+
+            pub fn get_by_email(&self, email: &str) -> Option<&Account> {
+                self.emails.get(&normalize_email(email)).and_then(|id| self.accounts.get(id))
+            }
+
+            fn account_mut(&mut self, id: UserId) -> Result<&mut Account, RegistryError> {
+                self.accounts.get_mut(&id).ok_or(RegistryError::NotFound(id))
+            }
+
+            pub fn update_username(&mut self, id: UserId, username: &str) -> Result<(), RegistryError> {
+                self.check_username(username, Some(id))?;
+                let account = self.account_mut(id)?;
+                let old = std::mem::replace(&mut account.user.username, username.to_string());
+                self.usernames.remove(&old);
+                self.usernames.insert(username.to_string(), id);
+                Ok(())
+            }
+
+            pub fn update_email(&mut self, id: UserId, email: &str) -> Result<(), RegistryError> {
+                let email = normalize_email(email);
+                self.check_email(&email, Some(id))?;
+                let account = self.account_mut(id)?;
+                let old = std::mem::replace(&mut account.user.email, email.clone());
+                self.emails.remove(&old);
+                self.emails.insert(email, id);
+                Ok(())
+            }
+
+            // 停用后仍然占用用户名和邮箱，可以重新启用
+            pub fn deactivate(&mut self, id: UserId) -> Result<(), RegistryError> {
+                self.account_mut(id)?.user.active = false;
+                Ok(())
+            }
+
+            pub fn activate(&mut self, id: UserId) -> Result<(), RegistryError> {
+                self.account_mut(id)?.user.active = true;
+                Ok(())
+            }
+
+            // 删除后用户名和邮箱可以被重新注册
+            pub fn delete(&mut self, id: UserId) -> Result<Account, RegistryError> {
+                let account = self.accounts.remove(&id).ok_or(RegistryError::NotFound(id))?;
+                self.usernames.remove(&account.user.username);
+                self.emails.remove(&account.user.email);
+                Ok(account)
+            }
+
+            pub fn record_sign_in(&mut self, id: UserId) -> Result<u64, RegistryError> {
+                self.record_sign_in_at(id, now())
+            }
+
+            // 返回登录后的 sign_in_count
+            pub fn record_sign_in_at(&mut self, id: UserId, timestamp: u64) -> Result<u64, RegistryError> {
+                let account = self.account_mut(id)?;
+                account.user.sign_in_count += 1;
+                account.sign_ins.push(timestamp);
+                Ok(account.user.sign_in_count)
+            }
+
+            // 所有记录，按 id 排序
+            pub fn iter(&self) -> impl Iterator<Item = &Account> {
+                self.accounts.values()
+            }
+
+            // 用户名以 prefix 开头的记录，active 为 None 时不限状态，结果按用户名排序
+            pub fn search<'a>(&'a self, prefix: &'a str, active: Option<bool>) -> impl Iterator<Item = &'a Account> + 'a {
+                self.usernames
+                    .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+                    .take_while(move |(name, _)| name.starts_with(prefix))
+                    .map(|(_, id)| &self.accounts[id])
+                    .filter(move |account| active.is_none_or(|a| account.user.active == a))
+            }
+
+            /*
+                保存为文本文件，一行一个用户，字段之间用制表符分隔:
+                    id  active  username  email  sign_in_count  created_at  登录时间(逗号分隔)
+                    密码哈希  连续输错次数  锁定到期时间
+                没有密码或没有锁定时写 -，只有前 7 个字段的旧文件(v1)也可以读取
+                第一行记录下一个要分配的 id，删除的用户的 id 在重新读取后也不会被再次使用
+                先写到临时文件再重命名，写到一半失败不会破坏原来的文件
+             */
+            pub fn save(&self, path: &str) -> Result<(), RegistryError> {
+                let mut out = format!("{HEADER_V2}{}\n", self.next_id);
+                for a in self.accounts.values() {
+                    let sign_ins: Vec<String> = a.sign_ins.iter().map(u64::to_string).collect();
+                    let password = a.password.as_ref().map_or("-".to_string(), PasswordHash::to_string);
+                    let locked_until = a.locked_until.map_or("-".to_string(), |t| t.to_string());
+                    out.push_str(&format!(
+                        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                        a.id,
+                        a.user.active,
+                        a.user.username,
+                        a.user.email,
+                        a.user.sign_in_count,
+                        a.created_at,
+                        sign_ins.join(","),
+                        password,
+                        a.failed_sign_ins,
+                        locked_until
+                    ));
+                }
+                let tmp = format!("{path}.tmp");
+                std::fs::write(&tmp, out)?;
+                std::fs::rename(&tmp, path)?;
+                Ok(())
+            }
+
+            // 读取时重新做一遍校验，手工改坏的文件会报告出错的行号
+            pub fn load(path: &str) -> Result<Self, RegistryError> {
+                let text = std::fs::read_to_string(path)?;
+                let mut registry = UserRegistry::new();
+                // 没有记录 next_id 的旧文件，只能从已有的最大 id 推算；同时记下它所在的行，出错时报告
+                let mut saved_next_id = None;
+                for (i, line) in text.lines().enumerate() {
+                    let line_no = i + 1;
+                    let error = |message: &str| RegistryError::Parse { line: line_no, message: message.to_string() };
+                    if let Some(next_id) = line.strip_prefix(HEADER_V2) {
+                        saved_next_id = Some((line_no, next_id.parse::<UserId>().map_err(|_| error("next_id 不是数字"))?));
+                        continue;
+                    }
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    let fields: Vec<&str> = line.split('\t').collect();
+                    let (v1, credentials) = match fields.len() {
+                        7 => (&fields[..], ["-", "0", "-"]),
+                        10 => (&fields[..7], [fields[7], fields[8], fields[9]]),
+                        n => return Err(error(&format!("应有 7 个或 10 个字段，实际 {n} 个"))),
+                    };
+                    let [id, active, username, email, count, created_at, sign_ins] = v1[..] else {
+                        unreachable!()
+                    };
+                    let [password, failed_sign_ins, locked_until] = credentials;
+                    let password = match password {
+                        "-" => None,
+                        s => Some(s.parse::<PasswordHash>().map_err(|e| error(&e))?),
+                    };
+                    let failed_sign_ins = failed_sign_ins.parse().map_err(|_| error("连续输错次数不是数字"))?;
+                    let locked_until = match locked_until {
+                        "-" => None,
+                        s => Some(s.parse().map_err(|_| error("锁定到期时间不是数字"))?),
+                    };
+                    let id: UserId = id.parse().map_err(|_| error("id 不是数字"))?;
+                    let sign_ins = if sign_ins.is_empty() {
+                        Vec::new()
+                    } else {
+                        sign_ins.split(',').map(str::parse).collect::<Result<Vec<u64>, _>>().map_err(|_| error("登录时间不是数字"))?
+                    };
+                    let user = User {
+                        active: active.parse().map_err(|_| error("active 应为 true 或 false"))?,
+                        username: username.to_string(),
+                        email: email.to_string(),
+                        sign_in_count: count.parse().map_err(|_| error("sign_in_count 不是数字"))?,
+                    };
+                    if registry.accounts.contains_key(&id) {
+                        return Err(error(&format!("id {id} 重复")));
+                    }
+                    registry.check_username(&user.username, None).map_err(|e| error(&e.to_string()))?;
+                    registry.check_email(&user.email, None).map_err(|e| error(&e.to_string()))?;
+                    let created_at = created_at.parse().map_err(|_| error("created_at 不是数字"))?;
+                    let after = id.checked_add(1).ok_or_else(|| error("id 太大"))?;
+                    registry.next_id = registry.next_id.max(after);
+                    registry.insert(Account { id, user, created_at, sign_ins, password, failed_sign_ins, locked_until });
+                }
+                if let Some((line, next_id)) = saved_next_id {
+                    if next_id < registry.next_id {
+                        return Err(RegistryError::Parse { line, message: format!("next_id {next_id} 不大于已有的 id") });
+                    }
+                    registry.next_id = next_id;
+                }
+                Ok(registry)
+            }
+        }
+
+        pub fn run() {
+            let mut registry = UserRegistry::new().with_hash_iterations(1000);
+            let alice = registry.create("Alice@Example.com", "alice").unwrap();
+            let bob = registry.create("bob@example.com", "bob").unwrap();
+            let albert = registry.create("albert@rust-lang.org", "albert").unwrap();
+            // 邮箱统一转成小写
+            println!("{}", registry.get(alice).unwrap().user.email);
+
+            // 唯一性和格式检查
+            for result in [
+                registry.create("alice2@example.com", "alice"),
+                registry.create("ALICE@example.com", "alice2"),
+                registry.create("someone@example", "someone"),
+                registry.create("a..b@example.com", "ab"),
+                registry.create("carol@example.com", "carol smith"),
+            ] {
+                println!("{}", result.unwrap_err());
+            }
+
+            // 改名后旧名字可以被别人使用
+            registry.update_username(bob, "bobby").unwrap();
+            registry.update_email(bob, "bobby@example.com").unwrap();
+            registry.record_sign_in_at(alice, 1_700_000_000).unwrap();
+
+            // 按前缀查询
+            registry.deactivate(albert).unwrap();
+            for a in registry.search("al", None) {
+                println!("{} {}", a.user.username, if a.user.active { "正常" } else { "停用" });
+            }
+
+            // 连续输错密码后锁定，锁定期间密码正确也不能登录
+            registry.register("carol@example.com", "carol", "rust-is-fun").unwrap();
+            let t = 1_800_000_000;
+            for _ in 0..MAX_FAILED_SIGN_INS {
+                registry.sign_in_at("carol", "wrong password", t).unwrap_err();
+            }
+            println!("{}", registry.sign_in_at("carol", "rust-is-fun", t + 60).unwrap_err());
+            registry.sign_in_at("carol", "rust-is-fun", t + LOCKOUT_SECS).unwrap();
+
+            // 保存后的文件内容，文件名带上进程号，同时运行多个演示也不会互相覆盖
+            let path = std::env::temp_dir().join(format!("rust_course_users_demo_{}.tsv", std::process::id()));
+            let path = path.to_str().unwrap();
+            registry.save(path).unwrap();
+            print!("{}", std::fs::read_to_string(path).unwrap());
+
+            // 文件被改坏时报告行号
+            std::fs::write(path, "# user registry v1\n1\ttrue\talice\talice@example.com\t0\t0\t\n2\tyes\tbob\tbob@example.com\t0\t0\t\n").unwrap();
+            println!("{}", UserRegistry::load(path).unwrap_err());
+            std::fs::remove_file(path).unwrap();
+        }
+
+        fn read_line(prompt: &str) -> io::Result<String> {
+            use std::io::Write;
+            print!("{prompt}");
+            io::stdout().flush()?;
+            let mut line = String::new();
+            io::stdin().read_line(&mut line)?;
+            Ok(line.trim_end_matches(['\r', '\n']).to_string())
+        }
+
+        /*
+            cargo run -- account register <用户名> <邮箱> [--file 路径]
+            cargo run -- account login <用户名> [--file 路径]
+            cargo run -- account list [--file 路径]
+            默认保存在系统临时目录下的 rust_course_users.tsv
+            标准库没有关闭终端回显的方法，输入的密码会显示在屏幕上
+         */
+        pub fn cli(args: &[String]) {
+            if let Err(e) = run_cli(args) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+
+        fn run_cli(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+            let usage = "用法: account register <用户名> <邮箱> | account login <用户名> | account list  [--file 路径]";
+            let mut args = args.to_vec();
+            let path = match args.iter().position(|a| a == "--file") {
+                Some(i) if i + 1 < args.len() => args.drain(i..i + 2).nth(1).unwrap(),
+                Some(_) => return Err(usage.into()),
+                None => std::env::temp_dir().join("rust_course_users.tsv").to_string_lossy().into_owned(),
+            };
+            let mut registry = match UserRegistry::load(&path) {
+                Ok(registry) => registry,
+                Err(RegistryError::Io(e)) if e.kind() == io::ErrorKind::NotFound => UserRegistry::new(),
+                Err(e) => return Err(e.into()),
+            };
+
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            match args[..] {
+                ["register", username, email] => {
+                    let password = read_line("密码: ")?;
+                    if read_line("再输入一次: ")? != password {
+                        return Err("两次输入的密码不一致".into());
+                    }
+                    let id = registry.register(email, username, &password)?;
+                    println!("注册成功，用户 id 为 {id}");
+                }
+                ["login", username] => {
+                    let password = read_line("密码: ")?;
+                    // 失败的次数也需要保存，所以无论成功与否都写回文件
+                    let result = registry.sign_in(username, &password);
+                    registry.save(&path)?;
+                    let id = result?;
+                    let account = registry.get(id).unwrap();
+                    println!("欢迎回来 {}，这是你第 {} 次登录", account.user.username, account.user.sign_in_count);
+                    return Ok(());
+                }
+                ["list"] => {
+                    for a in registry.iter() {
+                        let state = if a.user.active { "正常" } else { "停用" };
+                        println!("{}\t{}\t{}\t{}\t登录 {} 次", a.id, a.user.username, a.user.email, state, a.user.sign_in_count);
+                    }
+                    return Ok(());
+                }
+                _ => return Err(usage.into()),
+            }
+            registry.save(&path)?;
+            Ok(())
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            // 文件名带上进程号和序号，并行运行的测试之间不会互相覆盖
+            fn temp_path(name: &str) -> String {
+                use std::sync::atomic::{AtomicUsize, Ordering};
+                static NEXT: AtomicUsize = AtomicUsize::new(0);
+                let n = NEXT.fetch_add(1, Ordering::Relaxed);
+                let file = format!("rust_course_users_test_{}_{n}_{name}.tsv", std::process::id());
+                std::env::temp_dir().join(file).to_str().unwrap().to_string()
+            }
+
+            // 写入文件后读取，返回读取结果并删除文件
+            fn load_text(text: &str) -> Result<UserRegistry, RegistryError> {
+                let path = temp_path("load");
+                std::fs::write(&path, text).unwrap();
+                let result = UserRegistry::load(&path);
+                std::fs::remove_file(&path).unwrap();
+                result
+            }
+
+            fn names<'a>(accounts: impl Iterator<Item = &'a Account>) -> Vec<&'a str> {
+                accounts.map(|a| a.user.username.as_str()).collect()
+            }
+
+            // 删除最新的用户后保存、读取，再创建的用户不能拿到被删除的 id
+            #[test]
+            fn deleted_id_is_not_reused_after_reload() {
+                let path = temp_path("next_id");
+                let mut registry = UserRegistry::new();
+                registry.create("alice@example.com", "alice").unwrap();
+                let bob = registry.create("bob@example.com", "bob").unwrap();
+                registry.delete(bob).unwrap();
+                registry.save(&path).unwrap();
+
+                let mut loaded = UserRegistry::load(&path).unwrap();
+                std::fs::remove_file(&path).unwrap();
+                let carol = loaded.create("carol@example.com", "carol").unwrap();
+                assert_eq!(carol, bob + 1);
+            }
+
+            #[test]
+            fn file_without_next_id_uses_max_id() {
+                let mut loaded = load_text("# user registry v1\n7\ttrue\talice\talice@example.com\t0\t0\t\n").unwrap();
+                assert_eq!(loaded.create("bob@example.com", "bob").unwrap(), 8);
+            }
+
+            // 不存在、没有密码、被锁定，三种情况的错误完全一样
+            #[test]
+            fn sign_in_does_not_reveal_account_state() {
+                let mut registry = UserRegistry::new().with_hash_iterations(1);
+                registry.create("alice@example.com", "alice").unwrap();
+                let carol = registry.register("carol@example.com", "carol", "rust-is-fun").unwrap();
+                let t = 1_800_000_000;
+                for _ in 0..MAX_FAILED_SIGN_INS {
+                    registry.sign_in_at("carol", "wrong password", t).unwrap_err();
+                }
+                for (username, password) in [("nobody", "rust-is-fun"), ("alice", ""), ("alice", "rust-is-fun"), ("carol", "rust-is-fun")] {
+                    let err = registry.sign_in_at(username, password, t + 60).unwrap_err();
+                    assert!(matches!(err, RegistryError::InvalidCredentials), "{username}: {err}");
+                }
+                assert_eq!(registry.sign_in_at("carol", "rust-is-fun", t + LOCKOUT_SECS).unwrap(), carol);
+            }
+
+            // 用户不存在或没有密码时，校验的哈希与真实的哈希参数相同，计算量也就相同
+            #[test]
+            fn unknown_user_is_hashed_like_a_real_one() {
+                let mut registry = UserRegistry::new().with_hash_iterations(7);
+                registry.create("alice@example.com", "alice").unwrap();
+                let carol = registry.register("carol@example.com", "carol", "rust-is-fun").unwrap();
+                let real = registry.get(carol).unwrap().password.as_ref().unwrap();
+                assert!(std::ptr::eq(registry.hash_for_sign_in("carol"), real));
+                for username in ["nobody", "alice"] {
+                    let dummy = registry.hash_for_sign_in(username);
+                    assert_eq!(dummy.iterations, real.iterations, "{username}");
+                    assert_eq!((dummy.salt.len(), dummy.hash.len()), (real.salt.len(), real.hash.len()), "{username}");
+                }
+                // 假哈希只计算一次
+                assert!(std::ptr::eq(registry.hash_for_sign_in("nobody"), registry.hash_for_sign_in("alice")));
+                // 假哈希是空密码算出来的，空密码也不能登录没有密码的账号
+                assert!(matches!(registry.sign_in_at("alice", "", 0), Err(RegistryError::InvalidCredentials)));
+            }
+
+            #[test]
+            fn max_id_is_a_parse_error() {
+                let line = format!("{}\ttrue\talice\talice@example.com\t0\t0\t\n", UserId::MAX);
+                let err = load_text(&line).unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 1, .. }), "{err}");
+            }
+
+            #[test]
+            fn next_id_below_existing_ids_is_a_parse_error() {
+                let err = load_text(&format!("{HEADER_V2}2\n5\ttrue\talice\talice@example.com\t0\t0\t\n")).unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 1, .. }), "{err}");
+            }
+
+            // next_id 不在第一行时，报告它实际所在的行
+            #[test]
+            fn bad_next_id_reports_its_own_line() {
+                let alice = "5\ttrue\talice\talice@example.com\t0\t0\t\n";
+                let err = load_text(&format!("# 注释\n\n{HEADER_V2}abc\n{alice}")).unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 3, .. }), "{err}");
+                let err = load_text(&format!("{alice}{HEADER_V2}2\n")).unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 2, .. }), "{err}");
+            }
+
+            #[test]
+            fn create_read_update_delete() {
+                let mut registry = UserRegistry::new();
+                assert!(registry.is_empty());
+                let alice = registry.create("Alice@Example.com", "alice").unwrap();
+                let bob = registry.create("bob@example.com", "bob").unwrap();
+                assert_eq!((alice, bob), (1, 2));
+                assert_eq!(registry.len(), 2);
+                // 邮箱统一转成小写，查询时也不区分大小写
+                let account = registry.get(alice).unwrap();
+                assert_eq!(account.user.email, "alice@example.com");
+                assert_eq!(account.user.sign_in_count, 0);
+                assert!(account.user.active);
+                assert_eq!(registry.get_by_email("ALICE@example.COM").unwrap().id, alice);
+
+                // 改名后旧名字查不到了
+                registry.update_username(bob, "bobby").unwrap();
+                assert!(registry.get_by_username("bob").is_none());
+                assert_eq!(registry.get_by_username("bobby").unwrap().id, bob);
+                registry.update_email(bob, "BOBBY@example.com").unwrap();
+                assert!(registry.get_by_email("bob@example.com").is_none());
+                assert_eq!(registry.get_by_email("bobby@example.com").unwrap().id, bob);
+                // 改成自己现在的名字不算重复
+                registry.update_username(bob, "bobby").unwrap();
+
+                registry.deactivate(bob).unwrap();
+                assert!(!registry.get(bob).unwrap().user.active);
+                registry.activate(bob).unwrap();
+                assert!(registry.get(bob).unwrap().user.active);
+
+                assert_eq!(registry.record_sign_in_at(alice, 1_700_000_000).unwrap(), 1);
+                assert_eq!(registry.record_sign_in_at(alice, 1_700_003_600).unwrap(), 2);
+                assert_eq!(registry.get(alice).unwrap().last_sign_in(), Some(1_700_003_600));
+
+                let removed = registry.delete(bob).unwrap();
+                assert_eq!(removed.user.username, "bobby");
+                assert!(registry.get(bob).is_none());
+                assert!(matches!(registry.delete(bob), Err(RegistryError::NotFound(2))));
+                assert!(matches!(registry.deactivate(bob), Err(RegistryError::NotFound(2))));
+                assert!(matches!(registry.record_sign_in_at(bob, 0), Err(RegistryError::NotFound(2))));
+                // id 不会被重复使用
+                assert_eq!(registry.create("carol@example.com", "carol").unwrap(), 3);
+            }
+
+            #[test]
+            fn username_and_email_are_unique() {
+                let mut registry = UserRegistry::new();
+                let alice = registry.create("alice@example.com", "alice").unwrap();
+                let bob = registry.create("bob@example.com", "bob").unwrap();
+
+                assert!(matches!(registry.create("alice2@example.com", "alice"), Err(RegistryError::DuplicateUsername(_))));
+                // 邮箱不区分大小写
+                assert!(matches!(registry.create("ALICE@example.com", "alice2"), Err(RegistryError::DuplicateEmail(_))));
+                assert!(matches!(registry.update_username(bob, "alice"), Err(RegistryError::DuplicateUsername(_))));
+                assert!(matches!(registry.update_email(bob, "Alice@Example.com"), Err(RegistryError::DuplicateEmail(_))));
+                // 失败的操作不会留下任何改动
+                assert_eq!(registry.len(), 2);
+                assert_eq!(registry.get(bob).unwrap().user.username, "bob");
+
+                // 停用的账号仍然占用用户名，删除后才能被重新注册
+                registry.deactivate(alice).unwrap();
+                assert!(matches!(registry.create("a@example.com", "alice"), Err(RegistryError::DuplicateUsername(_))));
+                registry.delete(alice).unwrap();
+                registry.create("alice@example.com", "alice").unwrap();
+            }
+
+            #[test]
+            fn email_validation() {
+                for email in ["user@example.com", "user.name+tag@mail.example.co", "a!#$%&'*+/=?^_`{|}~-@x-y.io"] {
+                    assert_eq!(validate_email(email), Ok(()), "{email}");
+                }
+                for email in [
+                    "",
+                    "user.example.com",
+                    "@example.com",
+                    ".user@example.com",
+                    "user.@example.com",
+                    "a..b@example.com",
+                    "us er@example.com",
+                    "用户@example.com",
+                    "user@example",
+                    "user@-example.com",
+                    "user@example-.com",
+                    "user@exa_mple.com",
+                    "user@example..com",
+                    "user@example.c",
+                    "user@example.c0m",
+                ] {
+                    assert!(validate_email(email).is_err(), "{email}");
+                }
+                let long_local = format!("{}@example.com", "a".repeat(65));
+                assert!(validate_email(&long_local).is_err());
+                let long_label = format!("user@{}.com", "a".repeat(64));
+                assert!(validate_email(&long_label).is_err());
+            }
+
+            #[test]
+            fn username_and_password_validation() {
+                assert_eq!(validate_username("sunface"), Ok(()));
+                assert_eq!(validate_username("孙飞"), Ok(()));
+                // 按字符计数，32 个汉字是允许的
+                assert_eq!(validate_username(&"字".repeat(32)), Ok(()));
+                for username in ["", "carol smith", "tab\there", "new\nline", &"a".repeat(33)] {
+                    assert!(validate_username(username).is_err(), "{username:?}");
+                }
+                assert!(matches!(UserRegistry::new().create("c@example.com", "carol smith"), Err(RegistryError::InvalidUsername(..))));
+                assert!(matches!(UserRegistry::new().create("c@example", "carol"), Err(RegistryError::InvalidEmail(..))));
+
+                assert_eq!(validate_password("rust-is-fun"), Ok(()));
+                assert!(validate_password("short").is_err());
+                assert!(validate_password("12345678").is_err());
+                // 密码不合格时不会创建用户
+                let mut registry = UserRegistry::new().with_hash_iterations(1);
+                assert!(matches!(registry.register("dave@example.com", "dave", "12345678"), Err(RegistryError::WeakPassword(_))));
+                assert!(registry.get_by_username("dave").is_none());
+            }
+
+            #[test]
+            fn search_by_prefix_and_state() {
+                let mut registry = UserRegistry::new();
+                for name in ["bob", "alice", "albert", "al", "carol"] {
+                    registry.create(&format!("{name}@example.com"), name).unwrap();
+                }
+                let albert = registry.get_by_username("albert").unwrap().id;
+                registry.deactivate(albert).unwrap();
+
+                // 结果按用户名排序
+                assert_eq!(names(registry.search("al", None)), ["al", "albert", "alice"]);
+                assert_eq!(names(registry.search("al", Some(true))), ["al", "alice"]);
+                assert_eq!(names(registry.search("", Some(false))), ["albert"]);
+                assert_eq!(names(registry.search("ali", None)), ["alice"]);
+                assert!(names(registry.search("z", None)).is_empty());
+                // iter 按 id 排序
+                assert_eq!(names(registry.iter()), ["bob", "alice", "albert", "al", "carol"]);
+            }
+
+            #[test]
+            fn password_sign_in_and_lockout() {
+                let mut registry = UserRegistry::new().with_hash_iterations(1);
+                let carol = registry.register("carol@example.com", "carol", "rust-is-fun").unwrap();
+                let t = 1_800_000_000;
+                assert_eq!(registry.sign_in_at("carol", "rust-is-fun", t).unwrap(), carol);
+                // 锁定前输错的次数在登录成功后清零
+                registry.sign_in_at("carol", "wrong password", t).unwrap_err();
+                registry.sign_in_at("carol", "rust-is-fun", t).unwrap();
+                assert_eq!(registry.get(carol).unwrap().failed_sign_ins, 0);
+
+                for _ in 0..MAX_FAILED_SIGN_INS {
+                    registry.sign_in_at("carol", "wrong password", t).unwrap_err();
+                }
+                assert_eq!(registry.get(carol).unwrap().locked_until, Some(t + LOCKOUT_SECS));
+                assert!(registry.sign_in_at("carol", "rust-is-fun", t + LOCKOUT_SECS - 1).is_err());
+                registry.sign_in_at("carol", "rust-is-fun", t + LOCKOUT_SECS).unwrap();
+                assert_eq!(registry.get(carol).unwrap().user.sign_in_count, 3);
+
+                // 修改密码会解除锁定
+                for _ in 0..MAX_FAILED_SIGN_INS {
+                    registry.sign_in_at("carol", "wrong password", t).unwrap_err();
+                }
+                registry.set_password(carol, "new-password").unwrap();
+                registry.sign_in_at("carol", "new-password", t).unwrap();
+
+                // 密码正确但账号已停用
+                registry.deactivate(carol).unwrap();
+                assert!(matches!(registry.sign_in_at("carol", "new-password", t), Err(RegistryError::Inactive(_))));
+            }
+
+            #[test]
+            fn save_and_load_round_trip() {
+                let mut registry = UserRegistry::new().with_hash_iterations(1);
+                let alice = registry.create("alice@example.com", "alice").unwrap();
+                registry.record_sign_in_at(alice, 1_700_000_000).unwrap();
+                registry.record_sign_in_at(alice, 1_700_003_600).unwrap();
+                let bob = registry.register("bob@example.com", "bob", "rust-is-fun").unwrap();
+                registry.deactivate(bob).unwrap();
+                registry.register("carol@example.com", "carol", "rust-is-fun").unwrap();
+                for _ in 0..MAX_FAILED_SIGN_INS {
+                    registry.sign_in_at("carol", "wrong password", 1_800_000_000).unwrap_err();
+                }
+                registry.sign_in_at("carol", "wrong password", 1_800_000_000 + LOCKOUT_SECS).unwrap_err();
+
+                let path = temp_path("round_trip");
+                registry.save(&path).unwrap();
+                let mut loaded = UserRegistry::load(&path).unwrap();
+                std::fs::remove_file(&path).unwrap();
+                assert!(loaded.iter().eq(registry.iter()));
+                // 读取后索引也是完整的
+                assert_eq!(loaded.get_by_email("bob@example.com").unwrap().id, bob);
+                assert_eq!(names(loaded.search("", Some(false))), ["bob"]);
+                assert_eq!(loaded.sign_in_at("carol", "rust-is-fun", 1_900_000_000).unwrap(), 3);
+            }
+
+            #[test]
+            fn corrupted_file_reports_line() {
+                let text = "# user registry v1\n1\ttrue\talice\talice@example.com\t0\t0\t\n2\tyes\tbob\tbob@example.com\t0\t0\t\n";
+                let err = load_text(text).unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 3, .. }), "{err}");
+                // 重复的用户名
+                let text = "1\ttrue\talice\ta@example.com\t0\t0\t\n2\ttrue\talice\tb@example.com\t0\t0\t\n";
+                let err = load_text(text).unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 2, .. }), "{err}");
+                // 字段个数不对
+                let err = load_text("1\ttrue\talice\n").unwrap_err();
+                assert!(matches!(err, RegistryError::Parse { line: 1, .. }), "{err}");
+            }
+        }
+    }
+}
+
+// 枚举
+pub mod enum_test {
+    use crate::basic_learning::style::dim_dbg;
+
+    // 枚举类型是一个类型，它会包含所有可能的枚举成员,
+    // 完整的一副牌、洗牌和发牌见 cards 模块
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum PokerSuit {
+        Clubs,
+        Spades,
+        Diamonds,
+        Hearts,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PokerCard {
+        pub suit: PokerSuit,
+        pub value: u8,
+    }
+
+    pub fn run() {
         // 枚举值是该类型中的具体某个成员的实例
         let heart = PokerSuit::Hearts;
         let diamond = PokerSuit::Diamonds;
-        dbg!(heart);
-        dbg!(diamond);
+        dim_dbg!(heart);
+        dim_dbg!(diamond);
 
 
         let c1 = PokerCard { value: 1, suit: PokerSuit::Clubs };
-        dbg!(c1);
+        dim_dbg!(c1);
 
 
         // 直接将数据信息关联到枚举成员上
@@ -299,13 +2248,15 @@ pub mod enum_test {
             Test(String)
         }
         let c2 = PokerCardLaconic::Clubs('7');
-        dbg!(c2);
+        dim_dbg!(c2);
 
     }
 }
 
 // 数组
 pub mod arr {
+    use crate::basic_learning::style::dim_dbg;
+
     // 速度很快长度固定的 array
     // 可动态增长的但是有性能损耗的 Vector
 
@@ -319,17 +2270,17 @@ pub mod arr {
         // array 是存储在栈上  - 元素类型大小固定，且长度也是固定
         // 数组的元素类型要统一，长度要固定
         let a = [1, 2, 3, 4, 5];
-        dbg!(a);
+        dim_dbg!(a);
         // [类型; 长度]
         let b = [3; 5];
-        dbg!(b);
+        dim_dbg!(b);
 
         // 通过下标访问元素
         let c = a.clone();
         let first = c[0]; // 获取a数组第一个元素
-        dbg!(first);
+        dim_dbg!(first);
         let second = c[1]; // 获取第二个元素
-        dbg!(second);
+        dim_dbg!(second);
 
 
         // 数组访问越界，访问了数组中不存在的元素
@@ -341,7 +2292,7 @@ pub mod arr {
 
         let slice: &[i32] = &a[1..3];
 
-        dbg!(slice);
+        dim_dbg!(slice);
         // 切片的长度可以与数组不同，并不是固定的，而是取决于你使用时指定的起始和结束位置
         // 创建切片的代价非常小，因为切片只是针对底层数组的一个引用
         // 切片类型[T]拥有不固定的大小，
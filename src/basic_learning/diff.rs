@@ -0,0 +1,367 @@
+// 文本比较
+/*
+    diff 的本质是求两个序列的最短编辑脚本: 最少删掉多少、插入多少，能把 a 变成 b
+    Myers 算法 (1986) 把它看作在编辑图上找最短路径:
+        - 向右走一步表示删除 a 中的一个元素，向下走一步表示插入 b 中的一个元素
+        - 沿对角线走表示两边相同，不需要代价
+        - 第 d 轮只需要记录每条对角线 k = x - y 上能走到的最远位置
+    复杂度是 O((N + M) * D)，D 是差异的大小，两边越相似越快
+ */
+use crate::basic_learning::text_layout::{pad_right, truncate};
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tag {
+    Equal,
+    Delete,
+    Insert,
+}
+
+// 编辑脚本中的一步，old 和 new 分别是这一步在 a 和 b 中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Op {
+    pub tag: Tag,
+    pub old: usize,
+    pub new: usize,
+}
+
+// Myers 算法，返回把 a 变成 b 的最短编辑脚本
+pub fn diff<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Op> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    // v[k + offset] 是对角线 k 上走到的最远的 x，k 的范围是 -max..=max
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // 保存每一轮开始前的 v，回溯时使用
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let i = (k + offset) as usize;
+            // 从 k+1 向下走(插入)，或者从 k-1 向右走(删除)，选走得更远的那个
+            let mut x = if k == -d || (k != d && v[i - 1] < v[i + 1]) { v[i + 1] } else { v[i - 1] + 1 };
+            let mut y = x - k;
+            // 沿对角线尽量往前走
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // 从终点往回走，还原出路径
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]) { k + 1 } else { k - 1 };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op { tag: Tag::Equal, old: x as usize, new: y as usize });
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(Op { tag: Tag::Insert, old: x as usize, new: prev_y as usize });
+            } else {
+                ops.push(Op { tag: Tag::Delete, old: prev_x as usize, new: y as usize });
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+// 按行比较，返回每一行和它的变化
+pub fn diff_lines<'a>(a: &'a str, b: &'a str) -> Vec<(Tag, &'a str)> {
+    let (a, b): (Vec<&str>, Vec<&str>) = (a.lines().collect(), b.lines().collect());
+    diff(&a, &b)
+        .into_iter()
+        .map(|op| (op.tag, if op.tag == Tag::Insert { b[op.new] } else { a[op.old] }))
+        .collect()
+}
+
+// 按字符比较，连续相同变化的字符合并成一段
+pub fn diff_chars(a: &str, b: &str) -> Vec<(Tag, String)> {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut result: Vec<(Tag, String)> = Vec::new();
+    for op in diff(&a, &b) {
+        let c = if op.tag == Tag::Insert { b[op.new] } else { a[op.old] };
+        match result.last_mut() {
+            Some((tag, s)) if *tag == op.tag => s.push(c),
+            _ => result.push((op.tag, c.to_string())),
+        }
+    }
+    result
+}
+
+// 类似 git diff --word-diff 的行内显示: 删除的部分用 [-...-]，插入的部分用 {+...+}
+pub fn inline(a: &str, b: &str) -> String {
+    diff_chars(a, b)
+        .into_iter()
+        .map(|(tag, s)| match tag {
+            Tag::Equal => s,
+            Tag::Delete => format!("[-{s}-]"),
+            Tag::Insert => format!("{{+{s}+}}"),
+        })
+        .collect()
+}
+
+// 行号加上行数，行数为 0 时行号指向前一行，行数为 1 时省略，与 GNU diff 一致
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+/*
+    统一格式 (diff -u):
+        --- 旧文件
+        +++ 新文件
+        @@ -起始行,行数 +起始行,行数 @@
+         相同的行
+        -删除的行
+        +插入的行
+    每个变化前后保留 context 行上下文，上下文重叠的变化合并成一块
+ */
+pub fn unified(a: &str, b: &str, old_name: &str, new_name: &str, context: usize) -> String {
+    let (old_lines, new_lines): (Vec<&str>, Vec<&str>) = (a.lines().collect(), b.lines().collect());
+    let ops = diff(&old_lines, &new_lines);
+
+    // 每一块在 ops 中的范围
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, op) in ops.iter().enumerate() {
+        if op.tag == Tag::Equal {
+            continue;
+        }
+        let (start, end) = (i.saturating_sub(context), (i + 1 + context).min(ops.len()));
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = String::new();
+    if hunks.is_empty() {
+        return out;
+    }
+    writeln!(out, "--- {old_name}\n+++ {new_name}").unwrap();
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|op| op.tag != Tag::Insert).count();
+        let new_count = hunk.iter().filter(|op| op.tag != Tag::Delete).count();
+        let (old_start, new_start) = (hunk[0].old, hunk[0].new);
+        writeln!(out, "@@ -{} +{} @@", hunk_range(old_start, old_count), hunk_range(new_start, new_count)).unwrap();
+        for op in hunk {
+            match op.tag {
+                Tag::Equal => writeln!(out, " {}", old_lines[op.old]),
+                Tag::Delete => writeln!(out, "-{}", old_lines[op.old]),
+                Tag::Insert => writeln!(out, "+{}", new_lines[op.new]),
+            }
+            .unwrap();
+        }
+    }
+    out
+}
+
+/*
+    左右对照 (diff -y)，按显示宽度对齐，中文不会把列挤歪
+        相同        左右都显示
+        |           修改，左边是旧行，右边是新行
+        <           只有左边，删除
+        >           只有右边，插入
+    两列各占 (width - 3) / 2，超出的部分截断
+ */
+pub fn side_by_side(a: &str, b: &str, width: usize) -> String {
+    let column = width.saturating_sub(3) / 2;
+    let mut out = String::new();
+    let mut row = |left: &str, marker: char, right: &str| {
+        let right = truncate(right, column);
+        let line = format!("{} {marker} {right}", pad_right(&truncate(left, column), column));
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    };
+
+    let lines = diff_lines(a, b);
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].0 == Tag::Equal {
+            row(lines[i].1, ' ', lines[i].1);
+            i += 1;
+            continue;
+        }
+        // 一段连续的删除和插入，按顺序配对成修改
+        let end = (i..lines.len()).find(|&j| lines[j].0 == Tag::Equal).unwrap_or(lines.len());
+        let deleted: Vec<&str> = lines[i..end].iter().filter(|l| l.0 == Tag::Delete).map(|l| l.1).collect();
+        let inserted: Vec<&str> = lines[i..end].iter().filter(|l| l.0 == Tag::Insert).map(|l| l.1).collect();
+        for j in 0..deleted.len().max(inserted.len()) {
+            match (deleted.get(j), inserted.get(j)) {
+                (Some(l), Some(r)) => row(l, '|', r),
+                (Some(l), None) => row(l, '<', ""),
+                (None, Some(r)) => row("", '>', r),
+                (None, None) => unreachable!(),
+            }
+        }
+        i = end;
+    }
+    out
+}
+
+/*
+    运行一个子命令(不带参数就是默认的课程)，返回它打印到标准输出的内容
+    用来比较修改代码前后课程的输出:
+        cargo run > before.txt
+        (修改代码)
+        cargo run -- diff --lesson before.txt
+ */
+pub fn lesson_output(args: &[String]) -> std::io::Result<String> {
+    let output = std::process::Command::new(std::env::current_exe()?).args(args).output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/*
+    cargo run -- diff <旧文件> <新文件> [-y] [--width 列数] [-U 行数]
+    cargo run -- diff --lesson <保存的输出> [子命令 参数...]
+        -y        左右对照显示
+        --width   左右对照的总宽度，默认 100
+        -U        统一格式的上下文行数，默认 3
+ */
+pub fn cli(args: &[String]) {
+    if let Err(e) = run_cli(args) {
+        eprintln!("比较失败: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run_cli(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let usage = "用法: diff <旧文件> <新文件> [-y] [--width 列数] [-U 行数]\n      diff --lesson <保存的输出> [子命令 参数...]";
+    let (old_name, new_name, old, new, rest);
+    if args.first().map(String::as_str) == Some("--lesson") {
+        let saved = args.get(1).ok_or(usage)?;
+        old_name = saved.clone();
+        new_name = "当前输出".to_string();
+        old = std::fs::read_to_string(saved)?;
+        new = lesson_output(&args[2..])?;
+        rest = &[][..];
+    } else {
+        let [a, b, ..] = args else {
+            return Err(usage.into());
+        };
+        (old_name, new_name) = (a.clone(), b.clone());
+        old = std::fs::read_to_string(a)?;
+        new = std::fs::read_to_string(b)?;
+        rest = &args[2..];
+    }
+
+    let (mut side, mut width, mut context) = (false, 100, 3);
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-y" | "--side-by-side" => side = true,
+            "--width" => width = iter.next().ok_or(usage)?.parse()?,
+            "-U" => context = iter.next().ok_or(usage)?.parse()?,
+            _ => return Err(usage.into()),
+        }
+    }
+
+    if old == new {
+        println!("没有差异");
+    } else if side {
+        print!("{}", side_by_side(&old, &new, width));
+    } else {
+        print!("{}", unified(&old, &new, &old_name, &new_name, context));
+    }
+    Ok(())
+}
+
+pub fn run() {
+    let (before, after) = sample();
+    print!("{}", unified(before, after, "before.rs", "after.rs", 1));
+
+    // 中文按显示宽度对齐
+    let old = "名称: Rust语言圣经\n作者: 社区\n版本: 1.0\n价格: 免费";
+    let new = "名称: Rust语言圣经\n作者: Rust 中文社区\n版本: 2.0\n状态: 连载中\n价格: 免费";
+    print!("{}", side_by_side(old, new, 60));
+
+    println!("{}", inline("kitten", "sitting"));
+    println!("{}", inline("你好世界", "你好，Rust世界"));
+}
+
+fn sample() -> (&'static str, &'static str) {
+    let before = "fn main() {\n    let x = 5;\n    println!(\"x = {x}\");\n    let y = x * 2;\n    println!(\"y = {y}\");\n}\n";
+    let after = "fn main() {\n    let x = 6;\n    println!(\"x = {x}\");\n    let y = x * 2;\n    println!(\"y = {y}\");\n    println!(\"done\");\n}\n";
+    (before, after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_learning::text_layout::str_width;
+
+    // 最短编辑距离(只有插入和删除)，用动态规划求 LCS 来验证 Myers 的结果
+    fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+        let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                lcs[i][j] = if a[i - 1] == b[j - 1] { lcs[i - 1][j - 1] + 1 } else { lcs[i - 1][j].max(lcs[i][j - 1]) };
+            }
+        }
+        a.len() + b.len() - 2 * lcs[a.len()][b.len()]
+    }
+
+    #[test]
+    fn unified_format() {
+        let (before, after) = sample();
+        assert_eq!(
+            unified(before, after, "before.rs", "after.rs", 1),
+            "--- before.rs\n+++ after.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let x = 5;\n+    let x = 6;\n     println!(\"x = {x}\");\n@@ -5,2 +5,3 @@\n     println!(\"y = {y}\");\n+    println!(\"done\");\n }\n"
+        );
+        // 没有差异时输出为空
+        assert_eq!(unified(before, before, "a", "b", 3), "");
+    }
+
+    #[test]
+    fn side_by_side_aligns_wide_chars() {
+        let old = "名称: Rust语言圣经\n作者: 社区\n版本: 1.0\n价格: 免费";
+        let new = "名称: Rust语言圣经\n作者: Rust 中文社区\n版本: 2.0\n状态: 连载中\n价格: 免费";
+        let view = side_by_side(old, new, 60);
+        let widths: Vec<usize> = view.lines().filter(|l| l.contains(" | ")).map(|l| str_width(&l[..l.find(" | ").unwrap()])).collect();
+        assert!(!widths.is_empty());
+        assert!(widths.iter().all(|&w| w == widths[0]), "{view}");
+    }
+
+    #[test]
+    fn inline_marks() {
+        assert_eq!(inline("kitten", "sitting"), "[-k-]{+s+}itt[-e-]{+i+}n{+g+}");
+        assert_eq!(inline("你好世界", "你好，Rust世界"), "你好{+，Rust+}世界");
+        assert_eq!(inline("same", "same"), "same");
+    }
+
+    #[test]
+    fn myers_matches_lcs() {
+        // 随机序列: 编辑脚本能还原出两边，且长度是最短的
+        // 固定种子，失败时可以复现
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let a: Vec<u8> = (0..rng.gen_range(0..20)).map(|_| rng.gen_range(0..4)).collect();
+            let b: Vec<u8> = (0..rng.gen_range(0..20)).map(|_| rng.gen_range(0..4)).collect();
+            let ops = diff(&a, &b);
+            let old: Vec<u8> = ops.iter().filter(|op| op.tag != Tag::Insert).map(|op| a[op.old]).collect();
+            let new: Vec<u8> = ops.iter().filter(|op| op.tag != Tag::Delete).map(|op| b[op.new]).collect();
+            assert_eq!((old, new), (a.clone(), b.clone()));
+            assert!(ops.iter().filter(|op| op.tag == Tag::Equal).all(|op| a[op.old] == b[op.new]));
+            assert_eq!(ops.iter().filter(|op| op.tag != Tag::Equal).count(), edit_distance(&a, &b), "{a:?} {b:?}");
+        }
+    }
+}
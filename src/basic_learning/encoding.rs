@@ -0,0 +1,493 @@
+// 文本编码
+/*
+    Rust 的 String 和 &str 保证一定是合法的 UTF-8，但读进来的文件不一定是:
+        - Windows 上的很多文件是 UTF-16，还可能带有 BOM(字节顺序标记)
+        - 老系统导出的文件可能是 ISO-8859-1 (Latin-1)
+        - 文件可能被截断，或者夹杂了损坏的字节
+    这里实现这几种编码与 String 之间的相互转换
+ */
+use std::fmt;
+use std::str::FromStr;
+
+use crate::basic_learning::style::dim_dbg;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    // ISO-8859-1，每个字节对应 U+0000 ~ U+00FF 中的一个字符
+    Latin1,
+}
+
+impl Encoding {
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Encoding::Utf16Le => &[0xFF, 0xFE],
+            Encoding::Utf16Be => &[0xFE, 0xFF],
+            Encoding::Latin1 => &[],
+        }
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "utf-16le" | "utf16le" => Ok(Encoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+            "latin1" | "latin-1" | "iso-8859-1" => Ok(Encoding::Latin1),
+            _ => Err(format!("不支持的编码 {s}，可选: utf-8、utf-16le、utf-16be、latin1")),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "ISO-8859-1",
+        };
+        f.write_str(name)
+    }
+}
+
+// 根据开头的 BOM 判断编码，返回编码和 BOM 的长度
+pub fn detect_bom(bytes: &[u8]) -> Option<(Encoding, usize)> {
+    [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+        .into_iter()
+        .find(|e| bytes.starts_with(e.bom()))
+        .map(|e| (e, e.bom().len()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Utf8ErrorKind {
+    // 不可能出现在开头的字节: 80~C1、F5~FF
+    InvalidLeadByte(u8),
+    // 后续字节不在允许的范围内(包括超长编码和代理区)
+    InvalidContinuation,
+    // 字节流在一个字符的中间结束了
+    UnexpectedEnd,
+}
+
+// 一段非法的 UTF-8 字节
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidSequence {
+    pub offset: usize,
+    pub len: usize,
+    pub kind: Utf8ErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    InvalidUtf8(InvalidSequence),
+    // 没有配对的 UTF-16 代理项
+    UnpairedSurrogate { offset: usize, unit: u16 },
+    // UTF-16 的字节数必须是偶数
+    OddLength(usize),
+    // 目标编码无法表示这个字符
+    Unmappable { ch: char, offset: usize, encoding: Encoding },
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::InvalidUtf8(seq) => {
+                let reason = match seq.kind {
+                    Utf8ErrorKind::InvalidLeadByte(b) => format!("0x{b:02X} 不能作为首字节"),
+                    Utf8ErrorKind::InvalidContinuation => "后续字节无效".to_string(),
+                    Utf8ErrorKind::UnexpectedEnd => "字符不完整".to_string(),
+                };
+                write!(f, "第 {} 个字节处有 {} 个字节不是合法的 UTF-8: {reason}", seq.offset, seq.len)
+            }
+            EncodingError::UnpairedSurrogate { offset, unit } => {
+                write!(f, "第 {offset} 个字节处的代理项 0x{unit:04X} 没有配对")
+            }
+            EncodingError::OddLength(len) => write!(f, "UTF-16 数据的长度必须是偶数，实际为 {len}"),
+            EncodingError::Unmappable { ch, offset, encoding } => {
+                write!(f, "第 {offset} 个字节处的字符 {ch:?} 无法用 {encoding} 表示")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/*
+    合法的 UTF-8 字节序列 (Unicode 标准表 3-7):
+        00..7F
+        C2..DF  80..BF
+        E0      A0..BF  80..BF      E0 后面限制为 A0 起，排除超长编码
+        E1..EC  80..BF  80..BF
+        ED      80..9F  80..BF      ED 后面限制到 9F，排除 U+D800~U+DFFF 代理区
+        EE..EF  80..BF  80..BF
+        F0      90..BF  80..BF  80..BF
+        F1..F3  80..BF  80..BF  80..BF
+        F4      80..8F  80..BF  80..BF   不超过 U+10FFFF
+ */
+fn utf8_sequence(bytes: &[u8]) -> Result<usize, (usize, Utf8ErrorKind)> {
+    let lead = bytes[0];
+    let (len, second) = match lead {
+        0x00..=0x7F => return Ok(1),
+        0xC2..=0xDF => (2, 0x80..=0xBF),
+        0xE0 => (3, 0xA0..=0xBF),
+        0xE1..=0xEC | 0xEE..=0xEF => (3, 0x80..=0xBF),
+        0xED => (3, 0x80..=0x9F),
+        0xF0 => (4, 0x90..=0xBF),
+        0xF1..=0xF3 => (4, 0x80..=0xBF),
+        0xF4 => (4, 0x80..=0x8F),
+        _ => return Err((1, Utf8ErrorKind::InvalidLeadByte(lead))),
+    };
+    // 非法序列的长度取"最大合法前缀"，与 String::from_utf8_lossy 的替换方式一致
+    for i in 1..len {
+        let range = if i == 1 { second.clone() } else { 0x80..=0xBF };
+        match bytes.get(i) {
+            None => return Err((i, Utf8ErrorKind::UnexpectedEnd)),
+            Some(b) if !range.contains(b) => return Err((i, Utf8ErrorKind::InvalidContinuation)),
+            Some(_) => {}
+        }
+    }
+    Ok(len)
+}
+
+// 找出所有非法的 UTF-8 序列
+pub fn utf8_errors(bytes: &[u8]) -> Vec<InvalidSequence> {
+    let mut errors = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match utf8_sequence(&bytes[i..]) {
+            Ok(len) => i += len,
+            Err((len, kind)) => {
+                errors.push(InvalidSequence { offset: i, len, kind });
+                i += len;
+            }
+        }
+    }
+    errors
+}
+
+// 每一段非法序列替换为一个 U+FFFD (�)
+pub fn repair_utf8_lossy(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    let mut start = 0;
+    for seq in utf8_errors(bytes) {
+        // 两段非法序列之间的字节一定是合法的
+        out.push_str(std::str::from_utf8(&bytes[start..seq.offset]).unwrap());
+        out.push(char::REPLACEMENT_CHARACTER);
+        start = seq.offset + seq.len;
+    }
+    out.push_str(std::str::from_utf8(&bytes[start..]).unwrap());
+    out
+}
+
+fn decode_utf8(bytes: &[u8], lossy: bool) -> Result<String, EncodingError> {
+    match utf8_errors(bytes).first() {
+        Some(seq) if !lossy => Err(EncodingError::InvalidUtf8(*seq)),
+        Some(_) => Ok(repair_utf8_lossy(bytes)),
+        None => Ok(std::str::from_utf8(bytes).unwrap().to_string()),
+    }
+}
+
+/*
+    UTF-16 用 1 个或 2 个 16 位的码元表示一个字符:
+        - U+0000 ~ U+FFFF (除代理区) 直接用一个码元
+        - U+10000 ~ U+10FFFF 先减去 0x10000，剩下的 20 位拆成高 10 位和低 10 位
+            高代理 = 0xD800 + 高 10 位    (D800~DBFF)
+            低代理 = 0xDC00 + 低 10 位    (DC00~DFFF)
+ */
+fn decode_utf16(bytes: &[u8], big_endian: bool, lossy: bool) -> Result<String, EncodingError> {
+    if !bytes.len().is_multiple_of(2) && !lossy {
+        return Err(EncodingError::OddLength(bytes.len()));
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|b| if big_endian { u16::from_be_bytes([b[0], b[1]]) } else { u16::from_le_bytes([b[0], b[1]]) })
+        .collect();
+    let mut out = String::with_capacity(units.len());
+    let mut i = 0;
+    while i < units.len() {
+        let unit = units[i];
+        let ch = match unit {
+            0xD800..=0xDBFF => match units.get(i + 1) {
+                Some(&low @ 0xDC00..=0xDFFF) => {
+                    i += 1;
+                    let c = 0x10000 + (((unit as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+                    char::from_u32(c)
+                }
+                _ => None,
+            },
+            0xDC00..=0xDFFF => None,
+            _ => char::from_u32(unit as u32),
+        };
+        match ch {
+            Some(c) => out.push(c),
+            None if lossy => out.push(char::REPLACEMENT_CHARACTER),
+            None => return Err(EncodingError::UnpairedSurrogate { offset: i * 2, unit }),
+        }
+        i += 1;
+    }
+    // 奇数长度时多出的最后一个字节
+    if !bytes.len().is_multiple_of(2) {
+        out.push(char::REPLACEMENT_CHARACTER);
+    }
+    Ok(out)
+}
+
+fn encode_utf16(s: &str, big_endian: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(s.len() * 2);
+    let mut push = |unit: u16| out.extend(if big_endian { unit.to_be_bytes() } else { unit.to_le_bytes() });
+    for c in s.chars() {
+        let c = c as u32;
+        if c < 0x10000 {
+            push(c as u16);
+        } else {
+            let c = c - 0x10000;
+            push(0xD800 + (c >> 10) as u16);
+            push(0xDC00 + (c & 0x3FF) as u16);
+        }
+    }
+    out
+}
+
+fn decode_with(bytes: &[u8], encoding: Encoding, lossy: bool) -> Result<String, EncodingError> {
+    // 开头与目标编码相同的 BOM 不属于正文
+    let bytes = bytes.strip_prefix(encoding.bom()).unwrap_or(bytes);
+    match encoding {
+        Encoding::Utf8 => decode_utf8(bytes, lossy),
+        Encoding::Utf16Le => decode_utf16(bytes, false, lossy),
+        Encoding::Utf16Be => decode_utf16(bytes, true, lossy),
+        Encoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+    }
+}
+
+// 严格解码，遇到错误立即返回
+pub fn decode(bytes: &[u8], encoding: Encoding) -> Result<String, EncodingError> {
+    decode_with(bytes, encoding, false)
+}
+
+// 宽松解码，无法解码的部分替换为 U+FFFD
+pub fn decode_lossy(bytes: &[u8], encoding: Encoding) -> String {
+    decode_with(bytes, encoding, true).expect("宽松模式不会返回错误")
+}
+
+fn encode_with(s: &str, encoding: Encoding, lossy: bool) -> Result<Vec<u8>, EncodingError> {
+    match encoding {
+        Encoding::Utf8 => Ok(s.as_bytes().to_vec()),
+        Encoding::Utf16Le => Ok(encode_utf16(s, false)),
+        Encoding::Utf16Be => Ok(encode_utf16(s, true)),
+        Encoding::Latin1 => s
+            .char_indices()
+            .map(|(offset, ch)| match u8::try_from(ch) {
+                Ok(b) => Ok(b),
+                Err(_) if lossy => Ok(b'?'),
+                Err(_) => Err(EncodingError::Unmappable { ch, offset, encoding }),
+            })
+            .collect(),
+    }
+}
+
+// 严格编码，Latin-1 遇到无法表示的字符时返回错误
+pub fn encode(s: &str, encoding: Encoding) -> Result<Vec<u8>, EncodingError> {
+    encode_with(s, encoding, false)
+}
+
+// 宽松编码，无法表示的字符替换为 ?
+pub fn encode_lossy(s: &str, encoding: Encoding) -> Vec<u8> {
+    encode_with(s, encoding, true).expect("宽松模式不会返回错误")
+}
+
+/*
+    cargo run -- convert <输入文件> <输出文件> [--from 编码] [--to 编码] [--lossy] [--bom]
+        --from   默认根据 BOM 判断，没有 BOM 时按 UTF-8 处理
+        --to     默认 UTF-8
+        --lossy  遇到无法转换的内容时替换而不是报错
+        --bom    在输出文件开头写入 BOM
+ */
+pub fn cli(args: &[String]) {
+    if let Err(e) = convert(args) {
+        eprintln!("转换失败: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn convert(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    let (mut from, mut to, mut lossy, mut bom) = (None, Encoding::Utf8, false, false);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" => from = Some(iter.next().ok_or("--from 缺少参数")?.parse::<Encoding>()?),
+            "--to" => to = iter.next().ok_or("--to 缺少参数")?.parse::<Encoding>()?,
+            "--lossy" => lossy = true,
+            "--bom" => bom = true,
+            _ => files.push(arg),
+        }
+    }
+    let [input, output] = files[..] else {
+        return Err("用法: convert <输入文件> <输出文件> [--from 编码] [--to 编码] [--lossy] [--bom]".into());
+    };
+
+    let bytes = std::fs::read(input)?;
+    let from = from.unwrap_or_else(|| detect_bom(&bytes).map_or(Encoding::Utf8, |(e, _)| e));
+    if from == Encoding::Utf8 {
+        // 先把所有问题都报告出来，方便定位
+        for seq in utf8_errors(&bytes) {
+            eprintln!("警告: {}", EncodingError::InvalidUtf8(seq));
+        }
+    }
+    let text = if lossy { decode_lossy(&bytes, from) } else { decode(&bytes, from)? };
+    let mut out = if bom { to.bom().to_vec() } else { Vec::new() };
+    out.extend(if lossy { encode_lossy(&text, to) } else { encode(&text, to)? });
+    std::fs::write(output, &out)?;
+    println!("{input} ({from}, {} 字节) -> {output} ({to}, {} 字节)", bytes.len(), out.len());
+    Ok(())
+}
+
+pub fn run() {
+    let text = "Rust语言圣经🦀";
+
+    // UTF-16: 🦀 (U+1F980) 需要一对代理项 D83E DD80
+    let be = encode(text, Encoding::Utf16Be).unwrap();
+    println!("{text} 的 UTF-16BE 编码: {be:02X?}");
+
+    // 带 BOM 时可以自动识别编码
+    let mut with_bom = Encoding::Utf16Be.bom().to_vec();
+    with_bom.extend(&be);
+    let (detected, _) = detect_bom(&with_bom).unwrap();
+    println!("检测到 {detected}: {}", decode(&with_bom, detected).unwrap());
+
+    // 没有配对的代理项
+    let broken = [0x3E, 0xD8, 0x41, 0x00];
+    println!("{}", decode(&broken, Encoding::Utf16Le).unwrap_err());
+    dim_dbg!(decode_lossy(&broken, Encoding::Utf16Le));
+
+    // Latin-1 只能表示 256 个字符
+    let latin1 = encode("café", Encoding::Latin1).unwrap();
+    println!("café 的 Latin-1 编码: {latin1:02X?}");
+    println!("{}", encode("café 咖啡", Encoding::Latin1).unwrap_err());
+
+    // 损坏的 UTF-8: 缺了最后一个字节的 "语"、单独的后续字节、超长编码的 '/'、末尾被截断的 🦀
+    let bad = broken_utf8();
+    for seq in utf8_errors(&bad) {
+        println!("{}", EncodingError::InvalidUtf8(seq));
+    }
+    dim_dbg!(repair_utf8_lossy(&bad));
+}
+
+fn broken_utf8() -> Vec<u8> {
+    let mut bad = "测试".as_bytes().to_vec();
+    bad.extend([0xE8, 0xAF]);
+    bad.extend(b"ok");
+    bad.push(0x80);
+    bad.extend([0xC0, 0xAF]);
+    bad.extend([0xF0, 0x9F]);
+    bad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Rust语言圣经🦀";
+
+    #[test]
+    fn utf16_round_trip() {
+        let le = encode(TEXT, Encoding::Utf16Le).unwrap();
+        let be = encode(TEXT, Encoding::Utf16Be).unwrap();
+        // 🦀 (U+1F980) 需要一对代理项 D83E DD80
+        assert_eq!(&be[be.len() - 4..], [0xD8, 0x3E, 0xDD, 0x80]);
+        // 与标准库的 encode_utf16 对比
+        let std_units: Vec<u16> = TEXT.encode_utf16().collect();
+        assert_eq!(le, std_units.iter().flat_map(|u| u.to_le_bytes()).collect::<Vec<u8>>());
+        assert_eq!(be, std_units.iter().flat_map(|u| u.to_be_bytes()).collect::<Vec<u8>>());
+        assert_eq!(decode(&le, Encoding::Utf16Le).unwrap(), TEXT);
+        assert_eq!(decode(&be, Encoding::Utf16Be).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn bom_detection() {
+        for encoding in [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+            let mut bytes = encoding.bom().to_vec();
+            bytes.extend(encode(TEXT, encoding).unwrap());
+            assert_eq!(detect_bom(&bytes), Some((encoding, encoding.bom().len())), "{encoding}");
+            // 解码时去掉 BOM
+            assert_eq!(decode(&bytes, encoding).unwrap(), TEXT, "{encoding}");
+        }
+        assert_eq!(detect_bom(b"Rust"), None);
+        assert_eq!(detect_bom(&[]), None);
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        // 只有高代理项
+        let broken = [0x3E, 0xD8, 0x41, 0x00];
+        assert_eq!(decode(&broken, Encoding::Utf16Le), Err(EncodingError::UnpairedSurrogate { offset: 0, unit: 0xD83E }));
+        assert_eq!(decode_lossy(&broken, Encoding::Utf16Le), "\u{FFFD}A");
+        // 单独的低代理项
+        let broken = [0x00, 0x41, 0xDD, 0x80];
+        assert_eq!(decode(&broken, Encoding::Utf16Be), Err(EncodingError::UnpairedSurrogate { offset: 2, unit: 0xDD80 }));
+        // 高代理项出现在末尾
+        let broken = [0x3E, 0xD8];
+        assert_eq!(decode(&broken, Encoding::Utf16Le), Err(EncodingError::UnpairedSurrogate { offset: 0, unit: 0xD83E }));
+
+        // 与标准库的 from_utf16_lossy 对比
+        for units in [[0xD83E_u16, 0x0041], [0xDD80, 0xD83E], [0xD83E, 0xDD80]] {
+            let bytes: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+            assert_eq!(decode_lossy(&bytes, Encoding::Utf16Le), String::from_utf16_lossy(&units), "{units:04X?}");
+        }
+    }
+
+    #[test]
+    fn utf16_odd_length() {
+        assert_eq!(decode(&[0x41, 0x00, 0x42], Encoding::Utf16Le), Err(EncodingError::OddLength(3)));
+    }
+
+    #[test]
+    fn latin1() {
+        let latin1 = encode("café", Encoding::Latin1).unwrap();
+        assert_eq!(latin1, [b'c', b'a', b'f', 0xE9]);
+        assert_eq!(decode(&latin1, Encoding::Latin1).unwrap(), "café");
+        // 每个字节都能解码
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(encode(&decode(&all, Encoding::Latin1).unwrap(), Encoding::Latin1).unwrap(), all);
+
+        let err = encode("café 咖啡", Encoding::Latin1).unwrap_err();
+        assert_eq!(err, EncodingError::Unmappable { ch: '咖', offset: 6, encoding: Encoding::Latin1 });
+        assert_eq!(encode_lossy("café 咖啡", Encoding::Latin1), b"caf\xE9 ??");
+        // 把 Latin-1 的字节当作 UTF-8 读就会出错
+        assert!(decode(&latin1, Encoding::Utf8).is_err());
+    }
+
+    #[test]
+    fn invalid_utf8_offsets() {
+        let bad = broken_utf8();
+        let errors: Vec<_> = utf8_errors(&bad).iter().map(|s| (s.offset, s.len, s.kind)).collect();
+        assert_eq!(
+            errors,
+            [
+                (6, 2, Utf8ErrorKind::InvalidContinuation),
+                (10, 1, Utf8ErrorKind::InvalidLeadByte(0x80)),
+                (11, 1, Utf8ErrorKind::InvalidLeadByte(0xC0)),
+                (12, 1, Utf8ErrorKind::InvalidLeadByte(0xAF)),
+                (13, 2, Utf8ErrorKind::UnexpectedEnd),
+            ]
+        );
+        assert_eq!(repair_utf8_lossy(&bad), String::from_utf8_lossy(&bad));
+        assert!(utf8_errors(TEXT.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn repair_matches_std_lossy() {
+        // 用各种首字节组合和标准库的 from_utf8_lossy 做对比
+        for a in (0u8..=255).step_by(3) {
+            for b in [0x00, 0x80, 0x90, 0xA0, 0xBF, 0xC0] {
+                let bytes = [a, b, 0x80, 0xBF, b'x', a];
+                assert_eq!(repair_utf8_lossy(&bytes), String::from_utf8_lossy(&bytes), "{bytes:02X?}");
+            }
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub mod if_else {
 }
 
 pub mod loop_test {
+    use crate::basic_learning::style::dim_dbg;
 
     // continue 跳出本次循环
     // break    跳出整个循环
@@ -26,20 +27,20 @@ pub mod loop_test {
         // 如果不使用引用的话，所有权会被转移（move）到 for 语句块中
         // 后面就无法再使用这个集合了
         for _ in &a {}
-        dbg!(a);
+        dim_dbg!(a);
 
         let mut b = [String::from("1"), String::from("2"), String::from("3")];
         // 循环可变引用
         for i in &mut b {
             i.push_str("_new")
         }
-        dbg!(&b);
+        dim_dbg!(&b);
     }
 
     pub fn while_test() {
         let mut n = 0;
         while n <= 5 {
-            dbg!(n);
+            dim_dbg!(n);
             n = n + 1;
         }
         println!("循环结束！");
@@ -1,6 +1,8 @@
 // 在 Rust 中泛型是零成本的抽象，意味着你在使用泛型时，完全不用担心性能上的问题。
 // Rust 是在编译期为泛型对应的多个类型，生成各自的代码，因此损失了编译速度和增大了最终生成文件的大小。
 pub mod generic {
+    use crate::basic_learning::style::dim_dbg;
+
     pub fn to_struct() {
         #[derive(Debug)]
         struct Point<T> {
@@ -10,7 +12,7 @@ pub mod generic {
 
         let integer = Point { x: 5, y: 10 };
         let float = Point { x: 1.0, y: 4.0 };
-        dbg!(integer,float);
+        dim_dbg!(integer,float);
     }
 
     pub fn to_enum() {
@@ -25,9 +27,9 @@ pub mod generic {
             Err(E),
         }
         let some = Option::Some(18u8);
-        dbg!(some);
+        dim_dbg!(some);
         let ok = Option::Some(18u32);
-        dbg!(ok);
+        dim_dbg!(ok);
     }
 
     pub fn to_fn() {
@@ -54,12 +56,12 @@ pub mod generic {
             }
         }
         let p1 = Point { x: 10u32, y: 20u32 };
-        dbg!(&p1);
+        dim_dbg!(&p1);
         // p1.distance_from_origin(); // 报错,没有相关的方法
         let p2 = Point { x: 30.999f32, y: 40.0f32 };
-        dbg!(&p2);
+        dim_dbg!(&p2);
         let origin = p2.distance_from_origin();
-        dbg!(&origin);
+        dim_dbg!(&origin);
     }
 
     // const 泛型表达式
@@ -81,6 +83,9 @@ pub mod trait_test {
     use std::convert::TryInto;
     use std::fmt::Display;
 
+    use crate::basic_learning::builder::builder;
+    use crate::basic_learning::style::dim_dbg;
+
     // 定义特征
     trait Summary {
         // 使用 trait 关键字来声明一个特征
@@ -88,7 +93,8 @@ pub mod trait_test {
         fn summarize(&self) -> String;
     }
 
-    struct Post {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Post {
         pub title: String,
         // 标题
         pub author: String,
@@ -96,7 +102,8 @@ pub mod trait_test {
         pub content: String, // 内容
     }
 
-    struct Weibo {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Weibo {
         pub username: String,
         pub content: String
     }
@@ -117,6 +124,7 @@ pub mod trait_test {
     }
 
     pub fn run_1() {
+        // 下面几个例子反复 to_string() 同样的字面量，大量重复的字符串可以用 collections::interner 只保存一份
         let post = Post {
             title: "Rust语言简介".to_string(),
             author: "Sunface".to_string(),
@@ -146,6 +154,7 @@ pub mod trait_test {
         }
     }
 
+    #[derive(Debug)]
     pub struct Post1 {
         pub title: String,
         // 标题
@@ -154,6 +163,13 @@ pub mod trait_test {
         pub content: String, // 内容
     }
 
+    // content 可以省略，默认为空
+    builder!(pub Post1 => Post1Builder {
+        title: String,
+        author: String,
+        content: String = String::new(),
+    });
+
     pub struct Weibo1 {
         pub username: String,
         pub content: String
@@ -294,7 +310,7 @@ pub mod trait_test {
         let n = func();
         // 只知道返回了一个实现了 Summary 特征的对象
         let str = n.summarize();
-        dbg!(str);
+        dim_dbg!(str);
         // 并不知道他返回了一个 &str
         // println!("",n); // 报错
     }
@@ -318,6 +334,8 @@ pub mod trait_demo {
     use std::fmt::{Debug, Display, Formatter};
     use std::ops::Add;
 
+    use crate::basic_learning::style::dim_dbg;
+
     pub fn test_1() {
         // 同时实现了PartialOrd和Copy特征
         fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
@@ -332,9 +350,9 @@ pub mod trait_demo {
             largest
         }
 
-        dbg!(largest(&[3, 5, 1]));
+        dim_dbg!(largest(&[3, 5, 1]));
 
-        dbg!(largest(&['d', 'b', 'g']));
+        dim_dbg!(largest(&['d', 'b', 'g']));
     }
 
     // 给自定义类型实现 + 操作
@@ -365,11 +383,11 @@ pub mod trait_demo {
 
         let p1 = Point { x: 1.1f32, y: 1.1f32 };
         let p2 = Point { x: 2.1f32, y: 2.1f32 };
-        dbg!(p1 + p2);
+        dim_dbg!(p1 + p2);
 
         let p3 = Point { x: 1i32, y: 1i32 };
         let p4 = Point { x: 2i32, y: 2i32 };
-        dbg!(p3 + p4);
+        dim_dbg!(p3 + p4);
     }
 
     // 自定义类型的打印输出
@@ -420,6 +438,8 @@ pub mod trait_demo {
 
 // 特征对象 1
 pub mod trait_object_test_1 {
+    use crate::basic_learning::style::dim_dbg;
+
     pub struct Post {}
 
     pub struct Weibo {}
@@ -448,13 +468,16 @@ pub mod trait_object_test_1 {
     pub fn run() {
         let t = returns_summarizable(true);
         let f = returns_summarizable(false);
-        dbg!(t.summarize());
-        dbg!(f.summarize());
+        dim_dbg!(t.summarize());
+        dim_dbg!(f.summarize());
     }
 }
 
 // 特征对象 2
 pub mod trait_object_test_2 {
+    use crate::basic_learning::builder::builder;
+    use crate::basic_learning::style::dim_dbg;
+
     trait Draw {
         fn draw(&self) -> String;
     }
@@ -488,11 +511,11 @@ pub mod trait_object_test_2 {
 
         // x 和 y 的类型 T 都实现了 `Draw` 特征，因为 Box<T> 可以在函数调用时隐式地被转换为特征对象 Box<dyn Draw>
         // 基于 x 的值创建一个 Box<f64> 类型的智能指针，指针指向的数据被放置在了堆上
-        dbg!(draw1(Box::new(x)));
+        dim_dbg!(draw1(Box::new(x)));
         // 基于 y 的值创建一个 Box<u8> 类型的智能指针
-        dbg!(draw1(Box::new(y)));
-        dbg!(draw2(&x));
-        dbg!(draw2(&y));
+        dim_dbg!(draw1(Box::new(y)));
+        dim_dbg!(draw2(&x));
+        dim_dbg!(draw2(&y));
         /*
             draw1 函数的参数是 Box<dyn Draw> 形式的特征对象，该特征对象是通过 Box::new(x) 的方式创建的
             draw2 函数的参数是 &dyn Draw 形式的特征对象，该特征对象是通过 &x 的方式创建的
@@ -500,12 +523,20 @@ pub mod trait_object_test_2 {
         */
     }
 
+    #[derive(Debug)]
     pub struct Button {
         pub width: u32,
         pub height: u32,
         pub label: String,
     }
 
+    // 按钮的尺寸有默认值，文字必须设置
+    builder!(pub Button => ButtonBuilder {
+        width: u32 = 50,
+        height: u32 = 10,
+        label: String,
+    });
+
     impl Draw for Button {
         fn draw(&self) -> String { "Button Draw".to_string() }
     }
@@ -586,6 +617,8 @@ pub mod trait_object_test_2 {
 // self 与 Self
 // 一个指代当前的实例对象，一个指代特征或者方法类型的别名
 pub mod self_demo {
+    use crate::basic_learning::style::dim_dbg;
+
     trait Draw {
         fn draw(&self) -> Self;
     }
@@ -602,7 +635,7 @@ pub mod self_demo {
     pub fn run() {
         let button = Button;
         let new_b = button.draw();
-        dbg!(new_b);
+        dim_dbg!(new_b);
     }
 }
 
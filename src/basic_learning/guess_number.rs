@@ -2,9 +2,11 @@ use std::io;
 use std::cmp::Ordering;
 use rand::Rng;
 
+use crate::basic_learning::style;
+
 // https://kaisery.github.io/trpl-zh-cn/ch02-00-guessing-game-tutorial.html
 pub fn run() {
-    println!("Guess the number!");
+    style::heading("Guess the number!");
 
     let secret_number: u8 = rand::thread_rng().gen_range(1..=100);
 
@@ -28,10 +30,11 @@ pub fn run() {
         };
 
         match guess.cmp(&secret_number) {
-            Ordering::Less => println!("猜小了!"),
-            Ordering::Greater => println!("猜大了!"),
+            // 终端中用不同的颜色区分，输出重定向时不带颜色
+            Ordering::Less => println!("{}", style::LESS.paint("猜小了!")),
+            Ordering::Greater => println!("{}", style::GREATER.paint("猜大了!")),
             Ordering::Equal => {
-                println!("猜对了!");
+                println!("{}", style::EQUAL.paint("猜对了!"));
                 break;
             }
         }
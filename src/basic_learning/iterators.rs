@@ -0,0 +1,383 @@
+// 迭代器
+// 只要实现了 Iterator 特征的 next 方法，就能免费获得 map、filter 等几十个适配器方法
+// 这里自己动手实现一遍常用的适配器，看看它们内部是怎么工作的
+use crate::basic_learning::style::dim_dbg;
+
+/*
+    pub trait Iterator {
+        type Item;
+        fn next(&mut self) -> Option<Self::Item>;
+        // 省略其余有默认实现的方法
+    }
+
+    适配器本身也是一个迭代器，它持有上游的迭代器，在自己的 next 中调用上游的 next
+    适配器是惰性的：在调用 next 之前，不会做任何事情
+ */
+pub mod adapters {
+    use std::collections::VecDeque;
+
+    // map: 对每个元素调用 f
+    pub struct Map<I, F> {
+        iter: I,
+        f: F,
+    }
+
+    impl<B, I: Iterator, F: FnMut(I::Item) -> B> Iterator for Map<I, F> {
+        type Item = B;
+        fn next(&mut self) -> Option<B> {
+            self.iter.next().map(&mut self.f)
+        }
+
+        // map 不改变元素个数，直接沿用上游的估计
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.iter.size_hint()
+        }
+    }
+
+    // filter: 只保留 predicate 返回 true 的元素
+    pub struct Filter<I, P> {
+        iter: I,
+        predicate: P,
+    }
+
+    impl<I: Iterator, P: FnMut(&I::Item) -> bool> Iterator for Filter<I, P> {
+        type Item = I::Item;
+        fn next(&mut self) -> Option<I::Item> {
+            // 不断向上游要元素，直到遇到满足条件的
+            loop {
+                let item = self.iter.next()?;
+                if (self.predicate)(&item) {
+                    return Some(item);
+                }
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (0, self.iter.size_hint().1)
+        }
+    }
+
+    // zip: 把两个迭代器拉链式地合并成元组，任意一边结束就结束
+    pub struct Zip<A, B> {
+        a: A,
+        b: B,
+    }
+
+    impl<A: Iterator, B: Iterator> Iterator for Zip<A, B> {
+        type Item = (A::Item, B::Item);
+        fn next(&mut self) -> Option<Self::Item> {
+            let x = self.a.next()?;
+            let y = self.b.next()?;
+            Some((x, y))
+        }
+    }
+
+    // chain: 先迭代完 a，再迭代 b
+    pub struct Chain<A, B> {
+        // a 用完后置为 None，避免再次调用已经结束的迭代器
+        a: Option<A>,
+        b: B,
+    }
+
+    impl<A: Iterator, B: Iterator<Item = A::Item>> Iterator for Chain<A, B> {
+        type Item = A::Item;
+        fn next(&mut self) -> Option<A::Item> {
+            if let Some(a) = &mut self.a {
+                match a.next() {
+                    Some(item) => return Some(item),
+                    None => self.a = None,
+                }
+            }
+            self.b.next()
+        }
+    }
+
+    /*
+        windows / chunks: 切片上有同名方法 [T]::windows、[T]::chunks
+        这里的版本作用于任意迭代器，所以只能把元素收集到 Vec 里返回
+     */
+
+    // windows: 长度为 size 的滑动窗口，每次前进一个元素
+    pub struct Windows<I: Iterator> {
+        iter: I,
+        size: usize,
+        window: VecDeque<I::Item>,
+    }
+
+    impl<I: Iterator> Iterator for Windows<I>
+    where
+        I::Item: Clone,
+    {
+        type Item = Vec<I::Item>;
+        fn next(&mut self) -> Option<Self::Item> {
+            // 第一次调用时填满窗口，之后每次移出最旧的一个、放入一个新的
+            if self.window.len() == self.size {
+                self.window.pop_front();
+            }
+            while self.window.len() < self.size {
+                self.window.push_back(self.iter.next()?);
+            }
+            Some(self.window.iter().cloned().collect())
+        }
+    }
+
+    // chunks: 每 size 个元素一组，最后一组可能不满
+    pub struct Chunks<I> {
+        iter: I,
+        size: usize,
+    }
+
+    impl<I: Iterator> Iterator for Chunks<I> {
+        type Item = Vec<I::Item>;
+        fn next(&mut self) -> Option<Self::Item> {
+            let chunk: Vec<I::Item> = self.iter.by_ref().take(self.size).collect();
+            if chunk.is_empty() { None } else { Some(chunk) }
+        }
+    }
+
+    // step_by: 先返回第一个元素，之后每次跳过 step - 1 个
+    pub struct StepBy<I> {
+        iter: I,
+        step: usize,
+        first_take: bool,
+    }
+
+    impl<I: Iterator> Iterator for StepBy<I> {
+        type Item = I::Item;
+        fn next(&mut self) -> Option<I::Item> {
+            if self.first_take {
+                self.first_take = false;
+                self.iter.next()
+            } else {
+                // nth(n) 会跳过 n 个元素，返回第 n + 1 个
+                self.iter.nth(self.step - 1)
+            }
+        }
+    }
+
+    // peekable: 可以偷看下一个元素而不消费它
+    pub struct Peekable<I: Iterator> {
+        iter: I,
+        // None: 还没偷看过；Some(None): 偷看过，上游已经结束
+        peeked: Option<Option<I::Item>>,
+    }
+
+    impl<I: Iterator> Peekable<I> {
+        pub fn peek(&mut self) -> Option<&I::Item> {
+            let iter = &mut self.iter;
+            self.peeked.get_or_insert_with(|| iter.next()).as_ref()
+        }
+
+        // 下一个元素满足条件时才消费它
+        pub fn next_if(&mut self, f: impl FnOnce(&I::Item) -> bool) -> Option<I::Item> {
+            match self.next() {
+                Some(item) if f(&item) => Some(item),
+                other => {
+                    // 不满足条件，放回去
+                    self.peeked = Some(other);
+                    None
+                }
+            }
+        }
+    }
+
+    impl<I: Iterator> Iterator for Peekable<I> {
+        type Item = I::Item;
+        fn next(&mut self) -> Option<I::Item> {
+            match self.peeked.take() {
+                Some(peeked) => peeked,
+                None => self.iter.next(),
+            }
+        }
+    }
+
+    // flat_map: f 返回一个可迭代的值，把它们展平成一个迭代器
+    pub struct FlatMap<I, U: IntoIterator, F> {
+        iter: I,
+        f: F,
+        current: Option<U::IntoIter>,
+    }
+
+    impl<I: Iterator, U: IntoIterator, F: FnMut(I::Item) -> U> Iterator for FlatMap<I, U, F> {
+        type Item = U::Item;
+        fn next(&mut self) -> Option<U::Item> {
+            loop {
+                if let Some(item) = self.current.as_mut().and_then(Iterator::next) {
+                    return Some(item);
+                }
+                // 当前的内层迭代器用完了，从外层取下一个
+                self.current = Some((self.f)(self.iter.next()?).into_iter());
+            }
+        }
+    }
+
+    // group_by: 把 key 相同的 *连续* 元素分到一组，类似 Unix 的 uniq
+    pub struct GroupBy<I: Iterator, F> {
+        iter: Peekable<I>,
+        key: F,
+    }
+
+    impl<K: PartialEq, I: Iterator, F: FnMut(&I::Item) -> K> Iterator for GroupBy<I, F> {
+        type Item = (K, Vec<I::Item>);
+        fn next(&mut self) -> Option<Self::Item> {
+            let first = self.iter.next()?;
+            let key = (self.key)(&first);
+            let mut group = vec![first];
+            while let Some(item) = self.iter.next_if(|item| (self.key)(item) == key) {
+                group.push(item);
+            }
+            Some((key, group))
+        }
+    }
+
+    /*
+        扩展特征(extension trait): 给所有实现了 Iterator 的类型添加新方法
+        方法名加上 my_ 前缀，避免与 Iterator 自带的同名方法冲突
+     */
+    pub trait MyIteratorExt: Iterator + Sized {
+        fn my_map<B, F: FnMut(Self::Item) -> B>(self, f: F) -> Map<Self, F> {
+            Map { iter: self, f }
+        }
+
+        fn my_filter<P: FnMut(&Self::Item) -> bool>(self, predicate: P) -> Filter<Self, P> {
+            Filter { iter: self, predicate }
+        }
+
+        fn my_zip<U: IntoIterator>(self, other: U) -> Zip<Self, U::IntoIter> {
+            Zip { a: self, b: other.into_iter() }
+        }
+
+        fn my_chain<U: IntoIterator<Item = Self::Item>>(self, other: U) -> Chain<Self, U::IntoIter> {
+            Chain { a: Some(self), b: other.into_iter() }
+        }
+
+        fn my_windows(self, size: usize) -> Windows<Self> {
+            assert!(size != 0, "window size must be non-zero");
+            Windows { iter: self, size, window: VecDeque::with_capacity(size) }
+        }
+
+        fn my_chunks(self, size: usize) -> Chunks<Self> {
+            assert!(size != 0, "chunk size must be non-zero");
+            Chunks { iter: self, size }
+        }
+
+        fn my_step_by(self, step: usize) -> StepBy<Self> {
+            assert!(step != 0, "step must be non-zero");
+            StepBy { iter: self, step, first_take: true }
+        }
+
+        fn my_peekable(self) -> Peekable<Self> {
+            Peekable { iter: self, peeked: None }
+        }
+
+        fn my_flat_map<U: IntoIterator, F: FnMut(Self::Item) -> U>(self, f: F) -> FlatMap<Self, U, F> {
+            FlatMap { iter: self, f, current: None }
+        }
+
+        fn my_group_by<K: PartialEq, F: FnMut(&Self::Item) -> K>(self, key: F) -> GroupBy<Self, F> {
+            GroupBy { iter: self.my_peekable(), key }
+        }
+    }
+
+    // 为所有迭代器实现扩展特征
+    impl<I: Iterator> MyIteratorExt for I {}
+}
+
+pub fn run() {
+    use adapters::MyIteratorExt;
+
+    let v = [1, 2, 3, 4, 5, 6, 7];
+    dim_dbg!(v.iter().copied().my_windows(3).collect::<Vec<_>>());
+    dim_dbg!(v.iter().copied().my_chunks(3).collect::<Vec<_>>());
+    dim_dbg!(v.iter().my_step_by(3).collect::<Vec<_>>());
+
+    // 适配器可以像标准库一样链式调用
+    let text = "rust rust 语言 语言 语言 圣经";
+    let counted: Vec<String> = text
+        .split(' ')
+        .my_group_by(|w| w.to_string())
+        .my_map(|(word, group)| format!("{word} x{}", group.len()))
+        .collect();
+    dim_dbg!(&counted);
+}
+
+// 与标准库的实现逐一对比
+#[cfg(test)]
+mod tests {
+    use super::adapters::MyIteratorExt;
+
+    const V: [i32; 7] = [1, 2, 3, 4, 5, 6, 7];
+
+    #[test]
+    fn map() {
+        assert!(V.iter().my_map(|x| x * 10).eq(V.iter().map(|x| x * 10)));
+    }
+
+    #[test]
+    fn filter() {
+        assert!(V.iter().my_filter(|x| *x % 2 == 0).eq(V.iter().filter(|x| *x % 2 == 0)));
+    }
+
+    #[test]
+    fn zip() {
+        let names = ["张三", "李四", "王五"];
+        assert!(names.iter().my_zip(&V).eq(names.iter().zip(&V)));
+        assert!(V.iter().my_zip(&names).eq(V.iter().zip(&names)));
+    }
+
+    #[test]
+    fn chain() {
+        assert!(V.iter().my_chain(&V).eq(V.iter().chain(&V)));
+        assert!(V.iter().my_chain([]).eq(V.iter().chain([])));
+    }
+
+    // windows / chunks 与切片上的同名方法对比
+    #[test]
+    fn windows() {
+        for size in 1..=8 {
+            let ours: Vec<Vec<i32>> = V.iter().copied().my_windows(size).collect();
+            let std: Vec<Vec<i32>> = V.windows(size).map(<[i32]>::to_vec).collect();
+            assert_eq!(ours, std, "size = {size}");
+        }
+    }
+
+    #[test]
+    fn chunks() {
+        for size in 1..=8 {
+            let ours: Vec<Vec<i32>> = V.iter().copied().my_chunks(size).collect();
+            let std: Vec<Vec<i32>> = V.chunks(size).map(<[i32]>::to_vec).collect();
+            assert_eq!(ours, std, "size = {size}");
+        }
+    }
+
+    #[test]
+    fn step_by() {
+        for step in 1..=8 {
+            assert!(V.iter().my_step_by(step).eq(V.iter().step_by(step)), "step = {step}");
+        }
+    }
+
+    #[test]
+    fn peekable() {
+        let mut ours = V.iter().my_peekable();
+        let mut std = V.iter().peekable();
+        assert_eq!(ours.peek(), std.peek());
+        assert_eq!(ours.next_if(|x| **x == 1), std.next_if(|x| **x == 1));
+        assert_eq!(ours.next_if(|x| **x == 100), std.next_if(|x| **x == 100));
+        assert!(ours.eq(std));
+    }
+
+    #[test]
+    fn flat_map() {
+        let words = ["hello", "", "世界"];
+        assert!(words.iter().my_flat_map(|w| w.chars()).eq(words.iter().flat_map(|w| w.chars())));
+    }
+
+    // 标准库没有 group_by 适配器，用 chunk_by (Rust 1.77 起) 对比
+    #[test]
+    fn group_by() {
+        let ours: Vec<Vec<i32>> = V.iter().copied().my_group_by(|x| x / 3).map(|(_, g)| g).collect();
+        let std: Vec<Vec<i32>> = V.chunk_by(|a, b| a / 3 == b / 3).map(<[i32]>::to_vec).collect();
+        assert_eq!(ours, std);
+    }
+}
@@ -0,0 +1,1011 @@
+// JSON
+/*
+    不依赖 serde，手写一个 JSON 库:
+        - Value         JSON 值，对象保留键的插入顺序
+        - Parser        流式解析器，从任意 io::Read 逐字节读取，产生一个个事件，出错时报告行号和列号
+        - Display       {} 输出紧凑格式，{:#} 输出带缩进的格式
+        - ToJson / FromJson   Rust 类型与 Value 之间的转换
+    数字统一用 f64 表示，超过 2^53 的整数会丢失精度
+ */
+use std::fmt;
+use std::io::{self, BufReader, Read};
+
+use crate::basic_learning::compound_type::enum_test::{PokerCard, PokerSuit};
+use crate::basic_learning::compound_type::struct_test::{Rgb, User};
+use crate::basic_learning::generic_and_traits::trait_test::{Post, Weibo};
+use crate::basic_learning::match_pattern::all_patterns::Message;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    // Value::object([("name", "Sunface".to_json()), ...])
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Value)>) -> Value {
+        Value::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "布尔值",
+            Value::Number(_) => "数字",
+            Value::String(_) => "字符串",
+            Value::Array(_) => "数组",
+            Value::Object(_) => "对象",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum JsonError {
+    // 行号和列号都从 1 开始，列号按字符计算
+    Syntax { message: String, line: usize, column: usize },
+    Io(io::Error),
+    // 转换成 Rust 类型时出错，path 是出错的位置，例如 $.cards[2].suit
+    Convert { path: String, message: String },
+}
+
+impl JsonError {
+    fn convert(message: impl Into<String>) -> Self {
+        JsonError::Convert { path: "$".to_string(), message: message.into() }
+    }
+
+    fn expected(what: &str, found: &Value) -> Self {
+        Self::convert(format!("应为{what}，实际是{}", found.type_name()))
+    }
+
+    // 错误从内层往外传递时，在路径前面补上当前所在的字段或下标
+    fn at(self, segment: &str) -> Self {
+        match self {
+            JsonError::Convert { path, message } => {
+                let rest = path.strip_prefix('$').unwrap_or(&path);
+                JsonError::Convert { path: format!("${segment}{rest}"), message }
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax { message, line, column } => write!(f, "第 {line} 行第 {column} 列: {message}"),
+            JsonError::Io(e) => write!(f, "读取失败: {e}"),
+            JsonError::Convert { path, message } => write!(f, "{path}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<io::Error> for JsonError {
+    fn from(e: io::Error) -> Self {
+        JsonError::Io(e)
+    }
+}
+
+// 解析器产生的事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    // 对象中的键，后面紧跟着它的值
+    Key(String),
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+// 解析器接下来期望看到的内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Value,
+    // 刚读完 {，可以是键或者 }
+    KeyOrEnd,
+    // 读完 , 之后必须是键
+    Key,
+    // 刚读完 [，可以是值或者 ]
+    ValueOrEnd,
+    // 读完一个值，可以是 , 或者结束当前容器
+    CommaOrEnd,
+    Done,
+}
+
+/*
+    流式解析器，不需要把整个输入读进内存，也不会构建整棵树
+    用 next_event 逐个取出事件，适合处理很大的文件或者只关心其中一部分内容的场景
+    {"a": [1, true]} 产生的事件:
+        StartObject  Key("a")  StartArray  Number(1)  Bool(true)  EndArray  EndObject
+ */
+// 容器最多嵌套的层数，parse_value 递归构建 Value，层数过多会把栈撑爆
+pub const MAX_DEPTH: usize = 128;
+
+pub struct Parser<R> {
+    bytes: io::Bytes<BufReader<R>>,
+    peeked: Option<u8>,
+    line: usize,
+    column: usize,
+    // 当前所在的容器，true 表示对象，false 表示数组
+    stack: Vec<bool>,
+    state: State,
+}
+
+impl<R: Read> Parser<R> {
+    // 内部加了一层缓冲，逐字节读取时不会每次都调用一次 read
+    pub fn new(reader: R) -> Self {
+        Parser { bytes: BufReader::new(reader).bytes(), peeked: None, line: 1, column: 1, stack: Vec::new(), state: State::Value }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, JsonError> {
+        Err(JsonError::Syntax { message: message.into(), line: self.line, column: self.column })
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, JsonError> {
+        if self.peeked.is_none() {
+            self.peeked = self.bytes.next().transpose()?;
+        }
+        Ok(self.peeked)
+    }
+
+    fn bump(&mut self) -> Result<Option<u8>, JsonError> {
+        let b = self.peek()?;
+        self.peeked = None;
+        match b {
+            Some(b'\n') => {
+                self.line += 1;
+                self.column = 1;
+            }
+            // UTF-8 的后续字节不单独占一列
+            Some(b) if b & 0xC0 != 0x80 => self.column += 1,
+            _ => {}
+        }
+        Ok(b)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<Option<u8>, JsonError> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.bump()?;
+        }
+        self.peek()
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), JsonError> {
+        for expected in literal.bytes() {
+            if self.peek()? != Some(expected) {
+                return self.error(format!("无效的字面量，应为 {literal}"));
+            }
+            self.bump()?;
+        }
+        Ok(())
+    }
+
+    fn after_value(&mut self) {
+        self.state = if self.stack.is_empty() { State::Done } else { State::CommaOrEnd };
+    }
+
+    fn describe(b: Option<u8>) -> String {
+        match b {
+            None => "输入意外结束".to_string(),
+            Some(b) if b.is_ascii_graphic() => format!("意外的字符 '{}'", b as char),
+            Some(b) => format!("意外的字节 0x{b:02X}"),
+        }
+    }
+
+    // 返回下一个事件，整个文档解析完后返回 None
+    pub fn next_event(&mut self) -> Result<Option<Event>, JsonError> {
+        let b = self.skip_whitespace()?;
+        match self.state {
+            State::Done => match b {
+                None => Ok(None),
+                Some(_) => self.error(format!("{}，文档已经结束", Self::describe(b))),
+            },
+            State::KeyOrEnd | State::Key => match b {
+                Some(b'}') if self.state == State::KeyOrEnd => self.end_container(true),
+                Some(b'"') => {
+                    let key = self.string()?;
+                    if self.skip_whitespace()? != Some(b':') {
+                        return self.error("键后面应为 ':'");
+                    }
+                    self.bump()?;
+                    self.state = State::Value;
+                    Ok(Some(Event::Key(key)))
+                }
+                _ => self.error(format!("{}，应为用双引号括起来的键", Self::describe(b))),
+            },
+            State::CommaOrEnd => {
+                let is_object = *self.stack.last().unwrap();
+                match b {
+                    Some(b',') => {
+                        self.bump()?;
+                        self.state = if is_object { State::Key } else { State::Value };
+                        self.next_event()
+                    }
+                    Some(b'}') if is_object => self.end_container(true),
+                    Some(b']') if !is_object => self.end_container(false),
+                    _ => self.error(format!("{}，应为 ',' 或 '{}'", Self::describe(b), if is_object { '}' } else { ']' })),
+                }
+            }
+            State::ValueOrEnd if b == Some(b']') => self.end_container(false),
+            State::Value | State::ValueOrEnd => self.value(b),
+        }
+    }
+
+    fn end_container(&mut self, is_object: bool) -> Result<Option<Event>, JsonError> {
+        self.bump()?;
+        self.stack.pop();
+        self.after_value();
+        Ok(Some(if is_object { Event::EndObject } else { Event::EndArray }))
+    }
+
+    fn value(&mut self, b: Option<u8>) -> Result<Option<Event>, JsonError> {
+        let event = match b {
+            Some(b'{') | Some(b'[') => {
+                if self.stack.len() >= MAX_DEPTH {
+                    return self.error(format!("嵌套超过 {MAX_DEPTH} 层"));
+                }
+                self.bump()?;
+                let is_object = b == Some(b'{');
+                self.stack.push(is_object);
+                self.state = if is_object { State::KeyOrEnd } else { State::ValueOrEnd };
+                return Ok(Some(if is_object { Event::StartObject } else { Event::StartArray }));
+            }
+            Some(b'"') => Event::String(self.string()?),
+            Some(b't') => self.expect("true").map(|_| Event::Bool(true))?,
+            Some(b'f') => self.expect("false").map(|_| Event::Bool(false))?,
+            Some(b'n') => self.expect("null").map(|_| Event::Null)?,
+            Some(b'-' | b'0'..=b'9') => Event::Number(self.number()?),
+            _ => return self.error(format!("{}，应为一个值", Self::describe(b))),
+        };
+        self.after_value();
+        Ok(Some(event))
+    }
+
+    // -?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?
+    fn number(&mut self) -> Result<f64, JsonError> {
+        let mut text = String::new();
+        let digits = |parser: &mut Self, text: &mut String| -> Result<usize, JsonError> {
+            let mut count = 0;
+            while let Some(b @ b'0'..=b'9') = parser.peek()? {
+                text.push(b as char);
+                parser.bump()?;
+                count += 1;
+            }
+            Ok(count)
+        };
+        if self.peek()? == Some(b'-') {
+            text.push('-');
+            self.bump()?;
+        }
+        if self.peek()? == Some(b'0') {
+            text.push('0');
+            self.bump()?;
+            if matches!(self.peek()?, Some(b'0'..=b'9')) {
+                return self.error("数字不能以 0 开头");
+            }
+        } else if digits(self, &mut text)? == 0 {
+            return self.error("负号后面应为数字");
+        }
+        if self.peek()? == Some(b'.') {
+            text.push('.');
+            self.bump()?;
+            if digits(self, &mut text)? == 0 {
+                return self.error("小数点后面应为数字");
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek()? {
+            text.push('e');
+            self.bump()?;
+            if let Some(sign @ (b'+' | b'-')) = self.peek()? {
+                text.push(sign as char);
+                self.bump()?;
+            }
+            if digits(self, &mut text)? == 0 {
+                return self.error("指数部分应为数字");
+            }
+        }
+        Ok(text.parse().unwrap())
+    }
+
+    fn hex4(&mut self) -> Result<u16, JsonError> {
+        let mut n = 0;
+        for _ in 0..4 {
+            let digit = match self.peek()? {
+                Some(b) if b.is_ascii_hexdigit() => (b as char).to_digit(16).unwrap() as u16,
+                _ => return self.error("\\u 后面应为 4 位十六进制数"),
+            };
+            self.bump()?;
+            n = n * 16 + digit;
+        }
+        Ok(n)
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        // 跳过开头的引号
+        self.bump()?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek()? {
+                None => return self.error("字符串没有结束"),
+                Some(b'"') => {
+                    self.bump()?;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.bump()?;
+                    let c = match self.bump()? {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return self.error("无效的转义字符"),
+                    };
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                Some(b) if b < 0x20 => return self.error("字符串中的控制字符必须转义"),
+                Some(b) => {
+                    bytes.push(b);
+                    self.bump()?;
+                }
+            }
+        }
+        match String::from_utf8(bytes) {
+            Ok(s) => Ok(s),
+            Err(_) => self.error("字符串不是合法的 UTF-8"),
+        }
+    }
+
+    // 🦀 这样用一对代理项表示 BMP 以外的字符
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        match high {
+            0xD800..=0xDBFF => {}
+            // 低代理项只能跟在高代理项后面
+            0xDC00..=0xDFFF => return self.error("低代理项前面缺少高代理项"),
+            _ => return Ok(char::from_u32(high as u32).unwrap()),
+        }
+        if self.bump()? != Some(b'\\') || self.bump()? != Some(b'u') {
+            return self.error("高代理项后面应为 \\u 低代理项");
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return self.error("无效的低代理项");
+        }
+        let c = 0x10000 + (((high as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+        Ok(char::from_u32(c).unwrap())
+    }
+
+    // 读取一个完整的值，用事件构建出 Value
+    pub fn parse_value(&mut self) -> Result<Value, JsonError> {
+        match self.next_event()? {
+            Some(event) => self.build(event),
+            None => self.error("输入意外结束"),
+        }
+    }
+
+    fn build(&mut self, event: Event) -> Result<Value, JsonError> {
+        Ok(match event {
+            Event::Null => Value::Null,
+            Event::Bool(b) => Value::Bool(b),
+            Event::Number(n) => Value::Number(n),
+            Event::String(s) => Value::String(s),
+            Event::StartArray => {
+                let mut items = Vec::new();
+                loop {
+                    match self.next_event()? {
+                        Some(Event::EndArray) => break,
+                        Some(event) => items.push(self.build(event)?),
+                        None => return self.error("输入意外结束"),
+                    }
+                }
+                Value::Array(items)
+            }
+            Event::StartObject => {
+                let mut fields: Vec<(String, Value)> = Vec::new();
+                loop {
+                    let key = match self.next_event()? {
+                        Some(Event::EndObject) => break,
+                        Some(Event::Key(key)) => key,
+                        _ => return self.error("应为键"),
+                    };
+                    let value = self.parse_value()?;
+                    // 重复的键以最后一个为准
+                    match fields.iter_mut().find(|(k, _)| *k == key) {
+                        Some(field) => field.1 = value,
+                        None => fields.push((key, value)),
+                    }
+                }
+                Value::Object(fields)
+            }
+            // next_event 保证了容器的开始和结束是配对的
+            Event::EndArray | Event::EndObject | Event::Key(_) => unreachable!(),
+        })
+    }
+}
+
+// 解析一个完整的文档，后面不能有多余的内容
+pub fn parse(text: &str) -> Result<Value, JsonError> {
+    from_reader(text.as_bytes())
+}
+
+pub fn from_reader<R: Read>(reader: R) -> Result<Value, JsonError> {
+    let mut parser = Parser::new(reader);
+    let value = parser.parse_value()?;
+    match parser.next_event()? {
+        None => Ok(value),
+        Some(_) => unreachable!("next_event reports trailing content as an error"),
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{8}' => f.write_str("\\b")?,
+            '\u{c}' => f.write_str("\\f")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: &Value, indent: Option<usize>) -> fmt::Result {
+    // 带缩进时每个元素单独一行
+    let newline = |f: &mut fmt::Formatter<'_>, level: usize| match indent {
+        Some(_) => write!(f, "\n{}", "  ".repeat(level)),
+        None => Ok(()),
+    };
+    let level = indent.unwrap_or(0);
+    let inner = indent.map(|i| i + 1);
+    match value {
+        Value::Null => f.write_str("null"),
+        Value::Bool(b) => write!(f, "{b}"),
+        // NaN 和无穷大在 JSON 中无法表示
+        Value::Number(n) if !n.is_finite() => f.write_str("null"),
+        // 整数不输出小数点
+        Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+        Value::Number(n) => write!(f, "{n}"),
+        Value::String(s) => write_string(f, s),
+        Value::Array(items) if items.is_empty() => f.write_str("[]"),
+        Value::Array(items) => {
+            f.write_str("[")?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                newline(f, level + 1)?;
+                write_value(f, item, inner)?;
+            }
+            newline(f, level)?;
+            f.write_str("]")
+        }
+        Value::Object(fields) if fields.is_empty() => f.write_str("{}"),
+        Value::Object(fields) => {
+            f.write_str("{")?;
+            for (i, (key, item)) in fields.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                newline(f, level + 1)?;
+                write_string(f, key)?;
+                f.write_str(if indent.is_some() { ": " } else { ":" })?;
+                write_value(f, item, inner)?;
+            }
+            newline(f, level)?;
+            f.write_str("}")
+        }
+    }
+}
+
+// {} 输出紧凑格式，{:#} 输出两个空格缩进的格式
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self, f.alternate().then_some(0))
+    }
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, JsonError>;
+}
+
+pub fn to_string<T: ToJson + ?Sized>(value: &T) -> String {
+    value.to_json().to_string()
+}
+
+pub fn to_string_pretty<T: ToJson + ?Sized>(value: &T) -> String {
+    format!("{:#}", value.to_json())
+}
+
+pub fn from_str<T: FromJson>(text: &str) -> Result<T, JsonError> {
+    T::from_json(&parse(text)?)
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(value.clone())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            other => Err(JsonError::expected("布尔值", other)),
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        value.as_str().map(str::to_string).ok_or_else(|| JsonError::expected("字符串", value))
+    }
+}
+
+impl ToJson for f64 {
+    fn to_json(&self) -> Value {
+        Value::Number(*self)
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        value.as_f64().ok_or_else(|| JsonError::expected("数字", value))
+    }
+}
+
+// 整数类型: 必须是整数，并且在类型的取值范围内
+macro_rules! impl_json_int {
+    ($($t:ty),*) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    Value::Number(*self as f64)
+                }
+            }
+
+            impl FromJson for $t {
+                fn from_json(value: &Value) -> Result<Self, JsonError> {
+                    let n = f64::from_json(value)?;
+                    // 上界取 2 的幂，可以用 f64 精确表示；u64::MAX as f64 会舍入成 2^64，不能拿来比较
+                    let limit = 2f64.powi(<$t>::BITS as i32 - if <$t>::MIN == 0 { 0 } else { 1 });
+                    if n.fract() != 0.0 || n < <$t>::MIN as f64 || n >= limit {
+                        return Err(JsonError::convert(format!("{n} 不是 {} 类型的值", stringify!($t))));
+                    }
+                    Ok(n as $t)
+                }
+            }
+        )*
+    };
+}
+impl_json_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let items = value.as_array().ok_or_else(|| JsonError::expected("数组", value))?;
+        items.iter().enumerate().map(|(i, item)| T::from_json(item).map_err(|e| e.at(&format!("[{i}]")))).collect()
+    }
+}
+
+// None 对应 null
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::to_json)
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value {
+            Value::Null => Ok(None),
+            other => T::from_json(other).map(Some),
+        }
+    }
+}
+
+// 从对象中取出一个字段并转换，缺少的 Option 字段当作 null
+fn field<T: FromJson>(value: &Value, key: &str) -> Result<T, JsonError> {
+    if !matches!(value, Value::Object(_)) {
+        return Err(JsonError::expected("对象", value));
+    }
+    T::from_json(value.get(key).unwrap_or(&Value::Null)).map_err(|e| match value.get(key) {
+        Some(_) => e.at(&format!(".{key}")),
+        None => JsonError::convert(format!("缺少字段 {key}")),
+    })
+}
+
+// 元组结构体和元组成员按数组处理，elements 返回固定长度的数组
+fn elements<const N: usize>(value: &Value) -> Result<&[Value; N], JsonError> {
+    let items = value.as_array().ok_or_else(|| JsonError::expected("数组", value))?;
+    items.try_into().map_err(|_| JsonError::convert(format!("应为 {N} 个元素的数组，实际有 {} 个", items.len())))
+}
+
+fn element<T: FromJson>(items: &[Value], i: usize) -> Result<T, JsonError> {
+    T::from_json(&items[i]).map_err(|e| e.at(&format!("[{i}]")))
+}
+
+// 以下为课程中的类型实现转换
+
+impl ToJson for User {
+    fn to_json(&self) -> Value {
+        Value::object([
+            ("active", self.active.to_json()),
+            ("username", self.username.to_json()),
+            ("email", self.email.to_json()),
+            ("sign_in_count", self.sign_in_count.to_json()),
+        ])
+    }
+}
+
+impl FromJson for User {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(User {
+            active: field(value, "active")?,
+            username: field(value, "username")?,
+            email: field(value, "email")?,
+            sign_in_count: field(value, "sign_in_count")?,
+        })
+    }
+}
+
+impl ToJson for Post {
+    fn to_json(&self) -> Value {
+        Value::object([("title", self.title.to_json()), ("author", self.author.to_json()), ("content", self.content.to_json())])
+    }
+}
+
+impl FromJson for Post {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(Post { title: field(value, "title")?, author: field(value, "author")?, content: field(value, "content")? })
+    }
+}
+
+impl ToJson for Weibo {
+    fn to_json(&self) -> Value {
+        Value::object([("username", self.username.to_json()), ("content", self.content.to_json())])
+    }
+}
+
+impl FromJson for Weibo {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(Weibo { username: field(value, "username")?, content: field(value, "content")? })
+    }
+}
+
+// 没有数据的枚举成员直接用名字表示
+impl ToJson for PokerSuit {
+    fn to_json(&self) -> Value {
+        format!("{self:?}").to_json()
+    }
+}
+
+impl FromJson for PokerSuit {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value.as_str() {
+            Some("Clubs") => Ok(PokerSuit::Clubs),
+            Some("Spades") => Ok(PokerSuit::Spades),
+            Some("Diamonds") => Ok(PokerSuit::Diamonds),
+            Some("Hearts") => Ok(PokerSuit::Hearts),
+            Some(other) => Err(JsonError::convert(format!("未知的花色 {other}"))),
+            None => Err(JsonError::expected("字符串", value)),
+        }
+    }
+}
+
+impl ToJson for PokerCard {
+    fn to_json(&self) -> Value {
+        Value::object([("suit", self.suit.to_json()), ("value", self.value.to_json())])
+    }
+}
+
+impl FromJson for PokerCard {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(PokerCard { suit: field(value, "suit")?, value: field(value, "value")? })
+    }
+}
+
+// Rgb(255, 0, 0) -> [255, 0, 0]
+impl ToJson for Rgb {
+    fn to_json(&self) -> Value {
+        [self.0, self.1, self.2].to_json()
+    }
+}
+
+impl FromJson for Rgb {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let items = elements::<3>(value)?;
+        Ok(Rgb(element(items, 0)?, element(items, 1)?, element(items, 2)?))
+    }
+}
+
+/*
+    带数据的枚举用 {"成员名": 数据} 表示，没有数据的成员只写名字:
+        Message::Quit                   "Quit"
+        Message::Move { x: 1, y: 2 }    {"Move": {"x": 1, "y": 2}}
+        Message::Write("hi")            {"Write": "hi"}
+        Message::ChangeColor(0, 160, 255)   {"ChangeColor": [0, 160, 255]}
+ */
+impl ToJson for Message {
+    fn to_json(&self) -> Value {
+        let (name, data) = match self {
+            Message::Quit => return "Quit".to_json(),
+            Message::Move { x, y } => ("Move", Value::object([("x", x.to_json()), ("y", y.to_json())])),
+            Message::Write(text) => ("Write", text.to_json()),
+            Message::ChangeColor(r, g, b) => ("ChangeColor", [*r, *g, *b].to_json()),
+        };
+        Value::object([(name, data)])
+    }
+}
+
+impl FromJson for Message {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        if value.as_str() == Some("Quit") {
+            return Ok(Message::Quit);
+        }
+        let (name, data) = match value {
+            Value::Object(fields) if fields.len() == 1 => (fields[0].0.as_str(), &fields[0].1),
+            _ => return Err(JsonError::convert("应为 \"Quit\" 或只有一个键的对象")),
+        };
+        let variant = |data: &Value| match name {
+            "Move" => Ok(Message::Move { x: field(data, "x")?, y: field(data, "y")? }),
+            "Write" => String::from_json(data).map(Message::Write),
+            "ChangeColor" => {
+                let items = elements::<3>(data)?;
+                Ok(Message::ChangeColor(element(items, 0)?, element(items, 1)?, element(items, 2)?))
+            }
+            other => Err(JsonError::convert(format!("未知的成员 {other}"))),
+        };
+        variant(data).map_err(|e| e.at(&format!(".{name}")))
+    }
+}
+
+pub fn run() {
+    let user = User { active: true, username: "sunface".to_string(), email: "sunface@example.com".to_string(), sign_in_count: 3 };
+    println!("{}", to_string_pretty(&user));
+
+    let post = Post { title: "Rust语言简介".to_string(), author: "Sunface".to_string(), content: "Rust棒极了!\n\"引号\"".to_string() };
+    println!("{}", to_string(&post));
+
+    let messages = vec![Message::Quit, Message::Move { x: 10, y: -20 }, Message::Write("你好".to_string()), Message::ChangeColor(0, 160, 255)];
+    let text = to_string_pretty(&messages);
+    println!("{text}");
+    println!("{:?}", from_str::<Vec<Message>>(&text).unwrap());
+
+    // 转换错误会指出具体的位置
+    let err = from_str::<Vec<PokerCard>>(r#"[{"suit":"Clubs","value":1},{"suit":"Stars","value":2}]"#).unwrap_err();
+    println!("{err}");
+
+    // 语法错误报告行号和列号
+    let text = "{\n  \"name\": \"Sunface\",\n  \"age\": 18,\n}";
+    println!("{text:?} -> {}", parse(text).unwrap_err());
+
+    // 流式解析: 只数一下数组中有多少个对象，不构建整棵树
+    let big = format!("[{}]", vec![to_string(&user); 1000].join(","));
+    let mut parser = Parser::new(big.as_bytes());
+    let mut objects = 0;
+    while let Some(event) = parser.next_event().unwrap() {
+        if event == Event::StartObject {
+            objects += 1;
+        }
+    }
+    println!("数组中有 {objects} 个对象");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 序列化后再解析回来，结果应与原值相同
+    fn round_trip<T: ToJson + FromJson + PartialEq + fmt::Debug>(value: &T) {
+        assert_eq!(&from_str::<T>(&to_string(value)).unwrap(), value);
+        assert_eq!(&from_str::<T>(&to_string_pretty(value)).unwrap(), value);
+    }
+
+    // 返回语法错误的行号和列号
+    fn syntax_error_at(text: &str) -> (usize, usize) {
+        match parse(text) {
+            Err(JsonError::Syntax { line, column, .. }) => (line, column),
+            other => panic!("{text:?} should be a syntax error, got {other:?}"),
+        }
+    }
+
+    fn syntax_message(text: &str) -> String {
+        match parse(text) {
+            Err(JsonError::Syntax { message, .. }) => message,
+            other => panic!("{text:?} should be a syntax error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn structs_round_trip() {
+        round_trip(&User { active: true, username: "sunface".to_string(), email: "sunface@example.com".to_string(), sign_in_count: 3 });
+        round_trip(&Weibo { username: "sunface".to_string(), content: "好像微博没Tweet好用".to_string() });
+
+        let post = Post { title: "Rust语言简介".to_string(), author: "Sunface".to_string(), content: "Rust棒极了!\n\"引号\"".to_string() };
+        assert_eq!(to_string(&post), r#"{"title":"Rust语言简介","author":"Sunface","content":"Rust棒极了!\n\"引号\""}"#);
+        round_trip(&post);
+    }
+
+    #[test]
+    fn enums_and_tuples_round_trip() {
+        let hand = vec![PokerCard { suit: PokerSuit::Clubs, value: 1 }, PokerCard { suit: PokerSuit::Hearts, value: 13 }];
+        assert_eq!(to_string(&hand), r#"[{"suit":"Clubs","value":1},{"suit":"Hearts","value":13}]"#);
+        round_trip(&hand);
+
+        assert_eq!(to_string(&Rgb(255, 0, 0)), "[255,0,0]");
+        assert_eq!(from_str::<Rgb>("[0, 160, 255]").unwrap(), Rgb(0, 160, 255));
+        round_trip(&Rgb(0, 160, 255));
+
+        round_trip(&vec![Message::Quit, Message::Move { x: 10, y: -20 }, Message::Write("你好".to_string()), Message::ChangeColor(0, 160, 255)]);
+    }
+
+    #[test]
+    fn conversion_errors_report_path() {
+        let err = from_str::<Vec<PokerCard>>(r#"[{"suit":"Clubs","value":1},{"suit":"Stars","value":2}]"#).unwrap_err();
+        assert_eq!(err.to_string(), "$[1].suit: 未知的花色 Stars");
+        let err = from_str::<Rgb>("[255, 0, 256]").unwrap_err();
+        assert_eq!(err.to_string(), "$[2]: 256 不是 u8 类型的值");
+        let err = from_str::<Message>(r#"{"Move": {"x": 1}}"#).unwrap_err();
+        assert_eq!(err.to_string(), "$.Move: 缺少字段 y");
+    }
+
+    #[test]
+    fn syntax_errors_report_line_and_column() {
+        for (text, line, column) in [
+            ("{\n  \"name\": \"Sunface\",\n  \"age\": 18,\n}", 4, 1),
+            ("[1, 2,, 3]", 1, 7),
+            ("{\"名字\": tru}", 1, 11),
+            ("[\"abc]", 1, 7),
+            ("[01]", 1, 3),
+            ("{} []", 1, 4),
+        ] {
+            assert_eq!(syntax_error_at(text), (line, column), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn escapes_and_numbers() {
+        let value = parse(r#"{"emoji": "🦀", "tab": "a\tb", "n": [-0.5, 1e3, 0, 12345678901]}"#).unwrap();
+        assert_eq!(value.get("emoji").and_then(Value::as_str), Some("🦀"));
+        assert_eq!(value.to_string(), "{\"emoji\":\"🦀\",\"tab\":\"a\\tb\",\"n\":[-0.5,1000,0,12345678901]}");
+        // 输出的内容可以原样解析回来
+        assert_eq!(parse(&format!("{value:#}")).unwrap(), value);
+        // \u 转义，包括代理对
+        assert_eq!(parse(r#""你好🦀""#).unwrap(), Value::String("你好🦀".to_string()));
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        assert_eq!(syntax_message(r#""\uDC00""#), "低代理项前面缺少高代理项");
+        assert_eq!(syntax_message(r#""\uD83E""#), "高代理项后面应为 \\u 低代理项");
+        assert_eq!(syntax_message(r#""\uD83Ex""#), "高代理项后面应为 \\u 低代理项");
+        assert_eq!(syntax_message(r#""\uD83E\u0041""#), "无效的低代理项");
+    }
+
+    #[test]
+    fn stream_events() {
+        let user = User { active: true, username: "sunface".to_string(), email: "sunface@example.com".to_string(), sign_in_count: 3 };
+        let big = format!("[{}]", vec![to_string(&user); 1000].join(","));
+        let mut parser = Parser::new(big.as_bytes());
+        let mut objects = 0;
+        while let Some(event) = parser.next_event().unwrap() {
+            if event == Event::StartObject {
+                objects += 1;
+            }
+        }
+        assert_eq!(objects, 1000);
+    }
+
+    #[test]
+    fn nesting_deeper_than_max_depth_is_a_syntax_error() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        let err = parse(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert!(matches!(err, JsonError::Syntax { line: 1, column, .. } if column == MAX_DEPTH + 1), "{err}");
+        // 不会因为递归太深而栈溢出
+        let err = parse(&"{\"a\":".repeat(100_000)).unwrap_err();
+        assert!(matches!(err, JsonError::Syntax { .. }), "{err}");
+    }
+
+    #[test]
+    fn integers_at_the_type_bounds() {
+        assert_eq!(from_str::<u8>("255").unwrap(), 255);
+        assert!(from_str::<u8>("256").is_err());
+        assert_eq!(from_str::<i8>("-128").unwrap(), -128);
+        assert!(from_str::<i8>("128").is_err());
+        assert!(from_str::<i8>("-129").is_err());
+        assert_eq!(from_str::<i64>("-9223372036854775808").unwrap(), i64::MIN);
+        // 2^63 和 2^64
+        assert!(from_str::<i64>("9223372036854775808").is_err());
+        assert!(from_str::<u64>("18446744073709551616").is_err());
+        assert_eq!(from_str::<u64>("9007199254740992").unwrap(), 1 << 53);
+        assert!(from_str::<u32>("1.5").is_err());
+    }
+}
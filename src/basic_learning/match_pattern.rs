@@ -1,4 +1,6 @@
 pub mod match_test {
+    use crate::basic_learning::style::dim_dbg;
+
     pub fn base() {
         // 的匹配必须要穷举出所有可能
         // 每一个分支都必须是一个表达式，且所有分支的表达式最终返回值的类型必须相同
@@ -28,7 +30,7 @@ pub mod match_test {
             // 类似于 switch 中的 default
             _ => "West",
         };
-        dbg!(a);
+        dim_dbg!(a);
     }
 
     // 模式绑定
@@ -51,15 +53,15 @@ pub mod match_test {
                 Coin::Nickel => 5,
                 Coin::Dime => 10,
                 Coin::Quarter(state) => {
-                    dbg!(&state);
+                    dim_dbg!(&state);
                     25
                 }
             }
         }
         let a = value_in_cents(Coin::Dime);
-        dbg!(a);
+        dim_dbg!(a);
         let b = value_in_cents(Coin::Quarter(UsState::Alaska));
-        dbg!(b);
+        dim_dbg!(b);
     }
 
     pub fn wildcard() {
@@ -72,7 +74,7 @@ pub mod match_test {
             // _ 将会匹配所有遗漏的值
             _ => "else",
         };
-        dbg!(a);
+        dim_dbg!(a);
 
         let b = match some_u8_value {
             1 => "one".to_string(),
@@ -85,11 +87,13 @@ pub mod match_test {
                 format!("其他数值: {}", e_str).to_string()
             }
         };
-        dbg!(b);
+        dim_dbg!(b);
     }
 }
 
 pub mod if_let {
+    use crate::basic_learning::style::dim_dbg;
+
     pub fn run() {
         // 当你只要匹配一个条件，且忽略其他条件时就用 if let ，否则都用 match。
         let n = 4;
@@ -99,7 +103,7 @@ pub mod if_let {
                 println!("three");
                 1
             } else { 10 };
-            dbg!(result);
+            dim_dbg!(result);
         }
     }
 }
@@ -117,6 +121,8 @@ pub mod matches {
 }
 
 pub mod option {
+    use crate::basic_learning::style::dim_dbg;
+
     pub fn run() {
         /*
            一个变量要么有值：Some(T), 要么为空：None
@@ -137,14 +143,16 @@ pub mod option {
         let b = plus_one(Some(5));
         let c = plus_one(None);
 
-        dbg!(a);
-        dbg!(b);
-        dbg!(c);
+        dim_dbg!(a);
+        dim_dbg!(b);
+        dim_dbg!(c);
     }
 }
 
 // 模式列表
 pub mod all_patterns {
+    use crate::basic_learning::style::dim_dbg;
+
     // 匹配字面值
     pub fn a() {
         let x = 1;
@@ -222,14 +230,15 @@ pub mod all_patterns {
     }
 
     // 解构并分解值 - 枚举
-    pub fn f() {
-        enum Message {
-            Quit,
-            Move { x: i32, y: i32 },
-            Write(String),
-            ChangeColor(i32, i32, i32),
-        }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Message {
+        Quit,
+        Move { x: i32, y: i32 },
+        Write(String),
+        ChangeColor(i32, i32, i32),
+    }
 
+    pub fn f() {
         func(Message::Move { y: 20, x: 10 });
         func(Message::ChangeColor(0, 160, 255));
 
@@ -254,6 +263,7 @@ pub mod all_patterns {
 
     // 解构并分解值 - 嵌套的结构体和枚举
     pub fn g() {
+        // 这里只演示解构，真正的颜色类型和换算见 color 模块
         enum Color {
             Rgb(i32, i32, i32),
             Hsv(i32, i32, i32),
@@ -294,8 +304,8 @@ pub mod all_patterns {
 
         let ((feet, inches), Point { x, y }) = ((3, 10), Point { x: 3, y: -10 });
 
-        dbg!(feet,inches);
-        dbg!(x,y);
+        dim_dbg!(feet,inches);
+        dim_dbg!(x,y);
     }
 
     // 解构并分解值 - 数组
@@ -356,7 +366,7 @@ pub mod all_patterns {
     pub fn l() {
         let _x = 5; // 编译时不会提示未使用的变量
         let y = 10;
-        dbg!(y);
+        dim_dbg!(y);
     }
 
     // 忽略模式中的值 - 用 .. 忽略剩余值
@@ -4,6 +4,8 @@
 
 // 定义方法
 pub mod definition {
+    use crate::basic_learning::style::dim_dbg;
+
     // self、&self 和 &mut self
     /*
         self 表示 Rectangle 的所有权转移到该方法中，这种形式用的较少
@@ -44,6 +46,7 @@ pub mod definition {
             pub fn new(width: u32, height: u32) -> Self {
                 Rectangle { width, height }
             }
+            // 用 units::Pixels 可以让单位成为类型的一部分，而不只是输出时拼上的字符串
             pub fn width(&self) -> String {
                 self.width.to_string() + "px"
             }
@@ -51,8 +54,8 @@ pub mod definition {
 
         let rect = Rectangle::new(30, 50);
 
-        dbg!(rect.width());
-        dbg!(rect.width);
+        dim_dbg!(rect.width());
+        dim_dbg!(rect.width);
 
         // 用这种方式，我们可以把 Rectangle 的字段设置为私有属性，
         // 只需把它的 new 和 width 方法设置为公开可见
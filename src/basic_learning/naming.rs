@@ -0,0 +1,571 @@
+// 命名风格
+/*
+    Rust 的命名约定:
+        - 变量、函数、方法、模块、宏、字段        snake_case
+        - 结构体、枚举、枚举成员、特征、类型别名    PascalCase (UpperCamelCase)
+        - 常量、静态变量                          SCREAMING_SNAKE_CASE
+    其它语言和配置文件里还常见 camelCase、kebab-case 和 Title Case
+    转换的关键是先把标识符正确地拆成单词，再按目标风格拼回去
+ */
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    // milliseconds_in_a_day
+    Snake,
+    // MILLISECONDS_IN_A_DAY
+    ScreamingSnake,
+    // millisecondsInADay
+    Camel,
+    // MillisecondsInADay
+    Pascal,
+    // milliseconds-in-a-day
+    Kebab,
+    // Milliseconds In A Day
+    Title,
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Case::Snake => "snake_case",
+            Case::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+            Case::Camel => "camelCase",
+            Case::Pascal => "PascalCase",
+            Case::Kebab => "kebab-case",
+            Case::Title => "Title Case",
+        };
+        f.write_str(name)
+    }
+}
+
+// 没有大小写之分的字符(数字、中文等)当作小写处理
+fn is_upper(c: char) -> bool {
+    c.is_uppercase()
+}
+
+/*
+    拆分单词的规则:
+        - 下划线、连字符、空格等非字母数字的字符是分隔符
+        - 小写字母或数字后面跟大写字母时断开       fooBar    -> foo Bar
+        - 连续大写字母后面跟小写字母时，最后一个大写字母属于下一个单词
+                                                  HTTPServer -> HTTP Server
+        - 数字跟在字母后面不断开                   utf8Decode -> utf8 Decode
+ */
+pub fn split_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in s.split(|c: char| !c.is_alphanumeric()).filter(|p| !p.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            let boundary = (!is_upper(prev) && is_upper(cur)) || (is_upper(prev) && is_upper(cur) && next_lower);
+            if boundary {
+                words.push(chars[start..i].iter().collect());
+                start = i;
+            }
+        }
+        words.push(chars[start..].iter().collect());
+    }
+    words
+}
+
+// 首字母大写，其余小写。to_uppercase 可能得到多个字符，比如 ß -> SS
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn convert(s: &str, case: Case) -> String {
+    let words = split_words(s);
+    let join = |f: fn(&str) -> String, sep: &str| words.iter().map(|w| f(w)).collect::<Vec<_>>().join(sep);
+    match case {
+        Case::Snake => join(|w| w.to_lowercase(), "_"),
+        Case::ScreamingSnake => join(|w| w.to_uppercase(), "_"),
+        Case::Kebab => join(|w| w.to_lowercase(), "-"),
+        Case::Title => join(capitalize, " "),
+        // 缩写也当作一个普通单词: HTTPServer -> HttpServer，与 Rust 的 API 指南一致
+        Case::Pascal => join(capitalize, ""),
+        Case::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.to_lowercase() } else { capitalize(w) })
+            .collect(),
+    }
+}
+
+pub fn to_snake_case(s: &str) -> String {
+    convert(s, Case::Snake)
+}
+
+pub fn to_screaming_snake_case(s: &str) -> String {
+    convert(s, Case::ScreamingSnake)
+}
+
+pub fn to_camel_case(s: &str) -> String {
+    convert(s, Case::Camel)
+}
+
+pub fn to_pascal_case(s: &str) -> String {
+    convert(s, Case::Pascal)
+}
+
+pub fn to_kebab_case(s: &str) -> String {
+    convert(s, Case::Kebab)
+}
+
+pub fn to_title_case(s: &str) -> String {
+    convert(s, Case::Title)
+}
+
+// 转换后不变，就说明已经是这种风格
+pub fn is_case(s: &str, case: Case) -> bool {
+    convert(s, case) == s
+}
+
+// 源代码中声明的各种名字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Function,
+    Variable,
+    Module,
+    Macro,
+    Field,
+    Struct,
+    Enum,
+    Variant,
+    Trait,
+    TypeAlias,
+    Const,
+    Static,
+}
+
+impl ItemKind {
+    pub fn expected_case(self) -> Case {
+        match self {
+            ItemKind::Function | ItemKind::Variable | ItemKind::Module | ItemKind::Macro | ItemKind::Field => Case::Snake,
+            ItemKind::Struct | ItemKind::Enum | ItemKind::Variant | ItemKind::Trait | ItemKind::TypeAlias => Case::Pascal,
+            ItemKind::Const | ItemKind::Static => Case::ScreamingSnake,
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ItemKind::Function => "函数",
+            ItemKind::Variable => "变量",
+            ItemKind::Module => "模块",
+            ItemKind::Macro => "宏",
+            ItemKind::Field => "字段",
+            ItemKind::Struct => "结构体",
+            ItemKind::Enum => "枚举",
+            ItemKind::Variant => "枚举成员",
+            ItemKind::Trait => "特征",
+            ItemKind::TypeAlias => "类型别名",
+            ItemKind::Const => "常量",
+            ItemKind::Static => "静态变量",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub kind: ItemKind,
+    pub name: String,
+    pub suggestion: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "第 {} 行: {} {} 应使用 {}，建议改为 {}",
+            self.line,
+            self.kind,
+            self.name,
+            self.kind.expected_case(),
+            self.suggestion
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident,
+    Punct(char),
+    // 字符串、字符、数字和生命周期，检查时不关心内容
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    line: usize,
+}
+
+// 只做检查命名需要的词法分析: 跳过注释和字面量，拆出标识符和符号
+fn tokenize(src: &str) -> Vec<Token> {
+    let chars: Vec<char> = src.chars().collect();
+    let ident_end = |from: usize| (from..chars.len()).find(|&j| !is_ident_char(chars[j])).unwrap_or(chars.len());
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        // 每个分支算出这个记号结束的位置
+        let (end, token) = if c.is_whitespace() {
+            (i + 1, None)
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            ((i..chars.len()).find(|&j| chars[j] == '\n').unwrap_or(chars.len()), None)
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            // 块注释可以嵌套
+            let (mut depth, mut j) = (0, i);
+            while j < chars.len() {
+                if chars[j] == '/' && chars.get(j + 1) == Some(&'*') {
+                    depth += 1;
+                    j += 2;
+                } else if chars[j] == '*' && chars.get(j + 1) == Some(&'/') {
+                    depth -= 1;
+                    j += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            (j, None)
+        } else if let Some(end) = (c == 'r').then(|| raw_string_end(&chars, i + 1)).flatten() {
+            (end, Some((TokenKind::Other, String::new())))
+        } else if c == '"' {
+            let mut j = i + 1;
+            while j < chars.len() && chars[j] != '"' {
+                j += if chars[j] == '\\' { 2 } else { 1 };
+            }
+            (j + 1, Some((TokenKind::Other, String::new())))
+        } else if c == '\'' {
+            // 'a' 和 '\n' 是字符，'a 是生命周期
+            let end = if chars.get(i + 1) == Some(&'\\') {
+                (i + 2..chars.len()).find(|&j| chars[j] == '\'').map_or(chars.len(), |j| j + 1)
+            } else if chars.get(i + 2) == Some(&'\'') {
+                i + 3
+            } else {
+                ident_end(i + 1)
+            };
+            (end, Some((TokenKind::Other, String::new())))
+        } else if c == 'r' && chars.get(i + 1) == Some(&'#') && chars.get(i + 2).is_some_and(|&c| is_ident_char(c)) {
+            // 原始标识符 r#type
+            let end = ident_end(i + 2);
+            (end, Some((TokenKind::Ident, chars[i + 2..end].iter().collect())))
+        } else if c.is_alphabetic() || c == '_' {
+            let end = ident_end(i);
+            (end, Some((TokenKind::Ident, chars[i..end].iter().collect())))
+        } else if c.is_ascii_digit() {
+            (ident_end(i), Some((TokenKind::Other, String::new())))
+        } else {
+            (i + 1, Some((TokenKind::Punct(c), c.to_string())))
+        };
+        let end = end.min(chars.len());
+        if let Some((kind, text)) = token {
+            tokens.push(Token { kind, text, line });
+        }
+        line += chars[i..end].iter().filter(|&&c| c == '\n').count();
+        i = end;
+    }
+    tokens
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// chars[start] 是 r 后面的 # 或 "，返回原始字符串结束后的位置，不是原始字符串时返回 None
+fn raw_string_end(chars: &[char], start: usize) -> Option<usize> {
+    let hashes = chars.get(start..)?.iter().take_while(|&&c| c == '#').count();
+    if chars.get(start + hashes) != Some(&'"') {
+        return None;
+    }
+    let mut j = start + hashes + 1;
+    while j < chars.len() {
+        if chars[j] == '"' && chars[j + 1..].iter().take(hashes).filter(|&&c| c == '#').count() == hashes {
+            return Some(j + 1 + hashes);
+        }
+        j += 1;
+    }
+    Some(chars.len())
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "union", "unsafe", "use", "where", "while",
+];
+
+fn is_name(token: &Token) -> bool {
+    token.kind == TokenKind::Ident && !KEYWORDS.contains(&token.text.as_str())
+}
+
+/*
+    { 之后的成员: 枚举的成员或结构体的字段
+    只看大括号第一层里紧跟在 { 或 , 后面的标识符，跳过 pub、pub(crate) 和 #[属性]
+ */
+fn members(tokens: &[Token], open: usize) -> Vec<usize> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut expect = false;
+    // pub(crate) 和 #[...] 结束后仍然等待成员名
+    let mut resume = false;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token.kind {
+            TokenKind::Punct('{' | '(' | '[') => {
+                if depth == 1 {
+                    resume = expect && matches!(tokens[i - 1].text.as_str(), "pub" | "#");
+                }
+                depth += 1;
+                expect = depth == 1;
+            }
+            TokenKind::Punct('}' | ')' | ']') => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                expect = depth == 1 && resume;
+            }
+            _ if depth != 1 => {}
+            TokenKind::Punct(',') => expect = true,
+            TokenKind::Punct('#') => {}
+            TokenKind::Ident if token.text == "pub" => {}
+            TokenKind::Ident if expect => {
+                result.push(i);
+                expect = false;
+            }
+            _ => expect = false,
+        }
+    }
+    result
+}
+
+// 从 from 开始找到结构体或枚举主体的 {，遇到 ; 说明没有大括号主体(单元结构体、元组结构体)
+fn find_body(tokens: &[Token], from: usize) -> Option<usize> {
+    (from..tokens.len())
+        .find(|&i| matches!(tokens[i].kind, TokenKind::Punct('{' | ';')))
+        .filter(|&i| tokens[i].kind == TokenKind::Punct('{'))
+}
+
+// 检查源代码中声明的名字是否符合 Rust 的命名约定
+pub fn check_source(src: &str) -> Vec<Violation> {
+    let tokens = tokenize(src);
+    let mut found: Vec<(ItemKind, &Token)> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Ident {
+            continue;
+        }
+        // 关键字后面第一个不是 mut 或 ! 的标识符就是名字
+        let name_after = |i: usize| tokens[i + 1..].iter().position(|t| t.text != "mut" && t.text != "!").map(|p| i + 1 + p);
+        let kind = match token.text.as_str() {
+            "fn" => ItemKind::Function,
+            "let" => ItemKind::Variable,
+            "mod" => ItemKind::Module,
+            "trait" => ItemKind::Trait,
+            "type" => ItemKind::TypeAlias,
+            "const" => ItemKind::Const,
+            "static" => ItemKind::Static,
+            "macro_rules" => ItemKind::Macro,
+            "struct" | "union" => ItemKind::Struct,
+            "enum" => ItemKind::Enum,
+            _ => continue,
+        };
+        let Some(n) = name_after(i).filter(|&n| is_name(&tokens[n])) else {
+            continue;
+        };
+        // let Some(x) = ... 和 let Point { x, y } = ... 是模式，不是变量名
+        let next = |k: usize| tokens.get(n + k).map(|t| &t.kind);
+        if kind == ItemKind::Variable
+            && (matches!(next(1), Some(TokenKind::Punct('(' | '{')))
+                || (next(1) == Some(&TokenKind::Punct(':')) && next(2) == Some(&TokenKind::Punct(':'))))
+        {
+            continue;
+        }
+        found.push((kind, &tokens[n]));
+        let member_kind = match kind {
+            ItemKind::Struct => ItemKind::Field,
+            ItemKind::Enum => ItemKind::Variant,
+            _ => continue,
+        };
+        if let Some(open) = find_body(&tokens, i + 1) {
+            let is_punct = |k: usize, c: char| tokens.get(k).is_some_and(|t| t.kind == TokenKind::Punct(c));
+            // 字段名后面一定是冒号，这样可以排除 HashMap<String, Value> 中逗号后面的类型名
+            let members = members(&tokens, open)
+                .into_iter()
+                .filter(|&m| member_kind == ItemKind::Variant || (is_punct(m + 1, ':') && !is_punct(m + 2, ':')));
+            found.extend(members.map(|m| (member_kind, &tokens[m])));
+        }
+    }
+
+    found
+        .into_iter()
+        .filter_map(|(kind, token)| {
+            // _unused、type_ 这样首尾带下划线的名字去掉下划线再检查
+            let name = token.text.trim_matches('_');
+            let case = kind.expected_case();
+            if name.is_empty() || is_case(name, case) {
+                return None;
+            }
+            let prefix = &token.text[..token.text.find(name).unwrap()];
+            let suffix = &token.text[prefix.len() + name.len()..];
+            let suggestion = format!("{prefix}{}{suffix}", convert(name, case));
+            Some(Violation { line: token.line, kind, name: token.text.clone(), suggestion })
+        })
+        .collect()
+}
+
+pub fn check_file(path: &str) -> std::io::Result<Vec<Violation>> {
+    Ok(check_source(&std::fs::read_to_string(path)?))
+}
+
+// cargo run -- naming <源文件>...
+pub fn cli(args: &[String]) {
+    if args.is_empty() {
+        eprintln!("用法: naming <源文件>...");
+        std::process::exit(1);
+    }
+    let mut total = 0;
+    for path in args {
+        match check_file(path) {
+            Ok(violations) => {
+                for v in &violations {
+                    println!("{path}: {v}");
+                }
+                total += violations.len();
+            }
+            Err(e) => eprintln!("{path}: 读取失败: {e}"),
+        }
+    }
+    println!("共发现 {total} 处不符合命名约定");
+}
+
+pub fn run() {
+    let name = "MILLISECONDS_IN_A_DAY";
+    for case in [Case::Snake, Case::ScreamingSnake, Case::Camel, Case::Pascal, Case::Kebab, Case::Title] {
+        println!("{:<22}{}", case.to_string(), convert(name, case));
+    }
+
+    // 缩写、数字和非 ASCII 字符
+    for s in ["parseJSONResponse", "MD5Hash", "ÜberCoolÉcole", "用户Name"] {
+        println!("{s} -> {}", to_snake_case(s));
+    }
+
+    for v in check_source(SAMPLE_SOURCE) {
+        println!("{v}");
+    }
+}
+
+// 故意包含各种不符合命名规范的写法
+const SAMPLE_SOURCE: &str = r#"
+    // fn ignoredInComment() {}
+    const maxPoints: u32 = 100_000;
+    static GLOBAL_COUNT: u32 = 0;
+    struct poker_card {
+        pub Suit: char,
+        cache: std::collections::HashMap<String, Vec<u8>>,
+        #[allow(dead_code)]
+        pub(crate) valueOf: u8,
+    }
+    enum IpAddrKind { V4, ipv6(String), HTTP_PROXY { port: u16 } }
+    mod Shapes {}
+    fn ParseHTTPResponse<'a>(_rawInput: &'a str) -> char {
+        let mut totalCount = "let badName = 1";
+        let type_ = totalCount;
+        let r#fooBar = 'x';
+        let Some(Inner) = None::<u8> else { return 'y' };
+        'x'
+    }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_between_cases() {
+        let name = "MILLISECONDS_IN_A_DAY";
+        assert_eq!(to_snake_case(name), "milliseconds_in_a_day");
+        assert_eq!(to_camel_case(name), "millisecondsInADay");
+        assert_eq!(to_pascal_case(name), "MillisecondsInADay");
+        assert_eq!(to_kebab_case(name), "milliseconds-in-a-day");
+        assert_eq!(to_title_case(name), "Milliseconds In A Day");
+        assert_eq!(to_screaming_snake_case("millisecondsInADay"), name);
+        assert_eq!(to_title_case("  hello__world-rust "), "Hello World Rust");
+    }
+
+    #[test]
+    fn acronyms() {
+        assert_eq!(split_words("HTTPServerError"), ["HTTP", "Server", "Error"]);
+        assert_eq!(to_snake_case("parseJSONResponse"), "parse_json_response");
+        assert_eq!(to_pascal_case("UUID"), "Uuid");
+        assert_eq!(to_pascal_case("io_error"), "IoError");
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(to_snake_case("utf8Decode"), "utf8_decode");
+        assert_eq!(to_camel_case("base64-url-safe"), "base64UrlSafe");
+        assert_eq!(to_snake_case("Vec2D"), "vec2_d");
+        assert_eq!(to_kebab_case("MD5Hash"), "md5-hash");
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert_eq!(to_snake_case("ÜberCoolÉcole"), "über_cool_école");
+        assert_eq!(to_pascal_case("straße_name"), "StraßeName");
+        // ß 的大写是两个字符 SS
+        assert_eq!(to_screaming_snake_case("straße"), "STRASSE");
+        assert_eq!(to_snake_case("用户Name"), "用户_name");
+    }
+
+    #[test]
+    fn detect_case() {
+        assert!(is_case("guess_number", Case::Snake));
+        assert!(!is_case("guessNumber", Case::Snake));
+        assert!(is_case("PokerCard", Case::Pascal));
+        assert!(is_case("MAX_POINTS", Case::ScreamingSnake));
+        assert!(!is_case("Max_Points", Case::ScreamingSnake));
+    }
+
+    #[test]
+    fn check_source_finds_violations() {
+        let violations = check_source(SAMPLE_SOURCE);
+        let found: Vec<(&str, ItemKind)> = violations.iter().map(|v| (v.name.as_str(), v.kind)).collect();
+        assert_eq!(
+            found,
+            [
+                ("maxPoints", ItemKind::Const),
+                ("poker_card", ItemKind::Struct),
+                ("Suit", ItemKind::Field),
+                ("valueOf", ItemKind::Field),
+                ("ipv6", ItemKind::Variant),
+                ("HTTP_PROXY", ItemKind::Variant),
+                ("Shapes", ItemKind::Module),
+                ("ParseHTTPResponse", ItemKind::Function),
+                ("totalCount", ItemKind::Variable),
+                ("fooBar", ItemKind::Variable),
+            ]
+        );
+        assert_eq!(violations[7].suggestion, "parse_http_response");
+        assert_eq!(violations[7].line, 13);
+    }
+
+    #[test]
+    fn check_source_accepts_clean_code() {
+        let src = "struct PokerCard { suit: char }\nconst MAX: u8 = 1;\nfn deal_cards() { let hand_size = 5; }";
+        assert!(check_source(src).is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+// 密码哈希
+/*
+    数据库里不能保存明文密码，而是保存 哈希(盐 + 密码):
+        - 哈希是单向的，从结果推不回密码
+        - 盐是每个用户随机生成的，相同的密码也会得到不同的结果，预先算好的彩虹表就没用了
+        - 迭代很多次(PBKDF2)，让每次猜测都变慢，暴力破解的代价随之成倍增加
+    这里只用标准库实现 SHA-256 (FIPS 180-4)、HMAC (RFC 2104) 和 PBKDF2 (RFC 8018)
+    实际项目请使用经过审计的库，例如 argon2、pbkdf2
+ */
+use std::fmt;
+use std::str::FromStr;
+
+use crate::basic_learning::style::dim_dbg;
+
+// 前 64 个质数立方根小数部分的前 32 位
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01,
+    0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc,
+    0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147,
+    0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08,
+    0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208,
+    0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// 前 8 个质数平方根小数部分的前 32 位
+const H0: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+// 可以分多次输入数据的 SHA-256
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    // 还不够 64 字节的数据先放在这里
+    block: [u8; 64],
+    block_len: usize,
+    total_len: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Sha256 { state: H0, block: [0; 64], block_len: 0, total_len: 0 }
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 压缩函数，每次处理 64 字节
+    fn compress(state: &mut [u32; 8], block: &[u8; 64]) {
+        let mut w = [0u32; 64];
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+        while !data.is_empty() {
+            let n = (64 - self.block_len).min(data.len());
+            self.block[self.block_len..self.block_len + n].copy_from_slice(&data[..n]);
+            self.block_len += n;
+            data = &data[n..];
+            if self.block_len == 64 {
+                Self::compress(&mut self.state, &self.block);
+                self.block_len = 0;
+            }
+        }
+    }
+
+    /*
+        填充: 先补一个 0x80，再补 0 直到长度模 64 余 56，最后 8 字节是原始数据的位数(大端)
+     */
+    pub fn finalize(mut self) -> [u8; 32] {
+        let bit_len = self.total_len.wrapping_mul(8);
+        self.update(&[0x80]);
+        while self.block_len != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bit_len.to_be_bytes());
+        let mut out = [0u8; 32];
+        for (chunk, s) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&s.to_be_bytes());
+        }
+        out
+    }
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/*
+    HMAC(K, m) = H((K ^ opad) || H((K ^ ipad) || m))
+    K ^ ipad 和 K ^ opad 各占一个块，提前算好这两个块之后的状态，每次计算时克隆一份即可
+    PBKDF2 要计算几十万次 HMAC，这样能省掉一半的压缩
+ */
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> Self {
+        // 超过一个块的密钥先哈希
+        let mut block = [0u8; 64];
+        if key.len() > 64 {
+            block[..32].copy_from_slice(&sha256(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let (mut inner, mut outer) = (Sha256::new(), Sha256::new());
+        inner.update(&block.map(|b| b ^ 0x36));
+        outer.update(&block.map(|b| b ^ 0x5c));
+        HmacSha256 { inner, outer }
+    }
+
+    pub fn mac(&self, message: &[u8]) -> [u8; 32] {
+        let mut inner = self.inner.clone();
+        inner.update(message);
+        let mut outer = self.outer.clone();
+        outer.update(&inner.finalize());
+        outer.finalize()
+    }
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    HmacSha256::new(key).mac(message)
+}
+
+/*
+    PBKDF2-HMAC-SHA256，输出按 32 字节分块计算:
+        U1 = HMAC(密码, 盐 || 块序号)
+        Un = HMAC(密码, Un-1)
+        块 = U1 ^ U2 ^ ... ^ U迭代次数
+ */
+pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) {
+    assert!(iterations > 0, "iterations must be at least 1");
+    let prf = HmacSha256::new(password);
+    for (i, chunk) in out.chunks_mut(32).enumerate() {
+        let mut message = salt.to_vec();
+        message.extend_from_slice(&(i as u32 + 1).to_be_bytes());
+        let mut u = prf.mac(&message);
+        let mut block = u;
+        for _ in 1..iterations {
+            u = prf.mac(&u);
+            for (b, x) in block.iter_mut().zip(u) {
+                *b ^= x;
+            }
+        }
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+}
+
+/*
+    普通的 == 遇到第一个不同的字节就返回，比较耗时会泄露"前面猜对了几个字节"
+    这里无论在哪里不同，都会把所有字节比较完
+ */
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    // 哈希的长度是公开的，长度不同可以直接返回
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    // 防止编译器把循环优化成提前返回
+    std::hint::black_box(diff) == 0
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+// 默认迭代次数，debug 模式下一次校验大约需要零点几秒
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+// 保存到数据库里的内容: 算法参数、盐和哈希值，不包含密码本身
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordHash {
+    pub iterations: u32,
+    pub salt: Vec<u8>,
+    pub hash: Vec<u8>,
+}
+
+impl PasswordHash {
+    pub fn new(password: &str) -> Self {
+        Self::with_iterations(password, DEFAULT_ITERATIONS)
+    }
+
+    // 每次生成新的随机盐
+    pub fn with_iterations(password: &str, iterations: u32) -> Self {
+        Self::with_salt(password, &rand::random::<[u8; SALT_LEN]>(), iterations)
+    }
+
+    pub fn with_salt(password: &str, salt: &[u8], iterations: u32) -> Self {
+        let mut hash = vec![0; HASH_LEN];
+        pbkdf2_hmac_sha256(password.as_bytes(), salt, iterations, &mut hash);
+        PasswordHash { iterations, salt: salt.to_vec(), hash }
+    }
+
+    // 用同样的盐和迭代次数重新计算，再做常数时间比较
+    pub fn verify(&self, password: &str) -> bool {
+        let mut hash = vec![0; self.hash.len()];
+        pbkdf2_hmac_sha256(password.as_bytes(), &self.salt, self.iterations, &mut hash);
+        constant_time_eq(&hash, &self.hash)
+    }
+}
+
+// pbkdf2-sha256$迭代次数$盐$哈希值，盐和哈希值用十六进制表示
+impl fmt::Display for PasswordHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pbkdf2-sha256${}${}${}", self.iterations, to_hex(&self.salt), to_hex(&self.hash))
+    }
+}
+
+impl FromStr for PasswordHash {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = s.split('$').collect();
+        let ["pbkdf2-sha256", iterations, salt, hash] = parts[..] else {
+            return Err(format!("无法识别的密码哈希格式: {s}"));
+        };
+        let iterations = iterations.parse().ok().filter(|&n| n > 0).ok_or("迭代次数无效")?;
+        let salt = from_hex(salt).ok_or("盐不是合法的十六进制")?;
+        let hash = from_hex(hash).filter(|h| !h.is_empty()).ok_or("哈希值不是合法的十六进制")?;
+        Ok(PasswordHash { iterations, salt, hash })
+    }
+}
+
+pub fn run() {
+    dim_dbg!(to_hex(&sha256(b"abc")));
+    dim_dbg!(to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")));
+
+    // 相同的密码，盐不同，保存的内容也不同
+    let a = PasswordHash::with_iterations("correct horse", 1000);
+    let b = PasswordHash::with_iterations("correct horse", 1000);
+    println!("{a}\n{b}");
+    dim_dbg!(a.verify("correct horse"), a.verify("correct horse "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha(data: &[u8]) -> String {
+        to_hex(&sha256(data))
+    }
+
+    fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> String {
+        let mut out = vec![0; len];
+        pbkdf2_hmac_sha256(password, salt, iterations, &mut out);
+        to_hex(&out)
+    }
+
+    // FIPS 180-2 附录中的测试向量
+    #[test]
+    fn sha256_fips_180_2() {
+        assert_eq!(sha(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            sha(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    // 一百万个 a，分成不规则的小段输入，顺便检查 update 的分块逻辑
+    #[test]
+    fn sha256_million_a_in_chunks() {
+        let mut hasher = Sha256::new();
+        let million = vec![b'a'; 1_000_000];
+        for chunk in million.chunks(997) {
+            hasher.update(chunk);
+        }
+        assert_eq!(to_hex(&hasher.finalize()), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+
+    // RFC 4231 测试用例 1、2、6
+    #[test]
+    fn hmac_sha256_rfc_4231() {
+        assert_eq!(to_hex(&hmac_sha256(&[0x0b; 20], b"Hi There")), "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7");
+        assert_eq!(
+            to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            to_hex(&hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    // RFC 7914 第 11 节中的 PBKDF2-HMAC-SHA256 测试向量
+    #[test]
+    fn pbkdf2_rfc_7914() {
+        assert_eq!(
+            pbkdf2(b"passwd", b"salt", 1, 64),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        );
+        assert_eq!(
+            pbkdf2(b"Password", b"NaCl", 80000, 64),
+            "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"
+        );
+        assert_eq!(pbkdf2(b"password", b"salt", 4096, 32), "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a");
+    }
+
+    #[test]
+    fn constant_time_comparison() {
+        assert!(constant_time_eq(b"rust", b"rust"));
+        assert!(!constant_time_eq(b"rust", b"rusk"));
+        assert!(!constant_time_eq(b"rust", b"rus"));
+    }
+
+    #[test]
+    fn password_hash_round_trip() {
+        let a = PasswordHash::with_iterations("correct horse", 1000);
+        let b = PasswordHash::with_iterations("correct horse", 1000);
+        assert_ne!(a.hash, b.hash);
+        assert!(a.verify("correct horse") && b.verify("correct horse"));
+        assert!(!a.verify("correct horse "));
+        assert_eq!(a.to_string().parse::<PasswordHash>().unwrap(), a);
+        assert!("md5$1$00$00".parse::<PasswordHash>().is_err());
+    }
+}
@@ -0,0 +1,352 @@
+// 扑克牌型
+/*
+    在 cards 模块的基础上判断德州扑克的牌型:
+        - Category      高牌 < 一对 < 两对 < 三条 < 顺子 < 同花 < 葫芦 < 四条 < 同花顺 < 皇家同花顺
+        - HandRank      牌型 + 用来比较大小的点数 (先比成组的点数，再比单张)，derive(Ord) 直接比较两手牌
+        - evaluate      5 张牌的牌型
+        - best_five     5~7 张牌中最大的 5 张，7 张牌有 C(7,5) = 21 种组合
+    A 在 A-2-3-4-5 (wheel) 中当作 1，这是最小的顺子
+    花色不分大小，点数完全相同的两手牌算平局
+
+    KNOWN_FREQUENCIES 是 2,598,960 种 5 张牌组合中各牌型的已知数量
+    测试里穷举全部组合与它对照，debug 模式下大约要跑 5 秒
+ */
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::basic_learning::cards::{format_hand, parse_hand, Card, CardError, Rank};
+use crate::basic_learning::compound_type::enum_test::PokerCard;
+use crate::basic_learning::text_layout::{Align, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
+}
+
+impl Category {
+    pub const ALL: [Category; 10] = [
+        Category::HighCard,
+        Category::OnePair,
+        Category::TwoPair,
+        Category::ThreeOfAKind,
+        Category::Straight,
+        Category::Flush,
+        Category::FullHouse,
+        Category::FourOfAKind,
+        Category::StraightFlush,
+        Category::RoyalFlush,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::HighCard => "高牌",
+            Category::OnePair => "一对",
+            Category::TwoPair => "两对",
+            Category::ThreeOfAKind => "三条",
+            Category::Straight => "顺子",
+            Category::Flush => "同花",
+            Category::FullHouse => "葫芦",
+            Category::FourOfAKind => "四条",
+            Category::StraightFlush => "同花顺",
+            Category::RoyalFlush => "皇家同花顺",
+        }
+    }
+}
+
+// 字段的顺序决定了 derive(Ord) 的比较顺序: 先比牌型，再依次比 ranks
+// ranks 按重要程度排列，不足 5 个的后面补 0，比如:
+//     葫芦 K K K 7 7     [13, 7, 0, 0, 0]
+//     两对 9 9 4 4 A     [9, 4, 14, 0, 0]
+//     顺子 5 4 3 2 A     [5, 0, 0, 0, 0]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank {
+    pub category: Category,
+    pub ranks: [u8; 5],
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranks: Vec<String> = self
+            .ranks
+            .iter()
+            .filter(|&&r| r != 0)
+            .map(|&r| Rank::new(r).unwrap().to_string())
+            .collect();
+        write!(f, "{} ({})", self.category.name(), ranks.join(" "))
+    }
+}
+
+// 顺子的最大点数，A-2-3-4-5 的最大点数是 5
+// ranks 必须是从大到小排好的 5 个不同的点数
+fn straight_high(ranks: &[u8; 5]) -> Option<u8> {
+    if ranks[0] - ranks[4] == 4 {
+        Some(ranks[0])
+    } else if *ranks == [14, 5, 4, 3, 2] {
+        Some(5)
+    } else {
+        None
+    }
+}
+
+pub fn evaluate(hand: &[Card; 5]) -> HandRank {
+    let flush = hand.iter().all(|c| c.suit == hand[0].suit);
+
+    // 按 (张数, 点数) 从大到小分组，比如 7 K 7 K 7 分成 [(3, 7), (2, 13)]
+    let mut counts = [0u8; 15];
+    for card in hand {
+        counts[card.rank.value() as usize] += 1;
+    }
+    let mut groups: Vec<(u8, u8)> = (2..=14u8)
+        .rev()
+        .filter(|&r| counts[r as usize] > 0)
+        .map(|r| (counts[r as usize], r))
+        .collect();
+    groups.sort_by(|a, b| b.cmp(a));
+
+    let mut ranks = [0u8; 5];
+    for (i, &(_, r)) in groups.iter().enumerate() {
+        ranks[i] = r;
+    }
+
+    let category = match groups.len() {
+        // 5 个不同的点数: 高牌、顺子、同花或同花顺
+        5 => match (straight_high(&ranks), flush) {
+            (Some(14), true) => Category::RoyalFlush,
+            (Some(high), true) => {
+                ranks = [high, 0, 0, 0, 0];
+                Category::StraightFlush
+            }
+            (Some(high), false) => {
+                ranks = [high, 0, 0, 0, 0];
+                Category::Straight
+            }
+            (None, true) => Category::Flush,
+            (None, false) => Category::HighCard,
+        },
+        4 => Category::OnePair,
+        3 if groups[0].0 == 3 => Category::ThreeOfAKind,
+        3 => Category::TwoPair,
+        _ if groups[0].0 == 4 => Category::FourOfAKind,
+        _ => Category::FullHouse,
+    };
+    if category == Category::RoyalFlush {
+        ranks = [14, 0, 0, 0, 0];
+    }
+    HandRank { category, ranks }
+}
+
+// 从 n 个元素中选 k 个的所有组合，按字典序输出下标
+fn combinations(n: usize, k: usize) -> impl Iterator<Item = Vec<usize>> {
+    let mut next = if k <= n { Some((0..k).collect::<Vec<_>>()) } else { None };
+    std::iter::from_fn(move || {
+        let current = next.take()?;
+        // 找到最右边还能增加的位置，把它加一，后面的依次排列
+        let mut idx = current.clone();
+        if let Some(i) = (0..k).rev().find(|&i| idx[i] < n - k + i) {
+            idx[i] += 1;
+            for j in i + 1..k {
+                idx[j] = idx[j - 1] + 1;
+            }
+            next = Some(idx);
+        }
+        Some(current)
+    })
+}
+
+// 5~7 张牌中最大的 5 张，返回的 5 张按成组的点数从大到小排列
+pub fn best_five(cards: &[Card]) -> Option<(HandRank, [Card; 5])> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    let (rank, mut best) = combinations(cards.len(), 5)
+        .map(|idx| {
+            let hand = [cards[idx[0]], cards[idx[1]], cards[idx[2]], cards[idx[3]], cards[idx[4]]];
+            (evaluate(&hand), hand)
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))?;
+    // 成组的牌排在前面，A-2-3-4-5 中的 A 排在最后
+    let order = |c: &Card| {
+        let pos = rank.ranks.iter().position(|&r| r == c.rank.value()).unwrap_or(5);
+        let wheel_ace = rank.ranks[0] == 5 && c.rank == Rank::ACE;
+        (wheel_ace, pos, std::cmp::Reverse(c.rank))
+    };
+    best.sort_by_key(order);
+    Some((rank, best))
+}
+
+// 比较两手牌 (各 5~7 张) 的大小
+pub fn compare(a: &[Card], b: &[Card]) -> Option<Ordering> {
+    Some(best_five(a)?.0.cmp(&best_five(b)?.0))
+}
+
+// 用 enum_test::PokerCard 表示的 5~7 张牌
+pub fn evaluate_poker_cards(cards: &[PokerCard]) -> Result<Option<HandRank>, CardError> {
+    let cards: Vec<Card> = cards.iter().cloned().map(Card::try_from).collect::<Result<_, _>>()?;
+    Ok(best_five(&cards).map(|(rank, _)| rank))
+}
+
+// 5 张牌的各牌型数量，按 Category 的顺序，合计 C(52, 5) = 2,598,960
+pub const KNOWN_FREQUENCIES: [u32; 10] = [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 36, 4];
+
+fn hand(s: &str) -> [Card; 5] {
+    parse_hand(s).unwrap().try_into().unwrap()
+}
+
+pub fn run() {
+    // 各种牌型
+    for s in ["AS KS QS JS 10S", "5D 4D 3D 2D AD", "KC KD KH 7S 7C", "AC 2D 3H 4S 5C", "9C 9D 4H 4S AC", "KC AD 2H 3S 4C"] {
+        println!("{:<18} {}", s, evaluate(&hand(s)));
+    }
+
+    // 7 张牌中最大的 5 张: 两张底牌 + 五张公共牌
+    let board = "KH 10H 3C 7H 2H";
+    let player1 = parse_hand(&format!("AH 4D {}", board)).unwrap();
+    let player2 = parse_hand(&format!("KC KD {}", board)).unwrap();
+    let (rank1, best1) = best_five(&player1).unwrap();
+    let (rank2, best2) = best_five(&player2).unwrap();
+    println!("玩家 1: {} => {}", format_hand(&best1, false), rank1);
+    println!("玩家 2: {} => {}", format_hand(&best2, false), rank2);
+    println!("玩家 1 比玩家 2: {:?}", compare(&player1, &player2).unwrap());
+
+    // 各牌型的组合数和概率
+    let mut table = Table::new(&["牌型", "组合数", "概率"]).align(1, Align::Right).align(2, Align::Right);
+    for (category, &count) in Category::ALL.iter().zip(KNOWN_FREQUENCIES.iter()).rev() {
+        let probability = format!("{:.6}%", count as f64 / 2_598_960.0 * 100.0);
+        table.add_row(&[category.name().to_string(), count.to_string(), probability]);
+    }
+    println!("{table}");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::basic_learning::cards::Deck;
+
+    // 穷举所有 5 张牌的组合，统计每种牌型的数量，顺便统计不同的 HandRank 有多少种
+    fn five_card_frequencies() -> ([u32; 10], usize) {
+        let deck = Deck::new();
+        let cards = deck.cards();
+        let mut counts = [0u32; 10];
+        let mut distinct = HashSet::new();
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let rank = evaluate(&[cards[a], cards[b], cards[c], cards[d], cards[e]]);
+                            counts[rank.category as usize] += 1;
+                            distinct.insert(rank);
+                        }
+                    }
+                }
+            }
+        }
+        (counts, distinct.len())
+    }
+
+    #[test]
+    fn categories() {
+        let cases = [
+            ("AS KS QS JS 10S", Category::RoyalFlush),
+            ("9H KH QH JH 10H", Category::StraightFlush),
+            ("5D 4D 3D 2D AD", Category::StraightFlush),
+            ("7C 7D 7H 7S 2C", Category::FourOfAKind),
+            ("KC KD KH 7S 7C", Category::FullHouse),
+            ("2H 9H KH 4H 6H", Category::Flush),
+            ("10C 9D 8H 7S 6C", Category::Straight),
+            ("AC 2D 3H 4S 5C", Category::Straight),
+            ("QC QD QH 3S 2C", Category::ThreeOfAKind),
+            ("9C 9D 4H 4S AC", Category::TwoPair),
+            ("JC JD 8H 4S 2C", Category::OnePair),
+            ("AC QD 9H 4S 2C", Category::HighCard),
+            // K-A-2-3-4 不是顺子
+            ("KC AD 2H 3S 4C", Category::HighCard),
+        ];
+        for (s, category) in cases {
+            assert_eq!(evaluate(&hand(s)).category, category, "{}", s);
+        }
+    }
+
+    // 比较大小，包括踢脚牌和平局
+    #[test]
+    fn ordering() {
+        let cmp = |a: &str, b: &str| evaluate(&hand(a)).cmp(&evaluate(&hand(b)));
+        // 同样是一对 J，比较剩下的单张
+        assert_eq!(cmp("JC JD AH 4S 2C", "JH JS KH QS 10C"), Ordering::Greater);
+        assert_eq!(cmp("JC JD AH 4S 3C", "JH JS AD 4C 2C"), Ordering::Greater);
+        // 两对先比大的一对，再比小的一对，最后比单张
+        assert_eq!(cmp("9C 9D 4H 4S AC", "9H 9S 5H 5S 2C"), Ordering::Less);
+        assert_eq!(cmp("9C 9D 4H 4S AC", "9H 9S 4C 4D KC"), Ordering::Greater);
+        // 葫芦先比三条
+        assert_eq!(cmp("2C 2D 2H AS AC", "KC KD KH 3S 3C"), Ordering::Less);
+        // 最小的顺子比 6 开头的顺子小，同花比顺子大
+        assert_eq!(cmp("AC 2D 3H 4S 5C", "2C 3D 4H 5S 6C"), Ordering::Less);
+        assert_eq!(cmp("2H 9H KH 4H 6H", "AC KD QH JS 10C"), Ordering::Greater);
+        // 花色不分大小
+        assert_eq!(cmp("AC KD QH JS 9C", "AD KC QS JH 9D"), Ordering::Equal);
+        assert_eq!(cmp("5D 4D 3D 2D AD", "5S 4S 3S 2S AS"), Ordering::Equal);
+    }
+
+    #[test]
+    fn best_five_of_seven() {
+        let board = "KH 10H 3C 7H 2H";
+        let player1 = parse_hand(&format!("AH 4D {}", board)).unwrap();
+        let player2 = parse_hand(&format!("KC KD {}", board)).unwrap();
+        let (rank1, _) = best_five(&player1).unwrap();
+        let (rank2, _) = best_five(&player2).unwrap();
+        assert_eq!(rank1.category, Category::Flush);
+        assert_eq!(rank1.ranks, [14, 13, 10, 7, 2]);
+        assert_eq!(rank2.category, Category::ThreeOfAKind);
+        assert_eq!(compare(&player1, &player2), Some(Ordering::Greater));
+        // 7 张牌中有顺子也有同花顺时取同花顺，A-2-3-4-5 的 A 排在最后
+        let (rank, best) = best_five(&parse_hand("AS 2S 3S 4S 5S 6D 7D").unwrap()).unwrap();
+        assert_eq!(rank.category, Category::StraightFlush);
+        assert_eq!(format_hand(&best, true), "5S 4S 3S 2S AS");
+        // 葫芦可以从两个三条中选出最大的组合
+        let (rank, best) = best_five(&parse_hand("8C 8D 8H 6S 6C 6D 2H").unwrap()).unwrap();
+        assert_eq!((rank.category, rank.ranks), (Category::FullHouse, [8, 6, 0, 0, 0]));
+        assert_eq!(best.map(|c| c.rank.value()), [8, 8, 8, 6, 6]);
+        assert!(best_five(&parse_hand("AS KS QS JS").unwrap()).is_none());
+    }
+
+    #[test]
+    fn combination_counts() {
+        assert_eq!(combinations(7, 5).count(), 21);
+        assert_eq!(combinations(5, 5).count(), 1);
+        assert_eq!(combinations(4, 5).count(), 0);
+    }
+
+    // 用 enum_test::PokerCard 表示的牌，1 表示 A
+    #[test]
+    fn poker_cards() {
+        use crate::basic_learning::compound_type::enum_test::PokerSuit;
+        let cards: Vec<PokerCard> = [1, 13, 12, 11, 10]
+            .into_iter()
+            .map(|value| PokerCard { suit: PokerSuit::Spades, value })
+            .collect();
+        assert_eq!(evaluate_poker_cards(&cards).unwrap().unwrap().category, Category::RoyalFlush);
+    }
+
+    // 穷举全部 C(52, 5) = 2,598,960 种组合，与已知的数量对照
+    // 不考虑花色时，5 张牌一共有 7462 种不同的大小
+    #[test]
+    fn five_card_frequencies_match_known_counts() {
+        let (counts, distinct) = five_card_frequencies();
+        for (category, (&count, &want)) in Category::ALL.iter().zip(counts.iter().zip(&KNOWN_FREQUENCIES)) {
+            assert_eq!(count, want, "{}", category.name());
+        }
+        assert_eq!(counts.iter().sum::<u32>(), 2_598_960);
+        assert_eq!(distinct, 7462);
+    }
+}
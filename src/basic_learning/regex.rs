@@ -0,0 +1,909 @@
+// 正则表达式
+/*
+    str::replace / replacen 只能替换固定的字符串，这里实现一个只依赖标准库的小型正则引擎
+    支持的语法:
+        abc          字面量
+        .            除换行外的任意字符
+        [a-z] [^0-9] 字符类
+        \d \w \s     数字、单词字符、空白 (按 Unicode 判断)，大写为取反
+        \p{Han}      汉字，另有 \p{L} 字母、\p{N} 数字，\P{..} 为取反
+        \b           单词边界
+        ^ $          开头、结尾
+        * + ? {n,m}  重复，后面再加 ? 为非贪婪
+        (..) (?:..)  捕获组、非捕获组
+        a|b          选择
+
+    实现方式是 Thompson 构造 + Pike 虚拟机:
+        1. 把正则表达式解析成语法树
+        2. 把语法树编译成一段"指令"，相当于一个 NFA
+        3. 虚拟机同时模拟所有可能的执行路径，时间复杂度为 O(正则长度 × 文本长度)，不会出现回溯引擎的指数爆炸
+ */
+use std::fmt;
+
+use crate::basic_learning::style::dim_dbg;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    // ( 没有对应的 )
+    UnclosedGroup,
+    // 多余的 )
+    UnmatchedParen,
+    // [ 没有对应的 ]
+    UnclosedClass,
+    // 重复符号前面没有内容，比如 *a
+    NothingToRepeat,
+    // 不支持的分组语法，比如 (?=a)
+    UnsupportedGroup,
+    // 不支持的转义，比如 \q
+    InvalidEscape(char),
+    // 字符类中的区间起点大于终点，比如 [z-a]
+    InvalidRange(char, char),
+    // {n,m} 写法有误
+    InvalidRepeat,
+    // 不认识的 \p{..} 名称
+    UnknownProperty(String),
+    // 末尾单独的 \
+    TrailingBackslash,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexError {
+    pub kind: ErrorKind,
+    // 出错位置，按字符计算
+    pub position: usize,
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "正则表达式第 {} 个字符处出错: ", self.position)?;
+        match &self.kind {
+            ErrorKind::UnclosedGroup => write!(f, "( 没有闭合"),
+            ErrorKind::UnmatchedParen => write!(f, "多余的 )"),
+            ErrorKind::UnclosedClass => write!(f, "[ 没有闭合"),
+            ErrorKind::NothingToRepeat => write!(f, "重复符号前面没有可以重复的内容"),
+            ErrorKind::UnsupportedGroup => write!(f, "只支持 (..) 和 (?:..) 两种分组"),
+            ErrorKind::InvalidEscape(c) => write!(f, "不支持的转义 \\{c}"),
+            ErrorKind::InvalidRange(a, b) => write!(f, "无效的区间 {a}-{b}"),
+            ErrorKind::InvalidRepeat => write!(f, "无效的重复次数"),
+            ErrorKind::UnknownProperty(name) => write!(f, "不认识的字符属性 {name}"),
+            ErrorKind::TrailingBackslash => write!(f, "末尾单独的 \\"),
+        }
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+// \d \w \s \p{..} 这类预定义的字符集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Named {
+    Digit,
+    Word,
+    Space,
+    Han,
+    Letter,
+    Number,
+}
+
+impl Named {
+    fn matches(self, c: char) -> bool {
+        match self {
+            Named::Digit => c.is_numeric(),
+            Named::Word => c.is_alphanumeric() || c == '_',
+            Named::Space => c.is_whitespace(),
+            Named::Han => matches!(c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F),
+            Named::Letter => c.is_alphabetic(),
+            Named::Number => c.is_numeric(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClassItem {
+    Range(char, char),
+    Named(Named, bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+impl Class {
+    fn matches(&self, c: char) -> bool {
+        let hit = self.items.iter().any(|item| match *item {
+            ClassItem::Range(lo, hi) => lo <= c && c <= hi,
+            ClassItem::Named(named, negated) => named.matches(c) != negated,
+        });
+        hit != self.negated
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Assertion {
+    Start,
+    End,
+    WordBoundary,
+    NotWordBoundary,
+}
+
+// 语法树
+#[derive(Debug, Clone)]
+enum Ast {
+    Empty,
+    Literal(char),
+    Any,
+    Class(Class),
+    Assert(Assertion),
+    // 第二个字段是捕获组的编号，None 表示非捕获组
+    Group(Box<Ast>, Option<usize>),
+    Repeat { ast: Box<Ast>, min: usize, max: Option<usize>, greedy: bool },
+    Concat(Vec<Ast>),
+    Alternate(Vec<Ast>),
+}
+
+// 递归下降解析器
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn error(&self, kind: ErrorKind) -> RegexError {
+        RegexError { kind, position: self.pos }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    // a|b|c
+    fn parse_alternate(&mut self) -> Result<Ast, RegexError> {
+        let mut branches = vec![self.parse_concat()?];
+        while self.eat('|') {
+            branches.push(self.parse_concat()?);
+        }
+        Ok(if branches.len() == 1 { branches.pop().unwrap() } else { Ast::Alternate(branches) })
+    }
+
+    fn parse_concat(&mut self) -> Result<Ast, RegexError> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            items.push(self.parse_repeat()?);
+        }
+        Ok(match items.len() {
+            0 => Ast::Empty,
+            1 => items.pop().unwrap(),
+            _ => Ast::Concat(items),
+        })
+    }
+
+    fn parse_repeat(&mut self) -> Result<Ast, RegexError> {
+        let mut ast = self.parse_atom()?;
+        loop {
+            let start = self.pos;
+            let (min, max) = match self.peek() {
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some('?') => (0, Some(1)),
+                Some('{') => match self.parse_counted()? {
+                    Some(range) => range,
+                    // 不是合法的 {n,m}，当作普通字符，交给下一轮解析
+                    None => break,
+                },
+                _ => break,
+            };
+            if self.pos == start {
+                self.pos += 1;
+            }
+            // 断言没有宽度，重复它没有意义
+            if let Ast::Assert(_) | Ast::Empty = ast {
+                self.pos = start;
+                return Err(self.error(ErrorKind::NothingToRepeat));
+            }
+            let greedy = !self.eat('?');
+            ast = Ast::Repeat { ast: Box::new(ast), min, max, greedy };
+        }
+        Ok(ast)
+    }
+
+    // {n} {n,} {n,m}，不满足格式时返回 None 且不移动位置
+    fn parse_counted(&mut self) -> Result<Option<(usize, Option<usize>)>, RegexError> {
+        let start = self.pos;
+        let Some(len) = self.chars[start..].iter().position(|&c| c == '}') else {
+            return Ok(None);
+        };
+        let body: String = self.chars[start + 1..start + len].iter().collect();
+        let parse = |s: &str| s.trim().parse::<usize>().ok();
+        let range = match body.split_once(',') {
+            None => parse(&body).map(|n| (n, Some(n))),
+            Some((lo, "")) => parse(lo).map(|n| (n, None)),
+            Some((lo, hi)) => parse(lo).zip(parse(hi)).map(|(lo, hi)| (lo, Some(hi))),
+        };
+        match range {
+            None => Ok(None),
+            Some((min, Some(max))) if min > max || max > 1000 => Err(self.error(ErrorKind::InvalidRepeat)),
+            Some(range) => {
+                self.pos = start + len + 1;
+                Ok(Some(range))
+            }
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Ast, RegexError> {
+        let c = self.peek().expect("parse_concat 保证还有字符");
+        match c {
+            '*' | '+' | '?' => Err(self.error(ErrorKind::NothingToRepeat)),
+            '(' => {
+                let open = self.pos;
+                self.pos += 1;
+                let index = if self.eat('?') {
+                    if !self.eat(':') {
+                        return Err(self.error(ErrorKind::UnsupportedGroup));
+                    }
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups)
+                };
+                let inner = self.parse_alternate()?;
+                if !self.eat(')') {
+                    return Err(RegexError { kind: ErrorKind::UnclosedGroup, position: open });
+                }
+                Ok(Ast::Group(Box::new(inner), index))
+            }
+            '[' => self.parse_class(),
+            _ => {
+                self.pos += 1;
+                Ok(match c {
+                    '.' => Ast::Any,
+                    '^' => Ast::Assert(Assertion::Start),
+                    '$' => Ast::Assert(Assertion::End),
+                    '\\' => self.parse_escape()?,
+                    c => Ast::Literal(c),
+                })
+            }
+        }
+    }
+
+    // 反斜杠之后的部分
+    fn parse_escape(&mut self) -> Result<Ast, RegexError> {
+        let Some(c) = self.next() else {
+            return Err(self.error(ErrorKind::TrailingBackslash));
+        };
+        let class = |named, negated| Ast::Class(Class { negated: false, items: vec![ClassItem::Named(named, negated)] });
+        Ok(match c {
+            'd' | 'D' => class(Named::Digit, c == 'D'),
+            'w' | 'W' => class(Named::Word, c == 'W'),
+            's' | 'S' => class(Named::Space, c == 'S'),
+            'p' | 'P' => class(self.parse_property()?, c == 'P'),
+            'b' => Ast::Assert(Assertion::WordBoundary),
+            'B' => Ast::Assert(Assertion::NotWordBoundary),
+            _ => Ast::Literal(self.escaped_literal(c)?),
+        })
+    }
+
+    // \n \t 以及被转义的标点符号
+    fn escaped_literal(&self, c: char) -> Result<char, RegexError> {
+        match c {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            c if c.is_ascii_punctuation() || c == ' ' => Ok(c),
+            c => Err(RegexError { kind: ErrorKind::InvalidEscape(c), position: self.pos - 1 }),
+        }
+    }
+
+    // \p{Han} 中的 {Han}
+    fn parse_property(&mut self) -> Result<Named, RegexError> {
+        let start = self.pos;
+        if !self.eat('{') {
+            return Err(self.error(ErrorKind::UnknownProperty(String::new())));
+        }
+        let mut name = String::new();
+        loop {
+            match self.next() {
+                Some('}') => break,
+                Some(c) => name.push(c),
+                None => return Err(RegexError { kind: ErrorKind::UnknownProperty(name), position: start }),
+            }
+        }
+        match name.as_str() {
+            "Han" => Ok(Named::Han),
+            "L" | "Letter" => Ok(Named::Letter),
+            "N" | "Number" => Ok(Named::Number),
+            _ => Err(RegexError { kind: ErrorKind::UnknownProperty(name), position: start }),
+        }
+    }
+
+    // [abc] [^a-z] [\d_]
+    fn parse_class(&mut self) -> Result<Ast, RegexError> {
+        let open = self.pos;
+        self.pos += 1;
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        // 紧跟在 [ 或 [^ 后面的 ] 是普通字符
+        let mut first = true;
+        loop {
+            let c = self.next().ok_or(RegexError { kind: ErrorKind::UnclosedClass, position: open })?;
+            let lo = match c {
+                ']' if !first => break,
+                '\\' => {
+                    let e = self.next().ok_or(self.error(ErrorKind::TrailingBackslash))?;
+                    match e {
+                        'd' | 'D' => {
+                            items.push(ClassItem::Named(Named::Digit, e == 'D'));
+                            first = false;
+                            continue;
+                        }
+                        'w' | 'W' => {
+                            items.push(ClassItem::Named(Named::Word, e == 'W'));
+                            first = false;
+                            continue;
+                        }
+                        's' | 'S' => {
+                            items.push(ClassItem::Named(Named::Space, e == 'S'));
+                            first = false;
+                            continue;
+                        }
+                        'p' | 'P' => {
+                            items.push(ClassItem::Named(self.parse_property()?, e == 'P'));
+                            first = false;
+                            continue;
+                        }
+                        e => self.escaped_literal(e)?,
+                    }
+                }
+                c => c,
+            };
+            first = false;
+            // a-z，末尾的 - 是普通字符
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') {
+                self.pos += 1;
+                let mut hi = self.next().unwrap();
+                if hi == '\\' {
+                    let e = self.next().ok_or(self.error(ErrorKind::TrailingBackslash))?;
+                    hi = self.escaped_literal(e)?;
+                }
+                if lo > hi {
+                    return Err(RegexError { kind: ErrorKind::InvalidRange(lo, hi), position: self.pos - 1 });
+                }
+                items.push(ClassItem::Range(lo, hi));
+            } else {
+                items.push(ClassItem::Range(lo, lo));
+            }
+        }
+        Ok(Ast::Class(Class { negated, items }))
+    }
+}
+
+// 虚拟机指令
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Class),
+    Assert(Assertion),
+    // 同时尝试两个分支，第一个分支优先
+    Split(usize, usize),
+    Jmp(usize),
+    // 把当前位置记录到第 n 个捕获槽
+    Save(usize),
+    Match,
+}
+
+fn compile(ast: &Ast, prog: &mut Vec<Inst>) {
+    match ast {
+        Ast::Empty => {}
+        Ast::Literal(c) => prog.push(Inst::Char(*c)),
+        Ast::Any => prog.push(Inst::Any),
+        Ast::Class(class) => prog.push(Inst::Class(class.clone())),
+        Ast::Assert(a) => prog.push(Inst::Assert(*a)),
+        Ast::Group(inner, None) => compile(inner, prog),
+        Ast::Group(inner, Some(index)) => {
+            prog.push(Inst::Save(index * 2));
+            compile(inner, prog);
+            prog.push(Inst::Save(index * 2 + 1));
+        }
+        Ast::Concat(items) => items.iter().for_each(|item| compile(item, prog)),
+        Ast::Alternate(branches) => {
+            /*
+                    split L1, next
+                L1: 分支 1
+                    jmp end
+                next: split L2, next2 ...
+             */
+            let mut jumps = Vec::new();
+            for (i, branch) in branches.iter().enumerate() {
+                if i + 1 < branches.len() {
+                    let split = prog.len();
+                    prog.push(Inst::Split(split + 1, 0));
+                    compile(branch, prog);
+                    jumps.push(prog.len());
+                    prog.push(Inst::Jmp(0));
+                    let next = prog.len();
+                    prog[split] = Inst::Split(split + 1, next);
+                } else {
+                    compile(branch, prog);
+                }
+            }
+            let end = prog.len();
+            for j in jumps {
+                prog[j] = Inst::Jmp(end);
+            }
+        }
+        Ast::Repeat { ast, min, max, greedy } => {
+            // 必须出现的 min 次直接展开
+            for _ in 0..*min {
+                compile(ast, prog);
+            }
+            match max {
+                // e*:  L: split body, end; body: e; jmp L; end:
+                None => {
+                    let split = prog.len();
+                    prog.push(Inst::Split(0, 0));
+                    compile(ast, prog);
+                    prog.push(Inst::Jmp(split));
+                    prog[split] = branch(split + 1, prog.len(), *greedy);
+                }
+                // 可选的 max - min 次: split body, end; body: e; ...
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(prog.len());
+                        prog.push(Inst::Split(0, 0));
+                        compile(ast, prog);
+                    }
+                    let end = prog.len();
+                    for split in splits {
+                        prog[split] = branch(split + 1, end, *greedy);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 贪婪时优先继续匹配，非贪婪时优先跳过
+fn branch(body: usize, skip: usize, greedy: bool) -> Inst {
+    if greedy { Inst::Split(body, skip) } else { Inst::Split(skip, body) }
+}
+
+// 一个线程就是 (指令位置, 捕获槽)，同一步中同一条指令只保留优先级最高的线程
+struct Threads {
+    seen: Vec<bool>,
+    list: Vec<(usize, Vec<Option<usize>>)>,
+}
+
+impl Threads {
+    fn new(len: usize) -> Self {
+        Threads { seen: vec![false; len], list: Vec::new() }
+    }
+
+    fn clear(&mut self) {
+        self.seen.iter_mut().for_each(|s| *s = false);
+        self.list.clear();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Regex {
+    pattern: String,
+    prog: Vec<Inst>,
+    // 捕获组个数，包括代表整个匹配的第 0 组
+    groups: usize,
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Regex, RegexError> {
+        let mut parser = Parser { chars: pattern.chars().collect(), pos: 0, groups: 0 };
+        let ast = parser.parse_alternate()?;
+        if parser.pos < parser.chars.len() {
+            // parse_alternate 只会停在多余的 ) 上
+            return Err(parser.error(ErrorKind::UnmatchedParen));
+        }
+        // 整个匹配作为第 0 组
+        let mut prog = vec![Inst::Save(0)];
+        compile(&ast, &mut prog);
+        prog.push(Inst::Save(1));
+        prog.push(Inst::Match);
+        Ok(Regex { pattern: pattern.to_string(), prog, groups: parser.groups + 1 })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    // 捕获组个数，包括第 0 组
+    pub fn captures_len(&self) -> usize {
+        self.groups
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.exec(text, 0).is_some()
+    }
+
+    pub fn find<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+        self.captures(text).map(|caps| caps.get(0).unwrap())
+    }
+
+    pub fn find_iter<'r, 't>(&'r self, text: &'t str) -> impl Iterator<Item = Match<'t>> + 'r
+    where
+        't: 'r,
+    {
+        self.captures_iter(text).map(|caps| caps.get(0).unwrap())
+    }
+
+    pub fn captures<'t>(&self, text: &'t str) -> Option<Captures<'t>> {
+        self.exec(text, 0).map(|slots| Captures { text, slots })
+    }
+
+    pub fn captures_iter<'r, 't>(&'r self, text: &'t str) -> CapturesIter<'r, 't> {
+        CapturesIter { regex: self, text, pos: Some(0) }
+    }
+
+    // 替换所有匹配，rep 中可以用 $0、$1、${10} 引用捕获组，$$ 表示 $ 本身
+    pub fn replace_all(&self, text: &str, rep: &str) -> String {
+        self.replacen(text, 0, rep)
+    }
+
+    // 只替换前 n 个匹配，n 为 0 时替换全部，与 str::replacen 类似
+    pub fn replacen(&self, text: &str, n: usize, rep: &str) -> String {
+        let mut out = String::new();
+        let mut last = 0;
+        let limit = if n == 0 { usize::MAX } else { n };
+        for caps in self.captures_iter(text).take(limit) {
+            let m = caps.get(0).unwrap();
+            out.push_str(&text[last..m.start()]);
+            caps.expand(rep, &mut out);
+            last = m.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    // 从字节位置 start 开始查找最左边的匹配
+    fn exec(&self, text: &str, start: usize) -> Option<Vec<Option<usize>>> {
+        let mut current = Threads::new(self.prog.len());
+        let mut next = Threads::new(self.prog.len());
+        let mut matched = None;
+        let mut pos = start;
+        loop {
+            // 还没找到匹配时，每个位置都开启一个新线程，优先级最低
+            if matched.is_none() {
+                self.add_thread(&mut current, 0, pos, text, vec![None; self.groups * 2]);
+            }
+            // 已经找到匹配且没有更优先的线程时结束；还没找到时要继续尝试后面的位置
+            if current.list.is_empty() && matched.is_some() {
+                break;
+            }
+            let c = text[pos..].chars().next();
+            let next_pos = pos + c.map_or(0, char::len_utf8);
+            for (pc, slots) in std::mem::take(&mut current.list) {
+                let step = match &self.prog[pc] {
+                    Inst::Char(x) => c == Some(*x),
+                    Inst::Any => c.is_some_and(|c| c != '\n'),
+                    Inst::Class(class) => c.is_some_and(|c| class.matches(c)),
+                    Inst::Match => {
+                        // 优先级更低的线程都不需要了
+                        matched = Some(slots);
+                        break;
+                    }
+                    _ => false,
+                };
+                if step {
+                    self.add_thread(&mut next, pc + 1, next_pos, text, slots);
+                }
+            }
+            if c.is_none() {
+                break;
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+            pos = next_pos;
+        }
+        matched
+    }
+
+    // 沿着不消耗字符的指令(跳转、分支、保存、断言)展开线程
+    fn add_thread(&self, threads: &mut Threads, pc: usize, pos: usize, text: &str, mut slots: Vec<Option<usize>>) {
+        if threads.seen[pc] {
+            return;
+        }
+        threads.seen[pc] = true;
+        match &self.prog[pc] {
+            Inst::Jmp(target) => self.add_thread(threads, *target, pos, text, slots),
+            Inst::Split(a, b) => {
+                self.add_thread(threads, *a, pos, text, slots.clone());
+                self.add_thread(threads, *b, pos, text, slots);
+            }
+            Inst::Save(n) => {
+                slots[*n] = Some(pos);
+                self.add_thread(threads, pc + 1, pos, text, slots);
+            }
+            Inst::Assert(a) => {
+                if assert(*a, text, pos) {
+                    self.add_thread(threads, pc + 1, pos, text, slots);
+                }
+            }
+            _ => threads.list.push((pc, slots)),
+        }
+    }
+}
+
+fn assert(a: Assertion, text: &str, pos: usize) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(|c| Named::Word.matches(c));
+    let boundary = || is_word(text[..pos].chars().next_back()) != is_word(text[pos..].chars().next());
+    match a {
+        Assertion::Start => pos == 0,
+        Assertion::End => pos == text.len(),
+        Assertion::WordBoundary => boundary(),
+        Assertion::NotWordBoundary => !boundary(),
+    }
+}
+
+// 一次匹配在原文中的位置，start 和 end 都是字节下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'t> {
+    text: &'t str,
+    start: usize,
+    end: usize,
+}
+
+impl<'t> Match<'t> {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn as_str(&self) -> &'t str {
+        &self.text[self.start..self.end]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Captures<'t> {
+    text: &'t str,
+    // 第 i 组的起止位置存放在 slots[2i] 和 slots[2i + 1]
+    slots: Vec<Option<usize>>,
+}
+
+impl<'t> Captures<'t> {
+    // 没有参与匹配的组返回 None，比如 (a)|b 匹配 b 时的第 1 组
+    pub fn get(&self, i: usize) -> Option<Match<'t>> {
+        match (self.slots.get(i * 2)?, self.slots.get(i * 2 + 1)?) {
+            (Some(start), Some(end)) => Some(Match { text: self.text, start: *start, end: *end }),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    // 把 rep 中的 $n 替换为对应的捕获组后追加到 out
+    pub fn expand(&self, rep: &str, out: &mut String) {
+        let mut chars = rep.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                out.push(c);
+                continue;
+            }
+            let index = match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                    out.push('$');
+                    continue;
+                }
+                Some('{') => {
+                    chars.next();
+                    let digits: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    digits.parse::<usize>().ok()
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    let mut digits = String::new();
+                    while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(d);
+                    }
+                    digits.parse::<usize>().ok()
+                }
+                _ => {
+                    out.push('$');
+                    continue;
+                }
+            };
+            // 不存在的组替换为空字符串
+            if let Some(m) = index.and_then(|i| self.get(i)) {
+                out.push_str(m.as_str());
+            }
+        }
+    }
+}
+
+pub struct CapturesIter<'r, 't> {
+    regex: &'r Regex,
+    text: &'t str,
+    // 下一次查找的起点，None 表示已经结束
+    pos: Option<usize>,
+}
+
+impl<'t> Iterator for CapturesIter<'_, 't> {
+    type Item = Captures<'t>;
+    fn next(&mut self) -> Option<Captures<'t>> {
+        let start = self.pos?;
+        let Some(slots) = self.regex.exec(self.text, start) else {
+            self.pos = None;
+            return None;
+        };
+        let caps = Captures { text: self.text, slots };
+        let m = caps.get(0).unwrap();
+        // 空匹配时向前跳过一个字符，避免原地死循环
+        self.pos = if m.start() < m.end() {
+            Some(m.end())
+        } else {
+            self.text[m.end()..].chars().next().map(|c| m.end() + c.len_utf8())
+        };
+        Some(caps)
+    }
+}
+
+pub fn run() {
+    // find_iter
+    let re = Regex::new("rust").unwrap();
+    let positions: Vec<usize> = re.find_iter("Hello rust! rust! rust! rust!").map(|m| m.start()).collect();
+    dim_dbg!(positions);
+
+    // captures
+    let re = Regex::new("(\\d{4})-(\\d{2})-(\\d{2})").unwrap();
+    let caps = re.captures("发布日期: 2015-05-15").unwrap();
+    dim_dbg!(caps.get(1).map(|m| m.as_str()));
+
+    // 使用 $n 引用捕获组
+    let re = Regex::new("(\\w+)@(\\w+)\\.com").unwrap();
+    let s = re.replace_all("联系 sunface@course.com 或 alice@rust.com", "$2 的 $1 ($$${1})");
+    dim_dbg!(&s);
+
+    // 语法错误
+    for pattern in ["(abc", "[z-a]", "a{3,1}", "\\p{Foo}"] {
+        println!("{pattern:<8} {}", Regex::new(pattern).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (正则, 文本, 第一个匹配)
+    const CASES: &[(&str, &str, Option<&str>)] = &[
+        ("rust", "Hello rust!", Some("rust")),
+        ("r.st", "Hello rest!", Some("rest")),
+        ("colou?r", "color colour", Some("color")),
+        ("ab*c", "ac abc abbbc", Some("ac")),
+        ("ab+c", "ac abc abbbc", Some("abc")),
+        ("a{2,3}", "a aa aaaa", Some("aa")),
+        ("a{2,}?", "aaaa", Some("aa")),
+        ("<.+>", "<a><b>", Some("<a><b>")),
+        ("<.+?>", "<a><b>", Some("<a>")),
+        ("^hello", "say hello", None),
+        ("hello$", "hello world", None),
+        ("^$", "", Some("")),
+        ("[0-9]+", "价格: 128 元", Some("128")),
+        ("[^a-z ]+", "abc DEF ghi", Some("DEF")),
+        ("[-a]+", "x-a-y", Some("-a-")),
+        ("\\d+\\.\\d+", "版本 1.75.0", Some("1.75")),
+        ("\\w+", "  变量_name1 = 2", Some("变量_name1")),
+        ("\\s+", "a \t b", Some(" \t ")),
+        ("\\p{Han}+", "Rust语言圣经", Some("语言圣经")),
+        ("\\P{Han}+", "语言Rust圣经", Some("Rust")),
+        ("\\bcat\\b", "concat cat", Some("cat")),
+        ("cat|dog", "hotdog", Some("dog")),
+        ("(a|ab)(c|bcd)", "abcd", Some("abcd")),
+        ("a|ab", "ab", Some("a")),
+        ("(?:ab)+", "ababab", Some("ababab")),
+        ("(a*)*b", "aaab", Some("aaab")),
+        ("x*", "abc", Some("")),
+        ("测试.", "测试remove方法", Some("测试r")),
+    ];
+
+    #[test]
+    fn find_first_match() {
+        for &(pattern, text, expected) in CASES {
+            let re = Regex::new(pattern).unwrap_or_else(|e| panic!("pattern {pattern:?}: {e}"));
+            assert_eq!(re.find(text).map(|m| m.as_str()), expected, "pattern {pattern:?} on {text:?}");
+            assert_eq!(re.is_match(text), expected.is_some(), "is_match: pattern {pattern:?} on {text:?}");
+        }
+    }
+
+    // 病态正则 (a?){n}a{n} 在回溯引擎上是指数级的，这里依然很快
+    #[test]
+    fn pathological_pattern() {
+        let n = 25;
+        let re = Regex::new(&format!("{}{}", "a?".repeat(n), "a".repeat(n))).unwrap();
+        assert!(re.is_match(&"a".repeat(n)));
+    }
+
+    #[test]
+    fn find_iter() {
+        let re = Regex::new("rust").unwrap();
+        let positions: Vec<usize> = re.find_iter("Hello rust! rust! rust! rust!").map(|m| m.start()).collect();
+        assert_eq!(positions, [6, 12, 18, 24]);
+        let empty: Vec<(usize, usize)> = Regex::new("a*").unwrap().find_iter("baaa").map(|m| (m.start(), m.end())).collect();
+        assert_eq!(empty, [(0, 0), (1, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn captures() {
+        let re = Regex::new("(\\d{4})-(\\d{2})-(\\d{2})").unwrap();
+        let caps = re.captures("发布日期: 2015-05-15").unwrap();
+        assert_eq!(caps.len(), 4);
+        assert_eq!(caps.get(1).map(|m| m.as_str()), Some("2015"));
+        assert_eq!(caps.get(3).map(|m| m.as_str()), Some("15"));
+        let caps = Regex::new("(a)|b").unwrap().captures("b").unwrap();
+        assert!(caps.get(1).is_none());
+    }
+
+    // 与 str::replace 一课中的例子对比
+    #[test]
+    fn replace() {
+        let initial = "Hello rust! rust! rust! rust!";
+        let re = Regex::new("rust").unwrap();
+        assert_eq!(re.replace_all(initial, "RUST"), initial.replace("rust", "RUST"));
+        assert_eq!(re.replacen(initial, 2, "RUST"), initial.replacen("rust", "RUST", 2));
+
+        let re = Regex::new("(\\w+)@(\\w+)\\.com").unwrap();
+        let s = re.replace_all("联系 sunface@course.com 或 alice@rust.com", "$2 的 $1 ($$${1})");
+        assert_eq!(s, "联系 course 的 sunface ($sunface) 或 rust 的 alice ($alice)");
+        let re = Regex::new("(\\d{4})-(\\d{2})-(\\d{2})").unwrap();
+        assert_eq!(re.replace_all("2024-01-31", "$1年$2月$3日"), "2024年01月31日");
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let errors: &[(&str, ErrorKind)] = &[
+            ("(abc", ErrorKind::UnclosedGroup),
+            ("abc)", ErrorKind::UnmatchedParen),
+            ("[abc", ErrorKind::UnclosedClass),
+            ("*a", ErrorKind::NothingToRepeat),
+            ("(?=a)", ErrorKind::UnsupportedGroup),
+            ("\\q", ErrorKind::InvalidEscape('q')),
+            ("[z-a]", ErrorKind::InvalidRange('z', 'a')),
+            ("a{3,1}", ErrorKind::InvalidRepeat),
+            ("\\p{Foo}", ErrorKind::UnknownProperty("Foo".to_string())),
+            ("abc\\", ErrorKind::TrailingBackslash),
+        ];
+        for (pattern, kind) in errors {
+            let err = Regex::new(pattern).unwrap_err();
+            assert_eq!(&err.kind, kind, "pattern {pattern:?}");
+        }
+    }
+}
@@ -0,0 +1,342 @@
+// 终端样式
+/*
+    用 ANSI 转义序列给课程的输出加上颜色和样式:
+        - Style            前景色、背景色用 struct_test::Rgb，再加上粗体、暗淡、斜体、下划线
+        - ColorMode        Never / Ansi256 / TrueColor，同一个样式按不同模式渲染成不同的转义序列
+        - render           按指定模式渲染成字符串，方便检查输出的内容
+        - paint / paint_err   按 stdout / stderr 自动检测的模式渲染
+    满足任一条件时不输出颜色:
+        - 输出不是终端，比如重定向到文件或者通过管道交给 diff
+        - 设置了环境变量 NO_COLOR，见 https://no-color.org
+    COLORTERM 为 truecolor 或 24bit 时使用 24 位真彩色，否则换算成最接近的 256 色
+
+    课程中用到的几个固定样式:
+        - heading / lesson     每节课开头的标题
+        - dim_dbg!             与 dbg! 一样，但整行变暗，不抢正常输出的注意力
+        - install_panic_hook   panic 信息显示为红色
+ */
+use std::fmt::Debug;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use crate::basic_learning::compound_type::struct_test::Rgb;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Never,
+    Ansi256,
+    TrueColor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl ColorMode {
+    // 只根据参数判断，不读取环境，所以各种组合都可以直接验证
+    pub fn detect(is_tty: bool, no_color: Option<&str>, colorterm: Option<&str>) -> ColorMode {
+        // NO_COLOR 为空字符串时视为没有设置
+        if !is_tty || no_color.is_some_and(|v| !v.is_empty()) {
+            return ColorMode::Never;
+        }
+        match colorterm {
+            Some("truecolor") | Some("24bit") => ColorMode::TrueColor,
+            _ => ColorMode::Ansi256,
+        }
+    }
+
+    // 读取环境变量并检查输出是否是终端，结果在第一次调用后缓存
+    pub fn of(stream: Stream) -> ColorMode {
+        static STDOUT: OnceLock<ColorMode> = OnceLock::new();
+        static STDERR: OnceLock<ColorMode> = OnceLock::new();
+        let (cell, is_tty) = match stream {
+            Stream::Stdout => (&STDOUT, std::io::stdout().is_terminal()),
+            Stream::Stderr => (&STDERR, std::io::stderr().is_terminal()),
+        };
+        *cell.get_or_init(|| {
+            let no_color = std::env::var("NO_COLOR").ok();
+            let colorterm = std::env::var("COLORTERM").ok();
+            ColorMode::detect(is_tty, no_color.as_deref(), colorterm.as_deref())
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Style {
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    // const fn 让样式可以定义成常量
+    pub const fn new() -> Style {
+        Style {
+            fg: None,
+            bg: None,
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    pub const fn fg(mut self, color: Rgb) -> Style {
+        self.fg = Some(color);
+        self
+    }
+
+    pub const fn bg(mut self, color: Rgb) -> Style {
+        self.bg = Some(color);
+        self
+    }
+
+    pub const fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    pub const fn dim(mut self) -> Style {
+        self.dim = true;
+        self
+    }
+
+    pub const fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    pub const fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    // SGR 参数，比如 "1;38;5;196"，没有任何样式时为空
+    fn codes(&self, mode: ColorMode) -> String {
+        let mut codes: Vec<String> = Vec::new();
+        for (on, code) in [(self.bold, "1"), (self.dim, "2"), (self.italic, "3"), (self.underline, "4")] {
+            if on {
+                codes.push(code.to_string());
+            }
+        }
+        for (color, layer) in [(self.fg, 38), (self.bg, 48)] {
+            match (color, mode) {
+                (Some(Rgb(r, g, b)), ColorMode::TrueColor) => codes.push(format!("{};2;{};{};{}", layer, r, g, b)),
+                (Some(rgb), ColorMode::Ansi256) => codes.push(format!("{};5;{}", layer, rgb.to_ansi256())),
+                _ => {}
+            }
+        }
+        codes.join(";")
+    }
+
+    // 每一行单独加上开始和重置的转义序列，这样分页器或者按行处理输出时样式不会串到别的行
+    pub fn render(&self, text: &str, mode: ColorMode) -> String {
+        if mode == ColorMode::Never {
+            return text.to_string();
+        }
+        let codes = self.codes(mode);
+        if codes.is_empty() {
+            return text.to_string();
+        }
+        text.split('\n')
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("\x1b[{}m{}\x1b[0m", codes, line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // 用于 println!
+    pub fn paint(&self, text: &str) -> String {
+        self.render(text, ColorMode::of(Stream::Stdout))
+    }
+
+    // 用于 eprintln!
+    pub fn paint_err(&self, text: &str) -> String {
+        self.render(text, ColorMode::of(Stream::Stderr))
+    }
+}
+
+// 去掉字符串中的 CSI 转义序列 (ESC [ ... 结束字节)，用于计算显示宽度或比较输出
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.clone().next() == Some('[') {
+            chars.next();
+            // 参数和中间字节都在 0x20..=0x3f 之间，结束字节在 0x40..=0x7e 之间
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// 课程中使用的样式
+pub const HEADING: Style = Style::new().fg(Rgb(0x46, 0x82, 0xb4)).bold();
+pub const DBG: Style = Style::new().dim();
+pub const PANIC: Style = Style::new().fg(Rgb(0xdc, 0x14, 0x3c)).bold();
+pub const LESS: Style = Style::new().fg(Rgb(0x1e, 0x90, 0xff));
+pub const GREATER: Style = Style::new().fg(Rgb(0xff, 0x8c, 0x00));
+pub const EQUAL: Style = Style::new().fg(Rgb(0x2e, 0x8b, 0x57)).bold();
+
+pub fn heading_text(title: &str, mode: ColorMode) -> String {
+    HEADING.render(&format!("== {} ==", title), mode)
+}
+
+pub fn heading(title: &str) {
+    println!("{}", heading_text(title, ColorMode::of(Stream::Stdout)));
+}
+
+// 先输出标题再运行一节课
+pub fn lesson(title: &str, f: fn()) {
+    heading(title);
+    f();
+}
+
+// dbg! 的输出格式，DBG 样式下整体变暗
+pub fn dbg_text<T: Debug>(file: &str, line: u32, column: u32, expr: &str, value: &T, mode: ColorMode) -> String {
+    DBG.render(&format!("[{}:{}:{}] {} = {:#?}", file, line, column, expr, value), mode)
+}
+
+// 与 dbg! 相同: 输出到 stderr，并返回表达式的值
+macro_rules! dim_dbg {
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                eprintln!(
+                    "{}",
+                    $crate::basic_learning::style::dbg_text(
+                        file!(),
+                        line!(),
+                        column!(),
+                        stringify!($val),
+                        &tmp,
+                        $crate::basic_learning::style::ColorMode::of($crate::basic_learning::style::Stream::Stderr),
+                    )
+                );
+                tmp
+            }
+        }
+    };
+    // 多个表达式时逐个输出，返回元组
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::basic_learning::style::dim_dbg!($val)),+,)
+    };
+}
+
+pub(crate) use dim_dbg;
+
+// 与标准库默认的 panic 信息格式相同，只是加上了颜色
+pub fn panic_text(thread: &str, location: &str, message: &str, mode: ColorMode) -> String {
+    PANIC.render(&format!("thread '{}' panicked at {}:\n{}", thread, location, message), mode)
+}
+
+// 在 main 开头调用，stderr 不输出颜色时保持默认的 panic 处理
+pub fn install_panic_hook() {
+    let mode = ColorMode::of(Stream::Stderr);
+    if mode == ColorMode::Never {
+        return;
+    }
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // 需要 backtrace 时交给默认的处理
+        if std::env::var_os("RUST_BACKTRACE").is_some() {
+            return default_hook(info);
+        }
+        let payload = info.payload();
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            // 不是字符串的 payload 交给默认的处理
+            return default_hook(info);
+        };
+        let location = info.location().map(|l| l.to_string()).unwrap_or_default();
+        let thread = std::thread::current();
+        eprintln!("{}", panic_text(thread.name().unwrap_or("<unnamed>"), &location, &message, mode));
+        eprintln!("{}", DBG.render("note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace", mode));
+    }));
+}
+
+pub fn run() {
+    // 实际输出，重定向到文件或设置 NO_COLOR 时不带颜色
+    println!(
+        "stdout: {:?}, stderr: {:?}",
+        ColorMode::of(Stream::Stdout),
+        ColorMode::of(Stream::Stderr)
+    );
+
+    // 同一个样式在不同模式下的渲染结果
+    let red = Style::new().fg(Rgb(255, 0, 0)).bold();
+    for mode in [ColorMode::Never, ColorMode::Ansi256, ColorMode::TrueColor] {
+        println!("{mode:?}: {:?}", red.render("error", mode));
+    }
+
+    dim_dbg!(vec![1, 2, 3]);
+    println!("{} {} {}", LESS.paint("猜小了!"), GREATER.paint("猜大了!"), EQUAL.paint("猜对了!"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_color_mode() {
+        assert_eq!(ColorMode::detect(true, None, None), ColorMode::Ansi256);
+        assert_eq!(ColorMode::detect(true, None, Some("truecolor")), ColorMode::TrueColor);
+        assert_eq!(ColorMode::detect(true, None, Some("24bit")), ColorMode::TrueColor);
+        // 不是终端时不加颜色
+        assert_eq!(ColorMode::detect(false, None, Some("truecolor")), ColorMode::Never);
+        // NO_COLOR 只要非空就关闭颜色
+        assert_eq!(ColorMode::detect(true, Some("1"), Some("24bit")), ColorMode::Never);
+        assert_eq!(ColorMode::detect(true, Some(""), None), ColorMode::Ansi256);
+    }
+
+    #[test]
+    fn render_in_each_mode() {
+        let red = Style::new().fg(Rgb(255, 0, 0)).bold();
+        assert_eq!(red.render("error", ColorMode::Never), "error");
+        assert_eq!(red.render("error", ColorMode::Ansi256), "\x1b[1;38;5;196merror\x1b[0m");
+        assert_eq!(red.render("error", ColorMode::TrueColor), "\x1b[1;38;2;255;0;0merror\x1b[0m");
+        let label = Style::new().fg(Rgb(0, 0, 0)).bg(Rgb(255, 215, 0)).underline();
+        assert_eq!(label.render("gold", ColorMode::Ansi256), "\x1b[4;38;5;16;48;5;220mgold\x1b[0m");
+        // 没有任何样式时不输出转义序列
+        assert_eq!(Style::new().render("plain", ColorMode::TrueColor), "plain");
+        // 多行文本每行单独处理，空行保持为空
+        assert_eq!(DBG.render("a\n\nb", ColorMode::Ansi256), "\x1b[2ma\x1b[0m\n\n\x1b[2mb\x1b[0m");
+    }
+
+    #[test]
+    fn strip_ansi_restores_plain_text() {
+        assert_eq!(strip_ansi("\x1b[1;38;5;196m猜大了!\x1b[0m"), "猜大了!");
+        assert_eq!(strip_ansi("no escapes"), "no escapes");
+        // 去掉转义序列后与不加颜色的输出相同
+        let value = vec![1, 2];
+        let text = dbg_text("src/main.rs", 1, 5, "x", &value, ColorMode::TrueColor);
+        assert_eq!(strip_ansi(&text), dbg_text("src/main.rs", 1, 5, "x", &value, ColorMode::Never));
+        let panic = panic_text("main", "src/main.rs:3:5", "boom", ColorMode::Ansi256);
+        assert_eq!(strip_ansi(&panic), "thread 'main' panicked at src/main.rs:3:5:\nboom");
+    }
+
+    #[test]
+    fn heading_format() {
+        assert_eq!(heading_text("所有权", ColorMode::Never), "== 所有权 ==");
+        assert_eq!(strip_ansi(&heading_text("所有权", ColorMode::TrueColor)), "== 所有权 ==");
+    }
+}
@@ -0,0 +1,566 @@
+// 字符串模板
+/*
+    format! 的格式字符串必须是编译期的字面量，没法从配置文件或用户输入中读取
+    这里实现一个运行时的模板引擎:
+        - {name}               取值，支持 user.name 这样的嵌套路径
+        - {price:>10.2}        格式说明，与 format! 相同: [[填充]对齐][0][宽度][.精度]
+        - {{ 和 }}             输出 { 和 }
+        - {#if key}..{#else}..{/if}      条件
+        - {#each list}..{/each}          循环，循环体内用 {.} 表示当前元素，{@index} 表示下标
+ */
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::basic_learning::text_layout::{str_width, Align};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    List(Vec<Value>),
+    Map(Context),
+}
+
+impl Value {
+    // 用于 {#if}: false、0、空字符串、空列表都视为假
+    fn is_truthy(&self) -> bool {
+        match self {
+            Value::Str(s) => !s.is_empty(),
+            Value::Int(n) => *n != 0,
+            Value::Float(n) => *n != 0.0,
+            Value::Bool(b) => *b,
+            Value::List(items) => !items.is_empty(),
+            Value::Map(_) => true,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Float(n)
+    }
+}
+
+impl From<Context> for Value {
+    fn from(c: Context) -> Self {
+        Value::Map(c)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Value::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+// 各种整数类型统一转为 i64
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Value {
+            fn from(n: $t) -> Self {
+                Value::Int(n as i64)
+            }
+        })*
+    };
+}
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+// 模板中可以使用的变量
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Context {
+    vars: HashMap<String, Value>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 链式设置变量: Context::new().set("name", "Sunface").set("count", 3)
+    pub fn set(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.vars.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn insert(&mut self, key: &str, value: impl Into<Value>) {
+        self.vars.insert(key.to_string(), value.into());
+    }
+
+    // 按 a.b.c 的路径逐层查找
+    fn lookup(&self, path: &str) -> Option<&Value> {
+        let mut parts = path.split('.');
+        let mut value = self.vars.get(parts.next()?)?;
+        for part in parts {
+            match value {
+                Value::Map(ctx) => value = ctx.vars.get(part)?,
+                _ => return None,
+            }
+        }
+        Some(value)
+    }
+}
+
+// 模板中的位置，行列都从 1 开始，列按字符计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pos {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 行第 {} 列", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    // 上下文中没有这个变量
+    MissingKey(String),
+    // 格式说明无法解析
+    InvalidSpec(String),
+    // { 没有对应的 }
+    UnclosedBrace,
+    // 单独出现的 }，应该写成 }}
+    UnmatchedBrace,
+    // {#if} / {#each} 缺少结束标签
+    UnclosedBlock(String),
+    // 多余的 {/if}、{/each}、{#else}
+    UnexpectedTag(String),
+    // {#each} 的目标不是列表
+    NotAList(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub kind: ErrorKind,
+    pub pos: Pos,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.pos)?;
+        match &self.kind {
+            ErrorKind::MissingKey(key) => write!(f, "找不到变量 {key}"),
+            ErrorKind::InvalidSpec(spec) => write!(f, "无法解析格式说明 {spec:?}"),
+            ErrorKind::UnclosedBrace => write!(f, "{{ 没有闭合，如果要输出 {{ 请写成 {{{{"),
+            ErrorKind::UnmatchedBrace => write!(f, "多余的 }}，如果要输出 }} 请写成 }}}}"),
+            ErrorKind::UnclosedBlock(tag) => write!(f, "{tag} 缺少结束标签"),
+            ErrorKind::UnexpectedTag(tag) => write!(f, "多余的 {tag}"),
+            ErrorKind::NotAList(key) => write!(f, "{key} 不是列表，不能用于 #each"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+// 格式说明 [[填充]对齐][0][宽度][.精度]
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<Align>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl Spec {
+    fn parse(s: &str) -> Option<Spec> {
+        let mut spec = Spec::default();
+        let chars: Vec<char> = s.chars().collect();
+        let as_align = |c: char| match c {
+            '<' => Some(Align::Left),
+            '>' => Some(Align::Right),
+            '^' => Some(Align::Center),
+            _ => None,
+        };
+        let mut i = 0;
+        if chars.len() >= 2 && as_align(chars[1]).is_some() {
+            spec.fill = Some(chars[0]);
+            spec.align = as_align(chars[1]);
+            i = 2;
+        } else if let Some(align) = chars.first().and_then(|&c| as_align(c)) {
+            spec.align = Some(align);
+            i = 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            spec.zero = true;
+            i += 1;
+        }
+        let rest: String = chars[i..].iter().collect();
+        let (width, precision) = match rest.split_once('.') {
+            Some((w, p)) => (w, Some(p.parse().ok()?)),
+            None => (rest.as_str(), None),
+        };
+        if !width.is_empty() {
+            spec.width = width.parse().ok()?;
+        }
+        spec.precision = precision;
+        Some(spec)
+    }
+
+    fn apply(&self, value: &Value) -> String {
+        let numeric = matches!(value, Value::Int(_) | Value::Float(_));
+        let text = match (value, self.precision) {
+            (Value::Float(n), Some(p)) => format!("{n:.p$}"),
+            // 字符串的精度表示最多保留几个字符，整数忽略精度，都与 format! 一致
+            (Value::Str(s), Some(p)) => s.chars().take(p).collect(),
+            _ => display_value(value),
+        };
+        let fill = self.width.saturating_sub(str_width(&text));
+        if fill == 0 {
+            return text;
+        }
+        // 数字补 0 时，0 要放在符号后面
+        if self.zero && numeric && self.align.is_none() {
+            let (sign, digits) = text.split_at(if text.starts_with('-') { 1 } else { 0 });
+            return format!("{sign}{}{digits}", "0".repeat(fill));
+        }
+        // 与 format! 一样，数字默认右对齐，其余默认左对齐
+        let align = self.align.unwrap_or(if numeric { Align::Right } else { Align::Left });
+        let (left, right) = match align {
+            Align::Left => (0, fill),
+            Align::Right => (fill, 0),
+            Align::Center => (fill / 2, fill - fill / 2),
+        };
+        let pad = |n: usize| self.fill.unwrap_or(' ').to_string().repeat(n);
+        format!("{}{text}{}", pad(left), pad(right))
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Str(s) => s.clone(),
+        Value::Int(n) => n.to_string(),
+        Value::Float(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::List(items) => items.iter().map(display_value).collect::<Vec<_>>().join(", "),
+        Value::Map(_) => "{..}".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Var { path: String, spec: Spec, pos: Pos },
+    If { path: String, pos: Pos, then: Vec<Node>, otherwise: Vec<Node> },
+    Each { path: String, pos: Pos, body: Vec<Node> },
+}
+
+// 词法分析的结果，花括号中的内容按类型区分
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Var(String, Spec),
+    If(String),
+    Else,
+    EndIf,
+    Each(String),
+    EndEach,
+}
+
+fn tokenize(src: &str) -> Result<Vec<(Token, Pos)>, TemplateError> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let (mut line, mut column) = (1, 1);
+    let mut chars = src.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let pos = Pos { line, column };
+        column += 1;
+        match c {
+            '\n' => {
+                line += 1;
+                column = 1;
+                text.push(c);
+            }
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                column += 1;
+                text.push(c);
+            }
+            '}' => return Err(TemplateError { kind: ErrorKind::UnmatchedBrace, pos }),
+            '{' => {
+                let mut tag = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('\n') | None => return Err(TemplateError { kind: ErrorKind::UnclosedBrace, pos }),
+                        Some(c) => tag.push(c),
+                    }
+                }
+                column += tag.chars().count() + 1;
+                if !text.is_empty() {
+                    tokens.push((Token::Text(std::mem::take(&mut text)), pos));
+                }
+                tokens.push((parse_tag(tag.trim(), pos)?, pos));
+            }
+            _ => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push((Token::Text(text), Pos { line, column }));
+    }
+    Ok(tokens)
+}
+
+fn parse_tag(tag: &str, pos: Pos) -> Result<Token, TemplateError> {
+    if let Some(path) = tag.strip_prefix("#if ") {
+        return Ok(Token::If(path.trim().to_string()));
+    }
+    if let Some(path) = tag.strip_prefix("#each ") {
+        return Ok(Token::Each(path.trim().to_string()));
+    }
+    match tag {
+        "#else" => return Ok(Token::Else),
+        "/if" => return Ok(Token::EndIf),
+        "/each" => return Ok(Token::EndEach),
+        _ => {}
+    }
+    let (path, spec) = match tag.split_once(':') {
+        Some((path, spec)) => {
+            let parsed = Spec::parse(spec).ok_or_else(|| TemplateError { kind: ErrorKind::InvalidSpec(spec.to_string()), pos })?;
+            (path, parsed)
+        }
+        None => (tag, Spec::default()),
+    };
+    Ok(Token::Var(path.trim().to_string(), spec))
+}
+
+// 块结束时遇到的结束标签及其位置
+type BlockEnd = Option<(Token, Pos)>;
+
+// 把 token 序列组装成语法树
+// open 是正在解析的 {#if} 或 {#each}，遇到结束标签时连同它的位置一起返回给调用方检查是否匹配
+fn parse_block<I>(tokens: &mut I, open: Option<(&str, Pos)>) -> Result<(Vec<Node>, BlockEnd), TemplateError>
+where
+    I: Iterator<Item = (Token, Pos)>,
+{
+    let mut nodes = Vec::new();
+    while let Some((token, pos)) = tokens.next() {
+        match token {
+            Token::Text(s) => nodes.push(Node::Text(s)),
+            Token::Var(path, spec) => nodes.push(Node::Var { path, spec, pos }),
+            Token::If(path) => {
+                let (then, end) = parse_block(tokens, Some(("{#if}", pos)))?;
+                let otherwise = match end {
+                    Some((Token::Else, _)) => match parse_block(tokens, Some(("{#if}", pos)))? {
+                        (nodes, Some((Token::EndIf, _))) => nodes,
+                        (_, end) => return Err(unexpected(end)),
+                    },
+                    Some((Token::EndIf, _)) => Vec::new(),
+                    end => return Err(unexpected(end)),
+                };
+                nodes.push(Node::If { path, pos, then, otherwise });
+            }
+            Token::Each(path) => match parse_block(tokens, Some(("{#each}", pos)))? {
+                (body, Some((Token::EndEach, _))) => nodes.push(Node::Each { path, pos, body }),
+                (_, end) => return Err(unexpected(end)),
+            },
+            end @ (Token::Else | Token::EndIf | Token::EndEach) => {
+                if open.is_none() {
+                    return Err(unexpected(Some((end, pos))));
+                }
+                return Ok((nodes, Some((end, pos))));
+            }
+        }
+    }
+    match open {
+        Some((tag, pos)) => Err(TemplateError { kind: ErrorKind::UnclosedBlock(tag.to_string()), pos }),
+        None => Ok((nodes, None)),
+    }
+}
+
+// 出现在错误位置的结束标签
+fn unexpected(end: BlockEnd) -> TemplateError {
+    let (token, pos) = end.expect("未闭合的块在 parse_block 中已经报错");
+    let tag = match token {
+        Token::Else => "{#else}",
+        Token::EndIf => "{/if}",
+        _ => "{/each}",
+    };
+    TemplateError { kind: ErrorKind::UnexpectedTag(tag.to_string()), pos }
+}
+
+// 解析后的模板，可以用不同的上下文反复渲染
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    pub fn parse(src: &str) -> Result<Template, TemplateError> {
+        let tokens = tokenize(src)?;
+        let (nodes, _) = parse_block(&mut tokens.into_iter(), None)?;
+        Ok(Template { nodes })
+    }
+
+    pub fn render(&self, ctx: &Context) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        render_nodes(&self.nodes, &[ctx], &mut out)?;
+        Ok(out)
+    }
+}
+
+// 解析并渲染
+pub fn render(src: &str, ctx: &Context) -> Result<String, TemplateError> {
+    Template::parse(src)?.render(ctx)
+}
+
+// scopes 是作用域栈，循环体内的变量优先从最内层查找
+fn lookup<'a>(scopes: &[&'a Context], path: &str, pos: Pos) -> Result<&'a Value, TemplateError> {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|ctx| ctx.lookup(path))
+        .ok_or_else(|| TemplateError { kind: ErrorKind::MissingKey(path.to_string()), pos })
+}
+
+fn render_nodes(nodes: &[Node], scopes: &[&Context], out: &mut String) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(s) => out.push_str(s),
+            Node::Var { path, spec, pos } => out.push_str(&spec.apply(lookup(scopes, path, *pos)?)),
+            Node::If { path, pos, then, otherwise } => {
+                // 条件中的变量不存在时视为假，方便写可选字段
+                let truthy = lookup(scopes, path, *pos).is_ok_and(Value::is_truthy);
+                render_nodes(if truthy { then } else { otherwise }, scopes, out)?;
+            }
+            Node::Each { path, pos, body } => {
+                let Value::List(items) = lookup(scopes, path, *pos)? else {
+                    return Err(TemplateError { kind: ErrorKind::NotAList(path.clone()), pos: *pos });
+                };
+                for (index, item) in items.iter().enumerate() {
+                    // 元素是 Map 时，它的字段可以直接使用
+                    let mut scope = match item {
+                        Value::Map(ctx) => ctx.clone(),
+                        _ => Context::new(),
+                    };
+                    scope.insert(".", item.clone());
+                    scope.insert("@index", index);
+                    let mut inner = scopes.to_vec();
+                    inner.push(&scope);
+                    render_nodes(body, &inner, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn run() {
+    let ctx = Context::new().set("name", "Sunface").set("count", 3);
+    println!("{}", render("Hello {name}, 你有 {count} 条消息", &ctx).unwrap());
+
+    // 与 format! 的效果相同，宽度按显示宽度计算，中文也能对齐
+    let ctx = Context::new().set("pi", std::f64::consts::PI).set("word", "语言");
+    println!("{}", render("{pi:.2}|{word:*^8}|{{word}}", &ctx).unwrap());
+
+    // 模板可以来自运行时，比如 trait_test 中 Post 的 summarize
+    let template = Template::parse("文章{post.title}, 作者是{post.author}").unwrap();
+    let post = Context::new().set("title", "Rust语言简介").set("author", "Sunface");
+    println!("{}", template.render(&Context::new().set("post", post)).unwrap());
+
+    // 条件和循环
+    let users = vec![
+        Context::new().set("name", "张三").set("active", true).set("score", 92.5),
+        Context::new().set("name", "Alice").set("active", false).set("score", 78.0),
+    ];
+    let report = "{#each users}{@index}. {name:<6} {score:>5.1}{#if active} 在线{#else} 离线{/if}\n{/each}";
+    print!("{}", render(report, &Context::new().set("users", users)).unwrap());
+
+    // 错误会报告位置
+    let err = render("第一行\n你好 {nmae}", &Context::new().set("name", "x")).unwrap_err();
+    println!("{err}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_variables() {
+        let ctx = Context::new().set("name", "Sunface").set("count", 3);
+        assert_eq!(render("Hello {name}, 你有 {count} 条消息", &ctx).unwrap(), "Hello Sunface, 你有 3 条消息");
+        // 嵌套的上下文用 . 访问
+        let ctx = Context::new().set("post", Context::new().set("title", "Rust语言简介"));
+        assert_eq!(render("文章{post.title}", &ctx).unwrap(), "文章Rust语言简介");
+    }
+
+    #[test]
+    fn format_specs_match_format_macro() {
+        let ctx = Context::new().set("pi", std::f64::consts::PI).set("n", -42).set("word", "rust");
+        assert_eq!(
+            render("{pi:.2}|{n:06}|{word:*^8}|{word:>6}", &ctx).unwrap(),
+            format!("{:.2}|{:06}|{:*^8}|{:>6}", std::f64::consts::PI, -42, "rust", "rust")
+        );
+        // 宽度按显示宽度计算，中文也能对齐
+        assert_eq!(render("[{name:<6}]", &Context::new().set("name", "语言")).unwrap(), "[语言  ]");
+        assert_eq!(render("[{name:>6}]", &Context::new().set("name", "语言")).unwrap(), "[  语言]");
+    }
+
+    #[test]
+    fn escaped_braces() {
+        assert_eq!(render("{{name}} = {name}", &Context::new().set("name", 1)).unwrap(), "{name} = 1");
+        assert_eq!(render("{{}}", &Context::new()).unwrap(), "{}");
+    }
+
+    #[test]
+    fn conditionals_and_loops() {
+        let users = vec![
+            Context::new().set("name", "张三").set("active", true).set("score", 92.5),
+            Context::new().set("name", "Alice").set("active", false).set("score", 78.0),
+        ];
+        let report = "{#each users}{@index}. {name:<6} {score:>5.1}{#if active} 在线{#else} 离线{/if}\n{/each}";
+        let out = render(report, &Context::new().set("users", users)).unwrap();
+        assert_eq!(out, "0. 张三    92.5 在线\n1. Alice   78.0 离线\n");
+
+        // 空列表什么也不输出
+        let ctx = Context::new().set("users", Vec::<Context>::new());
+        assert_eq!(render("[{#each users}x{/each}]", &ctx).unwrap(), "[]");
+    }
+
+    #[test]
+    fn missing_key_reports_position() {
+        let err = render("第一行\n你好 {nmae}", &Context::new().set("name", "x")).unwrap_err();
+        // 列号按字符计算
+        assert_eq!(err.pos, Pos { line: 2, column: 4 });
+        assert_eq!(err.kind, ErrorKind::MissingKey("nmae".to_string()));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(Template::parse("a } b").unwrap_err().kind, ErrorKind::UnmatchedBrace);
+        assert_eq!(Template::parse("a {b").unwrap_err().kind, ErrorKind::UnclosedBrace);
+        assert_eq!(Template::parse("{#if x}没有结束").unwrap_err().kind, ErrorKind::UnclosedBlock("{#if}".to_string()));
+        assert!(matches!(Template::parse("{x:abc}").unwrap_err().kind, ErrorKind::InvalidSpec(_)));
+        let err = Template::parse("{#each xs}{/if}").unwrap_err();
+        assert_eq!((err.kind, err.pos.column), (ErrorKind::UnexpectedTag("{/if}".to_string()), 11));
+    }
+
+    #[test]
+    fn each_over_non_list() {
+        let err = render("{#each name}{/each}", &Context::new().set("name", "x")).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotAList("name".to_string()));
+    }
+}
@@ -0,0 +1,404 @@
+// 终端文本排版
+/*
+    format! 中的 {:<10} 是按字符个数补齐的，而在终端里
+        - 中文、全角符号、大部分 emoji 占 2 列
+        - 组合符号(如声调符号)、零宽字符占 0 列
+    所以中英文混排时用 {:<10} 对不齐，需要按显示宽度计算
+ */
+use std::fmt;
+
+// 宽度为 0 的字符: 组合符号、零宽字符、变体选择符
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036F), // 组合变音符号
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F), // 零宽空格、零宽连接符等
+    (0x2028, 0x202E),
+    (0x2060, 0x2064),
+    (0x20D0, 0x20FF),
+    (0x302A, 0x302D),
+    (0x3099, 0x309A),
+    (0xFE00, 0xFE0F), // 变体选择符
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF), // BOM
+    (0xE0100, 0xE01EF),
+];
+
+// 东亚宽字符(W)和全角字符(F)，宽度为 2
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F), // 韩文字母
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2E80, 0x303E), // CJK 部首、标点
+    (0x3041, 0x33FF), // 日文假名、注音等
+    (0x3400, 0x4DBF), // CJK 扩展 A
+    (0x4E00, 0x9FFF), // CJK 统一汉字
+    (0xA000, 0xA4CF), // 彝文
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3), // 韩文音节
+    (0xF900, 0xFAFF), // CJK 兼容汉字
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F), // CJK 兼容形式
+    (0xFF00, 0xFF60), // 全角 ASCII
+    (0xFFE0, 0xFFE6), // 全角符号
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F251),
+    (0x1F300, 0x1F64F), // emoji
+    (0x1F680, 0x1F6FF),
+    (0x1F900, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD), // CJK 扩展 B 及以后
+    (0x30000, 0x3FFFD),
+];
+
+// 区间表是有序的，可以二分查找
+fn in_table(table: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    table
+        .binary_search_by(|&(lo, hi)| {
+            if hi < c {
+                std::cmp::Ordering::Less
+            } else if lo > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+// 单个字符在终端中占的列数
+pub fn char_width(c: char) -> usize {
+    if c.is_ascii() {
+        // 控制字符不占位置
+        return if c.is_ascii_control() { 0 } else { 1 };
+    }
+    if c.is_control() || in_table(ZERO_WIDTH, c) {
+        0
+    } else if in_table(WIDE, c) {
+        2
+    } else {
+        1
+    }
+}
+
+// 字符串在终端中占的列数
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+// 用空格补齐到 width 列，已经超过 width 时原样返回
+pub fn pad(s: &str, width: usize, align: Align) -> String {
+    let fill = width.saturating_sub(str_width(s));
+    let (left, right) = match align {
+        Align::Left => (0, fill),
+        Align::Right => (fill, 0),
+        // 无法平分时，多出来的一个空格放在右边
+        Align::Center => (fill / 2, fill - fill / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), s, " ".repeat(right))
+}
+
+pub fn pad_left(s: &str, width: usize) -> String {
+    pad(s, width, Align::Right)
+}
+
+pub fn pad_right(s: &str, width: usize) -> String {
+    pad(s, width, Align::Left)
+}
+
+pub fn center(s: &str, width: usize) -> String {
+    pad(s, width, Align::Center)
+}
+
+// 截断到不超过 width 列，被截断时末尾加上省略号 …
+pub fn truncate(s: &str, width: usize) -> String {
+    if str_width(s) <= width {
+        return s.to_string();
+    }
+    // 给省略号留出 1 列
+    let budget = width.saturating_sub(1);
+    let mut used = 0;
+    let mut result = String::new();
+    for c in s.chars() {
+        let w = char_width(c);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        result.push(c);
+    }
+    if width > 0 {
+        result.push('…');
+    }
+    result
+}
+
+// 中日韩文字之间没有空格，每个字符前后都可以换行
+fn is_cjk(c: char) -> bool {
+    char_width(c) == 2
+}
+
+/*
+    按显示宽度自动换行
+        - 英文按单词换行，单词本身超过一行时才在单词中间断开
+        - 中文可以在任意两个字之间换行
+        - 行首行尾的空格会被去掉
+ */
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    assert!(width >= 2, "wrap width must be at least 2 columns");
+    let mut lines = Vec::new();
+    for paragraph in s.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for token in tokens(paragraph) {
+            let token_width = str_width(token);
+            if token.trim().is_empty() {
+                // 空格只在行中间保留
+                if line_width > 0 && line_width + token_width < width {
+                    line.push_str(token);
+                    line_width += token_width;
+                }
+                continue;
+            }
+            if line_width + token_width > width && line_width > 0 {
+                lines.push(line.trim_end().to_string());
+                line.clear();
+                line_width = 0;
+            }
+            if token_width <= width {
+                line.push_str(token);
+                line_width += token_width;
+                continue;
+            }
+            // 单词比一整行还长，只能硬断开
+            for c in token.chars() {
+                let w = char_width(c);
+                if line_width + w > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += w;
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+// 把文本切分成: 连续的空白、单个中文字符、连续的其它字符(单词)
+fn tokens(s: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let next = chars.peek().map(|&(_, n)| n);
+        let boundary = match next {
+            None => true,
+            Some(n) => is_cjk(c) || is_cjk(n) || c.is_whitespace() != n.is_whitespace(),
+        };
+        if boundary {
+            result.push(&s[start..end]);
+            start = end;
+        }
+    }
+    result
+}
+
+// 按显示宽度对齐的表格
+pub struct Table {
+    headers: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<S: ToString>(headers: &[S]) -> Self {
+        Table {
+            headers: headers.iter().map(ToString::to_string).collect(),
+            aligns: vec![Align::Left; headers.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    // 设置某一列的对齐方式，数字一般右对齐
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        self.aligns[column] = align;
+        self
+    }
+
+    // 列数不足时补空，多出的列会被忽略
+    pub fn add_row<S: ToString>(&mut self, row: &[S]) -> &mut Self {
+        let mut row: Vec<String> = row.iter().take(self.headers.len()).map(ToString::to_string).collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+        self
+    }
+
+    fn column_widths(&self) -> Vec<usize> {
+        (0..self.headers.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|row| str_width(&row[i]))
+                    .chain(std::iter::once(str_width(&self.headers[i])))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+}
+
+/*
+    +------+--------+
+    | 名称 |  数量  |
+    +------+--------+
+    | rust |     10 |
+    +------+--------+
+ */
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.column_widths();
+        let separator: String = widths.iter().map(|w| format!("+{}", "-".repeat(w + 2))).collect::<String>() + "+";
+
+        let write_row = |f: &mut fmt::Formatter<'_>, cells: &[String], aligns: &[Align]| -> fmt::Result {
+            for ((cell, &w), &align) in cells.iter().zip(&widths).zip(aligns) {
+                write!(f, "| {} ", pad(cell, w, align))?;
+            }
+            writeln!(f, "|")
+        };
+
+        writeln!(f, "{separator}")?;
+        // 表头始终居中
+        write_row(f, &self.headers, &vec![Align::Center; self.headers.len()])?;
+        writeln!(f, "{separator}")?;
+        for row in &self.rows {
+            write_row(f, row, &self.aligns)?;
+        }
+        write!(f, "{separator}")
+    }
+}
+
+pub fn run() {
+    // {:<8} 按字符数补齐，中文会多占位置
+    println!("使用 {{:<8}} 补齐:");
+    for name in ["rust", "语言", "圣经rust"] {
+        println!("|{:<8}|", name);
+    }
+    println!("按显示宽度补齐:");
+    for name in ["rust", "语言", "圣经rust"] {
+        println!("|{}|", pad_right(name, 8));
+    }
+
+    println!("截断到 9 列: {}", truncate("Rust语言圣经", 9));
+
+    let text = "Rust 是一门注重安全、速度和并发的系统编程语言, it is blazingly fast and memory-efficient.";
+    for line in wrap(text, 20) {
+        println!("|{}|", pad_right(&line, 20));
+    }
+
+    let mut table = Table::new(&["名称", "类型", "数量"]).align(2, Align::Right);
+    table.add_row(&["rust", "语言", "1"]);
+    table.add_row(&["Rust语言圣经", "书", "1024"]);
+    table.add_row(&["🦀", "吉祥物", "3"]);
+    println!("{table}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_width() {
+        assert_eq!(str_width("hello"), 5);
+        assert_eq!(str_width("你好"), 4);
+        assert_eq!(str_width("ｒｕｓｔ"), 8);
+        assert_eq!(str_width("🦀"), 2);
+        // e + 组合尖音符 = é，只占 1 列
+        assert_eq!(str_width("e\u{301}"), 1);
+        assert_eq!(str_width("a\u{200B}b"), 2);
+        assert_eq!(str_width(""), 0);
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(pad_right("语言", 8), "语言    ");
+        assert_eq!(pad_left("42", 5), "   42");
+        assert_eq!(center("中", 6), "  中  ");
+        // 无法平分时多出的空格在右边
+        assert_eq!(center("ab", 5), " ab  ");
+        // 已经超过宽度时原样返回
+        assert_eq!(pad("Rust语言圣经", 4, Align::Right), "Rust语言圣经");
+    }
+
+    #[test]
+    fn truncation() {
+        assert_eq!(truncate("Rust语言圣经", 12), "Rust语言圣经");
+        assert_eq!(truncate("Rust语言圣经", 9), "Rust语言…");
+        // 不能把一个中文字符截成一半，宁可少占一列
+        assert_eq!(truncate("Rust语言圣经", 8), "Rust语…");
+        assert_eq!(truncate("Rust", 1), "…");
+        assert_eq!(truncate("Rust", 0), "");
+    }
+
+    #[test]
+    fn wrapping() {
+        let text = "Rust 是一门注重安全、速度和并发的系统编程语言, it is blazingly fast and memory-efficient.";
+        let lines = wrap(text, 20);
+        assert!(lines.iter().all(|l| str_width(l) <= 20), "{lines:?}");
+        // 去掉换行处的空格后内容不变
+        let joined: String = lines.concat();
+        assert_eq!(joined.replace(' ', ""), text.replace(' ', ""));
+
+        assert_eq!(wrap("hello world foo", 11), ["hello world", "foo"]);
+        // 中文在任意两个字之间换行
+        assert_eq!(wrap("一二三四五", 4), ["一二", "三四", "五"]);
+        // 超长单词硬断开
+        assert_eq!(wrap("abcdefgh", 3), ["abc", "def", "gh"]);
+        // 保留原有的换行
+        assert_eq!(wrap("a\n\nb", 5), ["a", "", "b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrap width must be at least 2 columns")]
+    fn wrap_width_too_small() {
+        wrap("abc", 1);
+    }
+
+    #[test]
+    fn table_aligns_by_display_width() {
+        let mut table = Table::new(&["名称", "数量"]).align(1, Align::Right);
+        table.add_row(&["rust", "10"]);
+        table.add_row(&["语言"]);
+        let expected = "\
++------+------+
+| 名称 | 数量 |
++------+------+
+| rust |   10 |
+| 语言 |      |
++------+------+";
+        assert_eq!(table.to_string(), expected);
+    }
+}
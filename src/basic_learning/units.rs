@@ -0,0 +1,428 @@
+// 单位
+/*
+    用元组结构体给数字加上单位 (newtype 模式):
+        - Meters, Seconds, Milliseconds, MetersPerSecond 用 f64，Pixels 用 i32
+        - #[repr(transparent)] 保证和里面的数字内存布局相同，运行时没有额外开销
+        - 只实现有物理意义的运算:
+            同单位相加减                   Meters + Meters
+            乘除一个没有单位的数           Meters * 2.0, Meters / 2.0
+            同单位相除得到比例             Meters / Meters -> f64
+            速度                           Meters / Seconds -> MetersPerSecond，MetersPerSecond * Seconds -> Meters
+          Meters + Seconds、Meters * Meters 这样的写法编译不通过
+        - 不同单位之间用 From 显式转换，比如 Seconds::from(Milliseconds(1500.0))
+        - 从 "30px"、"1.5s"、"250ms"、"2km" 这样的字符串解析
+        - Display 带上单位，并且遵循格式说明: format!("{:.1}", Seconds(1.25)) == "1.2s"
+ */
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    // 数字部分无法解析，或者超出了范围
+    InvalidNumber(String),
+    // 没有单位，或者单位不对
+    UnknownUnit { input: String, expected: &'static str },
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::InvalidNumber(s) => write!(f, "无效的数值: {}", s),
+            UnitError::UnknownUnit { input, expected } => write!(f, "{} 的单位不对，需要 {}", input, expected),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+// 单位里面的数字类型，解析时先按 f64 计算，再转换回来
+pub trait Scalar: Copy {
+    fn from_f64(n: f64) -> Option<Self>;
+    fn to_f64(self) -> f64;
+}
+
+impl Scalar for f64 {
+    fn from_f64(n: f64) -> Option<Self> {
+        n.is_finite().then_some(n)
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+// 像素只能是整数，"1.5px" 无法解析
+impl Scalar for i32 {
+    fn from_f64(n: f64) -> Option<Self> {
+        (n.fract() == 0.0 && n >= i32::MIN as f64 && n <= i32::MAX as f64).then_some(n as i32)
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+// 按后缀解析，后缀按长度从长到短尝试，避免 "5mm" 被当成 "5m" 加上一个 "m"
+fn parse_with_units<T: Scalar>(s: &str, units: &[(&str, f64)], expected: &'static str) -> Result<T, UnitError> {
+    let s = s.trim();
+    let mut units = units.to_vec();
+    units.sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+    let (number, factor) = units
+        .iter()
+        .find_map(|(suffix, factor)| s.strip_suffix(suffix).map(|n| (n.trim_end(), *factor)))
+        .ok_or_else(|| UnitError::UnknownUnit { input: s.to_string(), expected })?;
+    let n: f64 = number.parse().map_err(|_| UnitError::InvalidNumber(s.to_string()))?;
+    T::from_f64(n * factor).ok_or_else(|| UnitError::InvalidNumber(s.to_string()))
+}
+
+// 为一个单位生成同单位的运算、Display 和 FromStr
+// 第一个后缀用于 Display，其余的后缀在解析时按倍数换算
+macro_rules! quantity {
+    ($name:ident($t:ty), $suffix:literal $(, $other:literal => $factor:expr)*) => {
+        #[repr(transparent)]
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+        pub struct $name(pub $t);
+
+        impl Add for $name {
+            type Output = $name;
+            fn add(self, rhs: $name) -> $name {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+            fn sub(self, rhs: $name) -> $name {
+                $name(self.0 - rhs.0)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: $name) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: $name) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        // 乘除一个没有单位的数，单位不变
+        // 与内部的数字类型规则相同: Pixels 除以 0 会 panic，溢出在 debug 模式下也会 panic，
+        // 输入不可信时用 Pixels 的 checked_* 方法
+        impl Mul<$t> for $name {
+            type Output = $name;
+            fn mul(self, rhs: $t) -> $name {
+                $name(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$name> for $t {
+            type Output = $name;
+            fn mul(self, rhs: $name) -> $name {
+                $name(self * rhs.0)
+            }
+        }
+
+        impl Div<$t> for $name {
+            type Output = $name;
+            fn div(self, rhs: $t) -> $name {
+                $name(self.0 / rhs)
+            }
+        }
+
+        // 同单位相除，单位抵消
+        impl Div for $name {
+            type Output = f64;
+            fn div(self, rhs: $name) -> f64 {
+                self.0.to_f64() / rhs.0.to_f64()
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> $name {
+                iter.fold($name::default(), |a, b| a + b)
+            }
+        }
+
+        // 数字部分交给内部类型格式化，所以宽度、精度都能用
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                f.write_str($suffix)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnitError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let units = [($suffix, 1.0) $(, ($other, $factor))*];
+                let expected = concat!("带单位的数字，比如 1", $suffix);
+                parse_with_units(s, &units, expected).map($name)
+            }
+        }
+    };
+}
+
+quantity!(Meters(f64), "m", "km" => 1000.0, "cm" => 0.01, "mm" => 0.001);
+quantity!(Seconds(f64), "s", "ms" => 0.001, "min" => 60.0, "h" => 3600.0);
+quantity!(Milliseconds(f64), "ms", "s" => 1000.0);
+quantity!(MetersPerSecond(f64), "m/s", "km/h" => 1.0 / 3.6);
+quantity!(Pixels(i32), "px");
+
+impl From<Milliseconds> for Seconds {
+    fn from(ms: Milliseconds) -> Seconds {
+        Seconds(ms.0 / 1000.0)
+    }
+}
+
+impl From<Seconds> for Milliseconds {
+    fn from(s: Seconds) -> Milliseconds {
+        Milliseconds(s.0 * 1000.0)
+    }
+}
+
+// 与标准库的 Duration 互相转换，Duration 不能为负
+impl From<Duration> for Seconds {
+    fn from(d: Duration) -> Seconds {
+        Seconds(d.as_secs_f64())
+    }
+}
+
+impl TryFrom<Seconds> for Duration {
+    type Error = UnitError;
+
+    fn try_from(s: Seconds) -> Result<Duration, UnitError> {
+        Duration::try_from_secs_f64(s.0).map_err(|_| UnitError::InvalidNumber(s.to_string()))
+    }
+}
+
+// 速度 = 距离 / 时间，距离 = 速度 * 时间，时间 = 距离 / 速度
+impl Div<Seconds> for Meters {
+    type Output = MetersPerSecond;
+    fn div(self, rhs: Seconds) -> MetersPerSecond {
+        MetersPerSecond(self.0 / rhs.0)
+    }
+}
+
+impl Mul<Seconds> for MetersPerSecond {
+    type Output = Meters;
+    fn mul(self, rhs: Seconds) -> Meters {
+        Meters(self.0 * rhs.0)
+    }
+}
+
+impl Mul<MetersPerSecond> for Seconds {
+    type Output = Meters;
+    fn mul(self, rhs: MetersPerSecond) -> Meters {
+        rhs * self
+    }
+}
+
+impl Div<MetersPerSecond> for Meters {
+    type Output = Seconds;
+    fn div(self, rhs: MetersPerSecond) -> Seconds {
+        Seconds(self.0 / rhs.0)
+    }
+}
+
+// 像素和长度之间的换算依赖屏幕的 DPI (每英寸的像素数)，所以不实现 From
+const METERS_PER_INCH: f64 = 0.0254;
+
+impl Pixels {
+    // 溢出或除以 0 时返回 None，不会 panic
+    pub fn checked_add(self, rhs: Pixels) -> Option<Pixels> {
+        self.0.checked_add(rhs.0).map(Pixels)
+    }
+
+    pub fn checked_sub(self, rhs: Pixels) -> Option<Pixels> {
+        self.0.checked_sub(rhs.0).map(Pixels)
+    }
+
+    pub fn checked_mul(self, rhs: i32) -> Option<Pixels> {
+        self.0.checked_mul(rhs).map(Pixels)
+    }
+
+    pub fn checked_div(self, rhs: i32) -> Option<Pixels> {
+        self.0.checked_div(rhs).map(Pixels)
+    }
+
+    pub fn to_meters(self, dpi: f64) -> Meters {
+        Meters(self.0 as f64 / dpi * METERS_PER_INCH)
+    }
+}
+
+impl Meters {
+    // 四舍五入到整像素
+    pub fn to_pixels(self, dpi: f64) -> Pixels {
+        Pixels((self.0 / METERS_PER_INCH * dpi).round() as i32)
+    }
+}
+
+pub fn run() {
+    // 下面几行都无法通过编译:
+    //     Meters(1.0) + Seconds(1.0)
+    //     Meters(1.0) * Meters(1.0)
+    //     Seconds(1.0) + Milliseconds(500.0)
+
+    // 速度
+    let speed = Meters(100.0) / Seconds(9.58);
+    println!("百米世界纪录的平均速度: {:.2}", speed);
+
+    // 单位换算
+    let wait: Seconds = Seconds(0.5) + Milliseconds(250.0).into();
+    println!("等待 {} = {:?}", wait, Duration::try_from(wait).unwrap());
+    println!("96 DPI 下 1 英寸: {}", Meters(0.0254).to_pixels(96.0));
+
+    // 解析失败时说明原因
+    for s in ["30px", "1.5px", "30"] {
+        match s.parse::<Pixels>() {
+            Ok(v) => println!("{s} -> {v:?}"),
+            Err(e) => println!("{s} -> {e}"),
+        }
+    }
+
+    // 按钮宽度按字符串配置，运算后再输出
+    let widths: Vec<Pixels> = "120px, 80px, 64px".split(',').map(|w| w.parse().unwrap()).collect();
+    let gap = Pixels(8);
+    let row = widths.iter().copied().sum::<Pixels>() + gap * (widths.len() as i32 - 1);
+    println!("按钮 {:?} 排成一行的宽度: {}", widths, row);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 按后缀选择类型解析，再格式化回字符串
+    fn round_trip(s: &str) -> String {
+        match s {
+            _ if s.ends_with("px") => s.parse::<Pixels>().unwrap().to_string(),
+            _ if s.ends_with("ms") => s.parse::<Milliseconds>().unwrap().to_string(),
+            _ if s.ends_with("m/s") => s.parse::<MetersPerSecond>().unwrap().to_string(),
+            _ if s.ends_with('s') => s.parse::<Seconds>().unwrap().to_string(),
+            _ => s.parse::<Meters>().unwrap().to_string(),
+        }
+    }
+
+    #[test]
+    fn no_runtime_overhead() {
+        assert_eq!(size_of::<Meters>(), size_of::<f64>());
+        assert_eq!(size_of::<Pixels>(), size_of::<i32>());
+    }
+
+    #[test]
+    fn same_unit_arithmetic() {
+        let a = Meters(1.5) + Meters(2.0) - Meters(0.5);
+        assert_eq!(a, Meters(3.0));
+        assert_eq!(a * 2.0, Meters(6.0));
+        assert_eq!(2.0 * a, Meters(6.0));
+        assert_eq!(a / 4.0, Meters(0.75));
+        assert_eq!(a / Meters(1.5), 2.0);
+        assert_eq!(-a, Meters(-3.0));
+        let mut total = Pixels(10);
+        total += Pixels(20);
+        total -= Pixels(5);
+        assert_eq!(total, Pixels(25));
+        assert_eq!([Pixels(1), Pixels(2), Pixels(3)].into_iter().sum::<Pixels>(), Pixels(6));
+        assert_eq!(Vec::<Seconds>::new().into_iter().sum::<Seconds>(), Seconds(0.0));
+        assert!(Seconds(1.0) < Seconds(1.5));
+        // 整数除法向 0 取整
+        assert_eq!(Pixels(7) / 2, Pixels(3));
+    }
+
+    #[test]
+    fn speed() {
+        let speed = Meters(100.0) / Seconds(9.58);
+        assert_eq!(format!("{:.2}", speed), "10.44m/s");
+        assert_eq!(MetersPerSecond(2.0) * Seconds(3.0), Meters(6.0));
+        assert_eq!(Seconds(3.0) * MetersPerSecond(2.0), Meters(6.0));
+        assert_eq!(Meters(6.0) / MetersPerSecond(2.0), Seconds(3.0));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Seconds::from(Milliseconds(1500.0)), Seconds(1.5));
+        assert_eq!(Milliseconds::from(Seconds(0.25)), Milliseconds(250.0));
+        let wait: Seconds = Seconds(0.5) + Milliseconds(250.0).into();
+        assert_eq!(wait, Seconds(0.75));
+        assert_eq!(Duration::try_from(wait).unwrap(), Duration::from_millis(750));
+        // Duration 不能为负，也不能是 NaN
+        assert!(Duration::try_from(Seconds(-1.0)).is_err());
+        assert!(Duration::try_from(Seconds(f64::NAN)).is_err());
+        assert_eq!(Seconds::from(Duration::from_millis(1500)), Seconds(1.5));
+        // 96 DPI 下 1 英寸是 96 像素
+        assert_eq!(Pixels(96).to_meters(96.0), Meters(0.0254));
+        assert_eq!(Meters(0.0254).to_pixels(96.0), Pixels(96));
+    }
+
+    #[test]
+    fn parse_suffixes() {
+        assert_eq!("30px".parse::<Pixels>().unwrap(), Pixels(30));
+        assert_eq!("-4px".parse::<Pixels>().unwrap(), Pixels(-4));
+        assert_eq!("1.5s".parse::<Seconds>().unwrap(), Seconds(1.5));
+        assert_eq!("250ms".parse::<Seconds>().unwrap(), Seconds(0.25));
+        assert_eq!("2min".parse::<Seconds>().unwrap(), Seconds(120.0));
+        assert_eq!("1h".parse::<Seconds>().unwrap(), Seconds(3600.0));
+        assert_eq!("1.5s".parse::<Milliseconds>().unwrap(), Milliseconds(1500.0));
+        assert_eq!("5mm".parse::<Meters>().unwrap(), Meters(0.005));
+        assert_eq!(" 2 km ".parse::<Meters>().unwrap(), Meters(2000.0));
+        assert_eq!("36km/h".parse::<MetersPerSecond>().unwrap(), MetersPerSecond(10.0));
+    }
+
+    #[test]
+    fn parse_errors() {
+        // 像素只能是整数，并且不能超出 i32
+        assert!(matches!("1.5px".parse::<Pixels>(), Err(UnitError::InvalidNumber(_))));
+        assert!(matches!("abcpx".parse::<Pixels>(), Err(UnitError::InvalidNumber(_))));
+        assert!(matches!("3000000000px".parse::<Pixels>(), Err(UnitError::InvalidNumber(_))));
+        assert!(matches!("1e400m".parse::<Meters>(), Err(UnitError::InvalidNumber(_))));
+        let err = "30".parse::<Pixels>().unwrap_err();
+        assert_eq!(err.to_string(), "30 的单位不对，需要 带单位的数字，比如 1px");
+        assert!(matches!("3kg".parse::<Meters>(), Err(UnitError::UnknownUnit { .. })));
+    }
+
+    #[test]
+    fn display_follows_format_spec() {
+        assert_eq!(Pixels(30).to_string(), "30px");
+        assert_eq!(Seconds(1.5).to_string(), "1.5s");
+        assert_eq!(format!("{:.1}", Seconds(1.25)), "1.2s");
+        assert_eq!(format!("[{:>5}]", Pixels(7)), "[    7px]");
+    }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        for s in ["30px", "-4px", "1.5s", "250ms", "0.001m", "12.5m/s"] {
+            assert_eq!(round_trip(s), s);
+        }
+    }
+
+    #[test]
+    fn checked_pixel_arithmetic() {
+        assert_eq!(Pixels(6).checked_mul(7), Some(Pixels(42)));
+        assert_eq!(Pixels(i32::MAX).checked_mul(2), None);
+        assert_eq!(Pixels(1).checked_div(0), None);
+        assert_eq!(Pixels(i32::MIN).checked_div(-1), None);
+        assert_eq!(Pixels(i32::MAX).checked_add(Pixels(1)), None);
+        assert_eq!(Pixels(i32::MIN).checked_sub(Pixels(1)), None);
+        assert_eq!(Pixels(10).checked_sub(Pixels(25)), Some(Pixels(-15)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pixels_divided_by_zero_panics() {
+        let zero = std::hint::black_box(0);
+        let _ = Pixels(1) / zero;
+    }
+}
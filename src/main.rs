@@ -20,8 +20,118 @@ mod basic_learning {
     pub mod generic_and_traits;
     // 9.集合类型
     pub mod collections;
+    // 10.迭代器
+    pub mod iterators;
+    // 11.终端文本排版
+    pub mod text_layout;
+    // 12.字符串模板
+    pub mod template;
+    // 13.正则表达式
+    pub mod regex;
+    // 14.文本编码
+    pub mod encoding;
+    // 15.命名风格
+    pub mod naming;
+    // 16.文本比较
+    pub mod diff;
+    // 17.密码哈希
+    pub mod password;
+    // 18.JSON
+    pub mod json;
+    // 19.构建者模式
+    pub mod builder;
+    // 20.颜色
+    pub mod color;
+    // 21.终端样式
+    pub mod style;
+    // 22.单位
+    pub mod units;
+    // 23.扑克牌
+    pub mod cards;
+    // 24.扑克牌型
+    pub mod poker;
+    // 25.二十一点
+    pub mod blackjack;
+}
+
+use basic_learning::*;
+
+// 可以单独运行的课程: (名称, 标题, 入口)
+const LESSONS: &[(&str, &str, fn())] = &[
+    ("guess", "猜数字游戏", guess_number::run),
+    ("utf8-edit", "字符串: 按字符下标编辑", compound_type::str::utf8_edit::run),
+    ("rope", "字符串: Rope", compound_type::str::rope::run),
+    ("rope-bench", "字符串: Rope 与 String 的插入耗时", compound_type::str::rope::bench),
+    ("registry", "结构体: 用户注册表", compound_type::struct_test::user_registry::run),
+    ("enum", "枚举", compound_type::enum_test::run),
+    ("array", "数组", compound_type::arr::run),
+    ("if-else", "流程控制: if else", flow_control::if_else::run),
+    ("if-let", "模式匹配: if let", match_pattern::if_let::run),
+    ("matches", "模式匹配: matches!", match_pattern::matches::run),
+    ("option", "模式匹配: Option", match_pattern::option::run),
+    ("trait-object", "特征对象", generic_and_traits::trait_object_test_1::run),
+    ("self", "self 与 Self", generic_and_traits::self_demo::run),
+    ("sort", "集合类型: 排序", collections::vector::sort),
+    ("my-vec", "集合类型: 自己实现 Vec", collections::my_vec::run),
+    ("ring-buffer", "集合类型: 环形缓冲区", collections::ring_buffer::run),
+    ("plugins", "集合类型: 插件注册表", collections::plugin_registry::run),
+    ("sorted-vec", "集合类型: 有序 Vec", collections::sorted_vec::run),
+    ("sorted-vec-bench", "集合类型: 有序 Vec 的耗时", collections::sorted_vec::bench),
+    ("interner", "集合类型: 字符串驻留", collections::interner::run),
+    ("iterators", "迭代器", iterators::run),
+    ("text-layout", "终端文本排版", text_layout::run),
+    ("template", "字符串模板", template::run),
+    ("regex", "正则表达式", regex::run),
+    ("encoding", "文本编码", encoding::run),
+    ("naming", "命名风格", naming::run),
+    ("diff", "文本比较", diff::run),
+    ("password", "密码哈希", password::run),
+    ("json", "JSON", json::run),
+    ("builder", "构建者模式", builder::run),
+    ("color", "颜色", color::run),
+    ("style", "终端样式", style::run),
+    ("units", "单位", units::run),
+    ("cards", "扑克牌", cards::run),
+    ("poker", "扑克牌型", poker::run),
+    ("blackjack", "二十一点", blackjack::run),
+];
+
+// 输出标题后运行一节课，找不到时列出所有课程
+fn lesson(name: Option<&str>) {
+    if let Some(&(_, title, f)) = LESSONS.iter().find(|(n, ..)| Some(*n) == name) {
+        return style::lesson(title, f);
+    }
+    if let Some(name) = name {
+        eprintln!("没有名为 {name} 的课程，可以运行的课程有:");
+    }
+    for (name, title, _) in LESSONS {
+        println!("{name:<18}{title}");
+    }
+    if name.is_some() {
+        std::process::exit(1);
+    }
 }
 
 fn main() {
-    basic_learning::collections::vector::sort()
+    // panic 信息在终端中显示为红色
+    basic_learning::style::install_panic_hook();
+    // cargo run -- <子命令> [参数]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        // 查看字符串的 UTF-8 编码
+        Some("inspect") => basic_learning::compound_type::str::inspect::cli(&args[1..]),
+        // 转换文件编码
+        Some("convert") => basic_learning::encoding::cli(&args[1..]),
+        // 检查源文件中的命名是否符合约定
+        Some("naming") => basic_learning::naming::cli(&args[1..]),
+        // 比较两个文件，或者比较课程修改前后的输出
+        Some("diff") => basic_learning::diff::cli(&args[1..]),
+        // 注册、登录用户
+        Some("account") => basic_learning::compound_type::struct_test::user_registry::cli(&args[1..]),
+        // 玩二十一点，或者模拟很多局统计期望收益
+        Some("blackjack") => basic_learning::blackjack::cli(&args[1..]),
+        // 运行一节课: cargo run -- lesson <名称>，不写名称时列出所有课程
+        Some("lesson") => lesson(args.get(1).map(String::as_str)),
+        _ => basic_learning::style::lesson("集合类型: 排序", basic_learning::collections::vector::sort),
+    }
 }
\ No newline at end of file